@@ -0,0 +1,320 @@
+// Budget and anomaly alerts for webhook notifications, with a sent-alert state file
+use crate::analysis::anomalies::{Anomaly, AnomalyKind, describe_anomaly};
+use crate::analysis::budget::{BudgetPeriod, BudgetState, BudgetStatus};
+use crate::models::currency::format_currency;
+use crate::output::locale::format_decimal;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Sent alerts are forgotten after this long; longer than any budget period
+const STATE_RETENTION_DAYS: i64 = 62;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertKind {
+    Budget,
+    Anomaly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+/// Something worth notifying about
+///
+/// `key` identifies the alert across runs: a budget alert repeats once per
+/// period and state, an anomaly once per day, session or project-day.
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub key: String,
+    pub kind: AlertKind,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+    pub details: serde_json::Value, // The budget status or anomaly behind the alert
+}
+
+/// Alerts for budgets past their warning threshold or limit
+pub fn budget_alerts(statuses: &[BudgetStatus], decimal_places: u8) -> Vec<Alert> {
+    statuses
+        .iter()
+        .filter(|status| status.status != BudgetState::Ok)
+        .map(|status| {
+            let (severity, state, title) = match status.status {
+                BudgetState::Exceeded => (
+                    Severity::Critical,
+                    "exceeded",
+                    format!("Budget '{}' exceeded", status.name),
+                ),
+                _ => (
+                    Severity::Warning,
+                    "warning",
+                    format!(
+                        "Budget '{}' at {}%",
+                        status.name,
+                        format_decimal(status.percent_used, 0)
+                    ),
+                ),
+            };
+            let mut message = format!(
+                "{} of {} spent this {} ({}%)",
+                format_currency(status.spent, &status.currency, decimal_places),
+                format_currency(status.amount, &status.currency, decimal_places),
+                period_noun(status),
+                format_decimal(status.percent_used, 1)
+            );
+            if status.projected_overrun > 0.0 {
+                message.push_str(&format!(
+                    ", projected {} by period end",
+                    format_currency(status.projected, &status.currency, decimal_places)
+                ));
+            }
+
+            Alert {
+                key: format!(
+                    "budget:{}:{}:{}",
+                    status.name,
+                    status.period_start.to_rfc3339(),
+                    state
+                ),
+                kind: AlertKind::Budget,
+                severity,
+                title,
+                message,
+                details: serde_json::to_value(status).unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+fn period_noun(status: &BudgetStatus) -> &'static str {
+    match status.period {
+        BudgetPeriod::Daily => "day",
+        BudgetPeriod::Weekly => "week",
+        BudgetPeriod::Monthly => "month",
+    }
+}
+
+/// Alerts for cost spikes; amounts are already in `currency`
+pub fn anomaly_alerts(anomalies: &[&Anomaly], currency: &str, decimal_places: u8) -> Vec<Alert> {
+    anomalies
+        .iter()
+        .map(|anomaly| {
+            let kind = match anomaly.kind {
+                AnomalyKind::Day => "day",
+                AnomalyKind::Session => "session",
+                AnomalyKind::Project => "project",
+            };
+            Alert {
+                key: format!(
+                    "anomaly:{kind}:{}:{}:{}",
+                    anomaly.project.as_deref().unwrap_or(""),
+                    anomaly.subject,
+                    anomaly.date
+                ),
+                kind: AlertKind::Anomaly,
+                severity: Severity::Warning,
+                title: "Cost spike detected".to_string(),
+                message: describe_anomaly(anomaly, currency, decimal_places),
+                details: serde_json::to_value(anomaly).unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Keys of alerts already delivered, so repeat runs stay quiet
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NotifyState {
+    sent: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl NotifyState {
+    /// Get path to the notification state file
+    pub fn get_state_path() -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Failed to determine home directory")?;
+        Ok(home.join(".config").join("ccost").join("notify_state.json"))
+    }
+
+    /// Load the state, starting empty if it is missing or unreadable
+    pub fn load() -> Self {
+        Self::get_state_path()
+            .map(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    /// Load the state from `state_path`, starting empty if it is missing or unreadable
+    pub fn load_from(state_path: &Path) -> Self {
+        fs::read_to_string(state_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the state to `state_path`, dropping entries past the retention window
+    pub fn save_to(&mut self, state_path: &Path, now: DateTime<Utc>) -> Result<()> {
+        let cutoff = now - Duration::days(STATE_RETENTION_DAYS);
+        self.sent.retain(|_, sent_at| *sent_at >= cutoff);
+
+        // Ensure parent directory exists
+        if let Some(parent) = state_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create config directory: {}", parent.display())
+            })?;
+        }
+
+        let contents =
+            serde_json::to_string_pretty(self).context("Failed to serialize notify state")?;
+        let temp_path = state_path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&temp_path, contents)
+            .with_context(|| format!("Failed to write state file: {}", temp_path.display()))?;
+        fs::rename(&temp_path, state_path)
+            .with_context(|| format!("Failed to write state file: {}", state_path.display()))?;
+
+        Ok(())
+    }
+
+    pub fn is_sent(&self, alert: &Alert) -> bool {
+        self.sent.contains_key(&alert.key)
+    }
+
+    pub fn mark_sent(&mut self, alert: &Alert, now: DateTime<Utc>) {
+        self.sent.insert(alert.key.clone(), now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    fn budget_status(
+        name: &str,
+        status: BudgetState,
+        percent_used: f64,
+        month: u32,
+    ) -> BudgetStatus {
+        BudgetStatus {
+            name: name.to_string(),
+            project: None,
+            model: None,
+            period: BudgetPeriod::Monthly,
+            period_start: Utc.with_ymd_and_hms(2025, month, 1, 0, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, month + 1, 1, 0, 0, 0).unwrap(),
+            currency: "USD".to_string(),
+            amount: 100.0,
+            spent: percent_used,
+            percent_used,
+            projected: 150.0,
+            projected_overrun: 50.0,
+            status,
+            conversion: None,
+        }
+    }
+
+    fn day_anomaly(project: Option<&str>, subject: &str, date: NaiveDate) -> Anomaly {
+        Anomaly {
+            kind: AnomalyKind::Day,
+            subject: subject.to_string(),
+            project: project.map(str::to_string),
+            date,
+            cost: 40.0,
+            typical_cost: 5.0,
+            score: 9.5,
+            start_time: Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap(),
+            daily_cost_usd: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_alerts_and_state() {
+        let start = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let budget = |name: &str, status: BudgetState, percent_used: f64| {
+            budget_status(name, status, percent_used, 6)
+        };
+        let statuses = vec![
+            budget("ok", BudgetState::Ok, 10.0),
+            budget("team", BudgetState::Warning, 85.0),
+            budget("solo", BudgetState::Exceeded, 120.0),
+        ];
+
+        let alerts = budget_alerts(&statuses, 2);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(
+            alerts[0].key,
+            "budget:team:2025-06-01T00:00:00+00:00:warning"
+        );
+        assert_eq!(alerts[0].title, "Budget 'team' at 85%");
+        assert_eq!(
+            alerts[0].message,
+            "$85.00 of $100.00 spent this month (85.0%), projected $150.00 by period end"
+        );
+        assert_eq!(alerts[1].severity, Severity::Critical);
+
+        let date = NaiveDate::from_ymd_opt(2025, 6, 9).unwrap();
+        let anomaly = day_anomaly(None, &date.to_string(), date);
+        let alerts = anomaly_alerts(&[&anomaly], "EUR", 2);
+        assert_eq!(alerts[0].key, "anomaly:day::2025-06-09:2025-06-09");
+        assert!(alerts[0].message.contains(&format_currency(40.0, "EUR", 2)));
+
+        let mut state = NotifyState::default();
+        assert!(!state.is_sent(&alerts[0]));
+        state.mark_sent(&alerts[0], start);
+        assert!(state.is_sent(&alerts[0]));
+    }
+
+    #[test]
+    fn test_alert_keys_dedup_across_runs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let state_path = temp_dir.path().join("notify_state.json");
+        let now = Utc.with_ymd_and_hms(2025, 6, 20, 12, 0, 0).unwrap();
+
+        // First run sends the warning; the state file remembers it
+        let warning = budget_alerts(&[budget_status("team", BudgetState::Warning, 85.0, 6)], 2);
+        let mut state = NotifyState::default();
+        state.mark_sent(&warning[0], now);
+        state.save_to(&state_path, now).unwrap();
+        let state = NotifyState::load_from(&state_path);
+
+        // A later run in the same period and state is a repeat, even with more spend
+        let repeat = budget_alerts(&[budget_status("team", BudgetState::Warning, 95.0, 6)], 2);
+        assert_eq!(repeat[0].key, warning[0].key);
+        assert!(state.is_sent(&repeat[0]));
+
+        // Crossing the limit or starting a new period alerts again
+        let exceeded = budget_alerts(&[budget_status("team", BudgetState::Exceeded, 120.0, 6)], 2);
+        assert!(!state.is_sent(&exceeded[0]));
+        let next_period = budget_alerts(&[budget_status("team", BudgetState::Warning, 85.0, 7)], 2);
+        assert!(!state.is_sent(&next_period[0]));
+
+        // Anomalies repeat once per subject and date, and projects don't collide
+        let date = NaiveDate::from_ymd_opt(2025, 6, 9).unwrap();
+        let next_day = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+        let anomalies = [
+            day_anomaly(Some("app"), "2025-06-09", date),
+            day_anomaly(Some("app"), "2025-06-09", date),
+            day_anomaly(Some("web"), "2025-06-09", date),
+            day_anomaly(Some("app"), "2025-06-10", next_day),
+        ];
+        let anomaly_refs: Vec<&Anomaly> = anomalies.iter().collect();
+        let keys: Vec<String> = anomaly_alerts(&anomaly_refs, "USD", 2)
+            .into_iter()
+            .map(|alert| alert.key)
+            .collect();
+        assert_eq!(keys[0], keys[1]);
+        assert_ne!(keys[0], keys[2]);
+        assert_ne!(keys[0], keys[3]);
+
+        // Entries past the retention window are dropped, so the alert could fire again
+        let mut state = NotifyState::load_from(&state_path);
+        let later = now + Duration::days(STATE_RETENTION_DAYS + 1);
+        state.save_to(&state_path, later).unwrap();
+        assert!(!NotifyState::load_from(&state_path).is_sent(&warning[0]));
+    }
+}
@@ -0,0 +1,458 @@
+// Spending anomaly detection: days, sessions and projects that cost far more than usual
+use crate::analysis::{TimezoneCalculator, UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
+use crate::output::table::{TableType, apply_list_table_style_with_color};
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tabled::builder::Builder;
+
+/// Robust z-score above which a cost is flagged (Iglewicz and Hoaglin's cut-off)
+pub const DEFAULT_THRESHOLD: f64 = 3.5;
+
+/// Fewer samples than this are not enough history to call anything unusual
+const MIN_HISTORY: usize = 5;
+
+/// Median and spread of a cost history, resistant to the outliers being looked for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RobustBaseline {
+    pub median: f64,
+    pub scale: f64, // Comparable to a standard deviation
+}
+
+impl RobustBaseline {
+    /// Baseline from the median absolute deviation (MAD)
+    ///
+    /// Falls back to the mean absolute deviation when more than half the values
+    /// are identical, and returns `None` for short or perfectly flat histories.
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.len() < MIN_HISTORY {
+            return None;
+        }
+        let center = median(values);
+        let deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+        let mad = median(&deviations);
+
+        let scale = if mad > f64::EPSILON {
+            mad / 0.6745
+        } else {
+            let mean_deviation = deviations.iter().sum::<f64>() / deviations.len() as f64;
+            if mean_deviation <= f64::EPSILON {
+                return None;
+            }
+            mean_deviation * 1.2533
+        };
+
+        Some(Self {
+            median: center,
+            scale,
+        })
+    }
+
+    /// Modified z-score; only spikes above the median score positive
+    pub fn score(&self, value: f64) -> f64 {
+        (value - self.median) / self.scale
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
+
+/// What was compared against its own history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalyKind {
+    Day,     // A day's total against all other days
+    Session, // A session against the project's other sessions
+    Project, // A project's day against that project's other days
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub subject: String, // Date, session ID or project name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    pub date: NaiveDate, // Local date (session start for sessions)
+    pub cost: f64,
+    pub typical_cost: f64, // Median of the history it was compared with
+    pub score: f64,
+    #[serde(skip)]
+    pub start_time: DateTime<Utc>,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnomalyReport {
+    pub threshold: f64,
+    pub anomalies: Vec<Anomaly>, // Highest score first
+}
+
+impl AnomalyReport {
+    /// Anomalies whose period starts inside the filter's date range
+    pub fn within(&self, filter: &UsageFilter) -> Vec<&Anomaly> {
+        self.anomalies
+            .iter()
+            .filter(|a| {
+                filter.since.is_none_or(|since| a.start_time >= since)
+                    && filter.until.is_none_or(|until| a.start_time <= until)
+            })
+            .collect()
+    }
+}
+
+/// Cost accumulated for one day, session or project-day
+#[derive(Debug, Clone)]
+struct CostBucket {
+    cost: f64,
+    start_time: DateTime<Utc>,
+    daily_cost_usd: BTreeMap<NaiveDate, f64>,
+}
+
+impl CostBucket {
+    fn add(&mut self, cost: f64, time: DateTime<Utc>) {
+        self.cost += cost;
+        self.start_time = self.start_time.min(time);
+        *self.daily_cost_usd.entry(time.date_naive()).or_insert(0.0) += cost;
+    }
+}
+
+fn add_to(
+    buckets: &mut HashMap<BucketKey, CostBucket>,
+    key: BucketKey,
+    cost: f64,
+    time: DateTime<Utc>,
+) {
+    buckets
+        .entry(key)
+        .or_insert_with(|| CostBucket {
+            cost: 0.0,
+            start_time: time,
+            daily_cost_usd: BTreeMap::new(),
+        })
+        .add(cost, time);
+}
+
+/// Kind of history plus the project it belongs to (None for all-days history)
+type HistoryKey = (AnomalyKind, Option<String>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum BucketKey {
+    Day(NaiveDate),
+    Session { project: String, session_id: String },
+    ProjectDay { project: String, date: NaiveDate },
+}
+
+/// Score every day, session and project-day against its own history
+///
+/// History is all supplied data matching the project and model filters; the
+/// date range is applied afterwards with [`AnomalyReport::within`].
+pub async fn detect_anomalies(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    timezone_calc: &TimezoneCalculator,
+    threshold: f64,
+) -> Result<AnomalyReport> {
+    let mut buckets: HashMap<BucketKey, CostBucket> = HashMap::new();
+
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let Some(message_time) = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+
+        if let Some(ref filter_project) = filter.project_name
+            && project_name != filter_project
+        {
+            continue;
+        }
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(message) {
+            continue;
+        }
+
+        let cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+
+        let date = timezone_calc.local_date(message_time);
+        add_to(&mut buckets, BucketKey::Day(date), cost, message_time);
+        add_to(
+            &mut buckets,
+            BucketKey::ProjectDay {
+                project: project_name.clone(),
+                date,
+            },
+            cost,
+            message_time,
+        );
+        if let Some(session_id) = &message.session_id {
+            add_to(
+                &mut buckets,
+                BucketKey::Session {
+                    project: project_name.clone(),
+                    session_id: session_id.clone(),
+                },
+                cost,
+                message_time,
+            );
+        }
+    }
+
+    // Histories: all days, each project's sessions, each project's days
+    let mut histories: BTreeMap<HistoryKey, Vec<(BucketKey, CostBucket)>> = BTreeMap::new();
+    for (key, bucket) in buckets {
+        let history = match &key {
+            BucketKey::Day(_) => (AnomalyKind::Day, None),
+            BucketKey::Session { project, .. } => (AnomalyKind::Session, Some(project.clone())),
+            BucketKey::ProjectDay { project, .. } => (AnomalyKind::Project, Some(project.clone())),
+        };
+        histories.entry(history).or_default().push((key, bucket));
+    }
+
+    let mut anomalies = Vec::new();
+    for ((kind, project), entries) in histories {
+        let costs: Vec<f64> = entries.iter().map(|(_, bucket)| bucket.cost).collect();
+        let Some(baseline) = RobustBaseline::from_values(&costs) else {
+            continue;
+        };
+
+        for (key, bucket) in entries {
+            let score = baseline.score(bucket.cost);
+            if score <= threshold {
+                continue;
+            }
+            let (subject, date) = match key {
+                BucketKey::Day(date) => (date.format("%Y-%m-%d").to_string(), date),
+                BucketKey::Session { session_id, .. } => {
+                    (session_id, timezone_calc.local_date(bucket.start_time))
+                }
+                BucketKey::ProjectDay { project, date } => (project, date),
+            };
+            anomalies.push(Anomaly {
+                kind,
+                subject,
+                project: project.clone(),
+                date,
+                cost: bucket.cost,
+                typical_cost: baseline.median,
+                score,
+                start_time: bucket.start_time,
+                daily_cost_usd: bucket.daily_cost_usd,
+            });
+        }
+    }
+
+    anomalies.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.subject.cmp(&b.subject))
+    });
+
+    Ok(AnomalyReport {
+        threshold,
+        anomalies,
+    })
+}
+
+/// One-line description used in verbose output of other commands
+pub fn describe_anomaly(anomaly: &Anomaly, currency: &str, decimal_places: u8) -> String {
+    let what = match anomaly.kind {
+        AnomalyKind::Day => format!("day {}", anomaly.subject),
+        AnomalyKind::Session => format!(
+            "session {} ({})",
+            short_id(&anomaly.subject),
+            anomaly.project.as_deref().unwrap_or("unknown")
+        ),
+        AnomalyKind::Project => format!("project {} on {}", anomaly.subject, anomaly.date),
+    };
+    format!(
+        "{what} cost {} vs typical {} (score {})",
+        format_currency(anomaly.cost, currency, decimal_places),
+        format_currency(anomaly.typical_cost, currency, decimal_places),
+        format_decimal(anomaly.score, 1)
+    )
+}
+
+fn short_id(id: &str) -> String {
+    if id.len() > 12 {
+        format!("{}...", &id[..12])
+    } else {
+        id.to_string()
+    }
+}
+
+impl OutputFormat for AnomalyReport {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.anomalies.is_empty() {
+            return format!(
+                "No anomalies found (threshold {}).",
+                format_decimal(self.threshold, 1)
+            );
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record([
+            "Kind", "Subject", "Project", "Date", "Cost", "Typical", "Score",
+        ]);
+        for anomaly in &self.anomalies {
+            builder.push_record([
+                format!("{:?}", anomaly.kind).to_lowercase(),
+                match anomaly.kind {
+                    AnomalyKind::Session => short_id(&anomaly.subject),
+                    _ => anomaly.subject.clone(),
+                },
+                anomaly.project.clone().unwrap_or_else(|| "-".to_string()),
+                anomaly.date.format("%Y-%m-%d").to_string(),
+                format_currency(anomaly.cost, currency, decimal_places),
+                format_currency(anomaly.typical_cost, currency, decimal_places),
+                format_decimal(anomaly.score, 1),
+            ]);
+        }
+
+        apply_list_table_style_with_color(builder.build(), colored, TableType::Anomalies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::parser::jsonl::Usage;
+
+    fn message(timestamp: &str, session: &str, cost: f64) -> UsageData {
+        UsageData {
+            timestamp: Some(timestamp.to_string()),
+            uuid: None,
+            request_id: None,
+            session_id: Some(session.to_string()),
+            message: None,
+            usage: Some(Usage {
+                input_tokens: Some(100),
+                output_tokens: Some(100),
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            }),
+            cost_usd: Some(cost),
+            cwd: None,
+            original_cwd: None,
+            git_branch: None,
+        }
+    }
+
+    #[test]
+    fn test_robust_baseline_ignores_the_spike() {
+        let baseline = RobustBaseline::from_values(&[1.0, 1.2, 0.8, 1.1, 0.9, 50.0]).unwrap();
+        assert!((baseline.median - 1.05).abs() < 1e-9);
+        assert!(baseline.score(50.0) > DEFAULT_THRESHOLD);
+        assert!(baseline.score(1.2) < DEFAULT_THRESHOLD);
+
+        // Too little history, or nothing to compare against
+        assert!(RobustBaseline::from_values(&[1.0, 50.0]).is_none());
+        assert!(RobustBaseline::from_values(&[2.0; 6]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_detect_anomalies_flags_expensive_day_and_session() {
+        let mut data = Vec::new();
+        for day in 1..=8 {
+            let ts = format!("2025-06-{day:02}T12:00:00Z");
+            data.push((
+                message(&ts, &format!("s{day}"), 1.0 + day as f64 * 0.05),
+                "alpha".to_string(),
+            ));
+        }
+        data.push((
+            message("2025-06-09T12:00:00Z", "s9", 30.0),
+            "alpha".to_string(),
+        ));
+
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing = PricingManager::new();
+        let tz = TimezoneCalculator::new("UTC", 0).unwrap();
+        let filter = UsageFilter {
+            project_name: None,
+            model_name: None,
+            since: None,
+            until: None,
+            branch_name: None,
+        };
+
+        let report = detect_anomalies(
+            &tracker,
+            &data,
+            &mut pricing,
+            &filter,
+            &tz,
+            DEFAULT_THRESHOLD,
+        )
+        .await
+        .unwrap();
+
+        let kinds: Vec<AnomalyKind> = report.anomalies.iter().map(|a| a.kind).collect();
+        assert!(kinds.contains(&AnomalyKind::Day));
+        assert!(kinds.contains(&AnomalyKind::Session));
+        assert!(kinds.contains(&AnomalyKind::Project));
+        assert!(report.anomalies.iter().all(|a| a.cost == 30.0));
+
+        let day = report
+            .anomalies
+            .iter()
+            .find(|a| a.kind == AnomalyKind::Day)
+            .unwrap();
+        assert_eq!(day.subject, "2025-06-09");
+
+        // Outside the requested range nothing is reported
+        let earlier = UsageFilter {
+            until: Some(tz.day_start(NaiveDate::from_ymd_opt(2025, 6, 8).unwrap())),
+            ..filter
+        };
+        assert!(report.within(&earlier).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+// Per-branch cost from the git branch Claude Code records on each message
+use crate::analysis::{TimezoneCalculator, UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::output::OutputFormat;
+use crate::output::table::{TableType, apply_table_style_with_color, format_number};
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use tabled::builder::Builder;
+
+/// Label for messages written outside a git repository or before Claude Code recorded branches
+pub const NO_BRANCH: &str = "(no branch)";
+
+/// Usage of one branch within one project
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BranchUsage {
+    pub project: String,
+    pub branch: String,
+    pub first_used: Option<NaiveDate>, // Local dates of the first and last message
+    pub last_used: Option<NaiveDate>,
+    pub session_count: usize,
+    pub message_count: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost: f64,
+    #[serde(skip)]
+    pub sessions: HashSet<String>,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+}
+
+impl BranchUsage {
+    fn add(&mut self, other: &BranchUsage) {
+        self.first_used = match (self.first_used, other.first_used) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.last_used = self.last_used.max(other.last_used);
+        self.sessions.extend(other.sessions.iter().cloned());
+        self.session_count = self.sessions.len();
+        self.message_count += other.message_count;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost += other.cost;
+        for (date, cost) in &other.daily_cost_usd {
+            *self.daily_cost_usd.entry(*date).or_insert(0.0) += cost;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BranchReport {
+    pub rows: Vec<BranchUsage>, // By project, then largest cost first
+    pub total: BranchUsage,
+}
+
+/// Branch name recorded on a message, if any
+pub fn message_branch(message: &UsageData) -> Option<&str> {
+    message
+        .git_branch
+        .as_deref()
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty())
+}
+
+/// Group usage by project and the `gitBranch` of each message
+pub async fn analyze_branches(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    timezone_calc: &TimezoneCalculator,
+) -> Result<BranchReport> {
+    let mut rows: BTreeMap<(String, String), BranchUsage> = BTreeMap::new();
+
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let message_time = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok());
+
+        // Apply timestamp filtering
+        if let Some(message_time) = message_time
+            && (filter.since.is_some_and(|since| message_time < since)
+                || filter.until.is_some_and(|until| message_time > until))
+        {
+            continue;
+        }
+
+        if let Some(ref filter_project) = filter.project_name
+            && project_name != filter_project
+        {
+            continue;
+        }
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+
+        if !filter.matches_branch(message) {
+            continue;
+        }
+        let branch_name = message_branch(message).unwrap_or(NO_BRANCH);
+
+        let cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+
+        let row = rows
+            .entry((project_name.clone(), branch_name.to_string()))
+            .or_insert_with(|| BranchUsage {
+                project: project_name.clone(),
+                branch: branch_name.to_string(),
+                ..BranchUsage::default()
+            });
+        row.message_count += 1;
+        row.input_tokens += usage.input_tokens.unwrap_or(0);
+        row.output_tokens += usage.output_tokens.unwrap_or(0);
+        row.cache_creation_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
+        row.cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0);
+        row.cost += cost;
+        if let Some(session_id) = &message.session_id {
+            row.sessions.insert(session_id.clone());
+            row.session_count = row.sessions.len();
+        }
+        if let Some(message_time) = message_time {
+            let local_date = timezone_calc.local_date(message_time);
+            row.first_used = Some(row.first_used.map_or(local_date, |d| d.min(local_date)));
+            row.last_used = row.last_used.max(Some(local_date));
+            *row.daily_cost_usd
+                .entry(message_time.date_naive())
+                .or_insert(0.0) += cost;
+        }
+    }
+
+    let mut rows: Vec<BranchUsage> = rows.into_values().collect();
+    rows.sort_by(|a, b| {
+        a.project
+            .cmp(&b.project)
+            .then_with(|| b.cost.total_cmp(&a.cost))
+            .then_with(|| a.branch.cmp(&b.branch))
+    });
+
+    Ok(BranchReport {
+        total: total_row(&rows),
+        rows,
+    })
+}
+
+/// Sum rows into a TOTAL row (also used after currency conversion)
+pub fn total_row(rows: &[BranchUsage]) -> BranchUsage {
+    let mut total = BranchUsage {
+        project: "TOTAL".to_string(),
+        ..BranchUsage::default()
+    };
+    for row in rows {
+        total.add(row);
+    }
+    total
+}
+
+impl OutputFormat for BranchReport {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.rows.is_empty() {
+            return "No usage data found.".to_string();
+        }
+
+        let date = |date: Option<NaiveDate>| {
+            date.map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let mut builder = Builder::default();
+        builder.push_record([
+            "Project",
+            "Branch",
+            "First Used",
+            "Last Used",
+            "Sessions",
+            "Messages",
+            "Input Tokens",
+            "Output Tokens",
+            "Cost",
+        ]);
+        for row in self.rows.iter().chain(std::iter::once(&self.total)) {
+            builder.push_record([
+                row.project.clone(),
+                row.branch.clone(),
+                date(row.first_used),
+                date(row.last_used),
+                format_number(row.session_count as u64),
+                format_number(row.message_count),
+                format_number(row.input_tokens),
+                format_number(row.output_tokens),
+                format_currency(row.cost, currency, decimal_places),
+            ]);
+        }
+
+        apply_table_style_with_color(builder.build(), colored, TableType::Branches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::parser::jsonl::Usage;
+
+    fn message(timestamp: &str, session: &str, branch: Option<&str>, cost: f64) -> UsageData {
+        UsageData {
+            timestamp: Some(timestamp.to_string()),
+            uuid: None,
+            request_id: None,
+            session_id: Some(session.to_string()),
+            message: None,
+            usage: Some(Usage {
+                input_tokens: Some(100),
+                output_tokens: Some(10),
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            }),
+            cost_usd: Some(cost),
+            cwd: None,
+            original_cwd: None,
+            git_branch: branch.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_analyze_branches_groups_within_projects() {
+        let data = vec![
+            (
+                message("2025-06-01T10:00:00Z", "s1", Some("main"), 1.0),
+                "app".to_string(),
+            ),
+            (
+                message("2025-06-02T10:00:00Z", "s1", Some("feature/ABC-1"), 4.0),
+                "app".to_string(),
+            ),
+            (
+                message("2025-06-03T10:00:00Z", "s2", Some("feature/ABC-1"), 2.0),
+                "app".to_string(),
+            ),
+            (
+                message("2025-06-03T11:00:00Z", "s3", Some(""), 0.5),
+                "scratch".to_string(),
+            ),
+        ];
+
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let tz = TimezoneCalculator::new("UTC", 0).unwrap();
+        let filter = UsageFilter::default();
+
+        let report = analyze_branches(&tracker, &data, &mut pricing_manager, &filter, &tz)
+            .await
+            .unwrap();
+        let names: Vec<(&str, &str)> = report
+            .rows
+            .iter()
+            .map(|row| (row.project.as_str(), row.branch.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("app", "feature/ABC-1"),
+                ("app", "main"),
+                ("scratch", NO_BRANCH)
+            ]
+        );
+        let feature = &report.rows[0];
+        assert_eq!(feature.session_count, 2);
+        assert_eq!(feature.message_count, 2);
+        assert_eq!(feature.cost, 6.0);
+        assert_eq!(feature.first_used, NaiveDate::from_ymd_opt(2025, 6, 2));
+        assert_eq!(feature.last_used, NaiveDate::from_ymd_opt(2025, 6, 3));
+        assert_eq!(report.total.session_count, 3);
+        assert_eq!(report.total.cost, 7.5);
+
+        let filter = UsageFilter {
+            branch_name: Some("main".to_string()),
+            ..UsageFilter::default()
+        };
+        let report = analyze_branches(&tracker, &data, &mut pricing_manager, &filter, &tz)
+            .await
+            .unwrap();
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.total.cost, 1.0);
+    }
+}
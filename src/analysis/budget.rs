@@ -0,0 +1,285 @@
+// Budget tracking: spend in the current period against configured limits
+use crate::analysis::TimezoneCalculator;
+use crate::config::BudgetConfig;
+use crate::models::currency::{CurrencyProvenance, format_currency};
+use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
+use crate::output::table::{BudgetRow, TableType, apply_list_table_style_with_color};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::Serialize;
+
+/// Calendar period a budget resets on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub fn from_config_str(period: &str) -> Result<Self> {
+        match period {
+            "daily" => Ok(BudgetPeriod::Daily),
+            "weekly" => Ok(BudgetPeriod::Weekly),
+            "monthly" => Ok(BudgetPeriod::Monthly),
+            _ => anyhow::bail!(
+                "Invalid budget period: {period}. Must be 'daily', 'weekly', or 'monthly'"
+            ),
+        }
+    }
+
+    /// Start and end of the current period in the configured timezone
+    pub fn current_bounds(
+        &self,
+        timezone_calc: &TimezoneCalculator,
+    ) -> (DateTime<Utc>, DateTime<Utc>) {
+        match self {
+            BudgetPeriod::Daily => {
+                let start = timezone_calc.today_start();
+                (start, start + Duration::days(1))
+            }
+            BudgetPeriod::Weekly => {
+                let start = timezone_calc.this_week_start();
+                (start, start + Duration::days(7))
+            }
+            BudgetPeriod::Monthly => {
+                let start = timezone_calc.this_month_start();
+                let end = start
+                    .checked_add_months(Months::new(1))
+                    .unwrap_or(start + Duration::days(31));
+                (start, end)
+            }
+        }
+    }
+}
+
+/// Where spend stands relative to a budget's thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetState {
+    Ok,
+    Warning,  // At or above warn_percent
+    Exceeded, // At or above 100%
+}
+
+impl BudgetState {
+    /// Process exit code: 0 within budget, 2 past the warning threshold, 3 over budget
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BudgetState::Ok => 0,
+            BudgetState::Warning => 2,
+            BudgetState::Exceeded => 3,
+        }
+    }
+}
+
+/// Spend against one budget for its current period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetStatus {
+    pub name: String,
+    pub project: Option<String>,
+    pub model: Option<String>,
+    pub period: BudgetPeriod,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub currency: String,
+    pub amount: f64,
+    pub spent: f64,
+    pub percent_used: f64,
+    pub projected: f64,         // Spend by period end at the current pace
+    pub projected_overrun: f64, // Projected spend beyond the budget, 0 if none
+    pub status: BudgetState,
+    pub conversion: Option<CurrencyProvenance>, // How USD spend became `currency`
+}
+
+/// Evaluate spend (already in the budget's currency) for the period ending at `period_end`
+pub fn evaluate_budget(
+    name: &str,
+    budget: &BudgetConfig,
+    period: BudgetPeriod,
+    (period_start, period_end): (DateTime<Utc>, DateTime<Utc>),
+    spent: f64,
+    now: DateTime<Utc>,
+) -> BudgetStatus {
+    let percent_used = if budget.amount > 0.0 {
+        spent / budget.amount * 100.0
+    } else {
+        0.0
+    };
+
+    // Linear projection; the first hour counts as a full hour so early spikes stay sane
+    let period_seconds = (period_end - period_start).num_seconds().max(1) as f64;
+    let elapsed_seconds = (now - period_start).num_seconds().max(3600) as f64;
+    let projected = if elapsed_seconds >= period_seconds {
+        spent
+    } else {
+        spent * period_seconds / elapsed_seconds
+    };
+
+    let status = if percent_used >= 100.0 {
+        BudgetState::Exceeded
+    } else if percent_used >= budget.warn_percent {
+        BudgetState::Warning
+    } else {
+        BudgetState::Ok
+    };
+
+    BudgetStatus {
+        name: name.to_string(),
+        project: budget.project.clone(),
+        model: budget.model.clone(),
+        period,
+        period_start,
+        period_end,
+        currency: budget.currency.clone(),
+        amount: budget.amount,
+        spent,
+        percent_used,
+        projected,
+        projected_overrun: (projected - budget.amount).max(0.0),
+        status,
+        conversion: None,
+    }
+}
+
+// Wrapper for budget statuses to implement OutputFormat
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatusList(pub Vec<BudgetStatus>);
+
+impl BudgetStatusList {
+    /// Worst state across all budgets
+    pub fn overall_state(&self) -> BudgetState {
+        self.0
+            .iter()
+            .map(|b| b.status)
+            .max()
+            .unwrap_or(BudgetState::Ok)
+    }
+}
+
+impl OutputFormat for BudgetStatusList {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.0)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        _currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.0.is_empty() {
+            return "No budgets configured.".to_string();
+        }
+
+        // Each budget is shown in its own currency
+        let rows: Vec<BudgetRow> = self
+            .0
+            .iter()
+            .map(|b| BudgetRow {
+                budget: b.name.clone(),
+                scope: match (&b.project, &b.model) {
+                    (Some(project), Some(model)) => format!("{project} / {model}"),
+                    (Some(project), None) => project.clone(),
+                    (None, Some(model)) => model.clone(),
+                    (None, None) => "all".to_string(),
+                },
+                period: format!("{:?}", b.period).to_lowercase(),
+                spent: format_currency(b.spent, &b.currency, decimal_places),
+                limit: format_currency(b.amount, &b.currency, decimal_places),
+                used: format!("{}%", format_decimal(b.percent_used, 0)),
+                projected: if b.projected_overrun > 0.0 {
+                    format!(
+                        "{} (+{})",
+                        format_currency(b.projected, &b.currency, decimal_places),
+                        format_currency(b.projected_overrun, &b.currency, decimal_places)
+                    )
+                } else {
+                    format_currency(b.projected, &b.currency, decimal_places)
+                },
+                status: match b.status {
+                    BudgetState::Ok => "ok",
+                    BudgetState::Warning => "WARNING",
+                    BudgetState::Exceeded => "EXCEEDED",
+                }
+                .to_string(),
+            })
+            .collect();
+
+        apply_list_table_style_with_color(tabled::Table::new(rows), colored, TableType::Budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn budget(amount: f64) -> BudgetConfig {
+        BudgetConfig {
+            amount,
+            period: "monthly".to_string(),
+            currency: "USD".to_string(),
+            project: Some("alpha".to_string()),
+            model: None,
+            warn_percent: 80.0,
+        }
+    }
+
+    fn bounds() -> (DateTime<Utc>, DateTime<Utc>) {
+        (
+            Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 7, 1, 0, 0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_evaluate_budget_projects_overrun() {
+        // 10 of 30 days elapsed
+        let now = Utc.with_ymd_and_hms(2025, 6, 11, 0, 0, 0).unwrap();
+        let status = evaluate_budget(
+            "alpha",
+            &budget(100.0),
+            BudgetPeriod::Monthly,
+            bounds(),
+            50.0,
+            now,
+        );
+
+        assert_eq!(status.status, BudgetState::Ok);
+        assert!((status.percent_used - 50.0).abs() < 1e-9);
+        assert!((status.projected - 150.0).abs() < 1e-9);
+        assert!((status.projected_overrun - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_budget_thresholds_and_exit_codes() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 30, 0, 0, 0).unwrap();
+        let warn = evaluate_budget(
+            "a",
+            &budget(100.0),
+            BudgetPeriod::Monthly,
+            bounds(),
+            85.0,
+            now,
+        );
+        let over = evaluate_budget(
+            "b",
+            &budget(100.0),
+            BudgetPeriod::Monthly,
+            bounds(),
+            100.0,
+            now,
+        );
+        assert_eq!(warn.status, BudgetState::Warning);
+        assert_eq!(over.status, BudgetState::Exceeded);
+
+        let list = BudgetStatusList(vec![warn.clone()]);
+        assert_eq!(list.overall_state().exit_code(), 2);
+        let list = BudgetStatusList(vec![warn, over]);
+        assert_eq!(list.overall_state().exit_code(), 3);
+        assert_eq!(BudgetStatusList(vec![]).overall_state().exit_code(), 0);
+    }
+}
@@ -0,0 +1,469 @@
+// Prompt cache efficiency: what cache reads saved and what unused cache writes cost
+use crate::analysis::{UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::{format_currency, format_currency_signed};
+use crate::models::litellm::EnhancedModelPricing;
+use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
+use crate::output::table::{TableType, apply_table_style_with_color, format_number};
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tabled::builder::Builder;
+
+/// What each cache report row aggregates over
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheGroup {
+    Project,
+    Model,
+    Session,
+}
+
+impl CacheGroup {
+    pub fn from_arg(group: &str) -> Result<Self> {
+        match group {
+            "project" => Ok(CacheGroup::Project),
+            "model" => Ok(CacheGroup::Model),
+            "session" => Ok(CacheGroup::Session),
+            _ => {
+                anyhow::bail!("Invalid grouping: {group}. Must be 'project', 'model' or 'session'")
+            }
+        }
+    }
+}
+
+/// Share of prompt tokens served from the cache
+pub fn cache_hit_rate(
+    input_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+) -> f64 {
+    let prompt_tokens = input_tokens + cache_creation_tokens + cache_read_tokens;
+    if prompt_tokens == 0 {
+        0.0
+    } else {
+        cache_read_tokens as f64 / prompt_tokens as f64
+    }
+}
+
+/// Cache activity and its cost impact for one project, model or session
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CacheUsage {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>, // Owning project, for sessions
+    pub message_count: u64,
+    pub input_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub hit_rate: f64,                 // Cache reads / all prompt tokens
+    pub read_write_ratio: Option<f64>, // Cache reads per cache write token
+    pub read_savings_usd: f64,         // Cache reads at the read rate instead of the input rate
+    pub write_premium_usd: f64,        // Cache writes at the creation rate above the input rate
+    pub net_savings_usd: f64,          // read_savings_usd - write_premium_usd
+    pub unread_write_tokens: u64,      // Cache writes never followed by a read in the session
+    pub unread_write_cost_usd: f64,    // What those writes cost at the creation rate
+    #[serde(skip)]
+    pub daily_read_savings_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+    #[serde(skip)]
+    pub daily_write_premium_usd: BTreeMap<NaiveDate, f64>,
+    #[serde(skip)]
+    pub daily_unread_write_usd: BTreeMap<NaiveDate, f64>,
+}
+
+impl CacheUsage {
+    fn add(&mut self, other: &CacheUsage) {
+        self.message_count += other.message_count;
+        self.input_tokens += other.input_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.read_savings_usd += other.read_savings_usd;
+        self.write_premium_usd += other.write_premium_usd;
+        self.unread_write_tokens += other.unread_write_tokens;
+        self.unread_write_cost_usd += other.unread_write_cost_usd;
+        for (target, source) in [
+            (
+                &mut self.daily_read_savings_usd,
+                &other.daily_read_savings_usd,
+            ),
+            (
+                &mut self.daily_write_premium_usd,
+                &other.daily_write_premium_usd,
+            ),
+            (
+                &mut self.daily_unread_write_usd,
+                &other.daily_unread_write_usd,
+            ),
+        ] {
+            for (date, amount) in source {
+                *target.entry(*date).or_insert(0.0) += amount;
+            }
+        }
+    }
+
+    /// Recompute the derived ratios and net savings from the summed fields
+    pub fn finish(&mut self) {
+        self.hit_rate = cache_hit_rate(
+            self.input_tokens,
+            self.cache_creation_tokens,
+            self.cache_read_tokens,
+        );
+        self.read_write_ratio = (self.cache_creation_tokens > 0)
+            .then(|| self.cache_read_tokens as f64 / self.cache_creation_tokens as f64);
+        self.net_savings_usd = self.read_savings_usd - self.write_premium_usd;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheReport {
+    pub group_by: CacheGroup,
+    pub rows: Vec<CacheUsage>, // Largest net savings first
+    pub total: CacheUsage,
+}
+
+/// One message's cache figures, kept in order so unread writes can be found
+struct CacheEvent {
+    time: DateTime<Utc>,
+    group: String,
+    creation_tokens: u64,
+    read_tokens: u64,
+    creation_rate: f64, // USD per token
+}
+
+/// Price cache activity with each model's separate creation and read rates
+///
+/// A cache write counts as unread when no later message of the same session and
+/// model reads from the cache. Messages without a session ID can't be judged and
+/// are never counted as unread.
+pub async fn analyze_cache(
+    usage_tracker: &UsageTracker,
+    data: Vec<(UsageData, String)>,
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    group_by: CacheGroup,
+) -> Result<CacheReport> {
+    let mut pricing_by_model: HashMap<String, EnhancedModelPricing> = HashMap::new();
+    let mut rows: BTreeMap<String, CacheUsage> = BTreeMap::new();
+    let mut sessions: HashMap<(String, String), Vec<CacheEvent>> = HashMap::new();
+
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let message_time = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok());
+
+        // Apply timestamp filtering
+        if let Some(message_time) = message_time
+            && (filter.since.is_some_and(|since| message_time < since)
+                || filter.until.is_some_and(|until| message_time > until))
+        {
+            continue;
+        }
+
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(&message) {
+            continue;
+        }
+
+        let group = match group_by {
+            CacheGroup::Project => project_name.clone(),
+            CacheGroup::Model => model_name.clone(),
+            CacheGroup::Session => match &message.session_id {
+                Some(session_id) => session_id.clone(),
+                None => continue,
+            },
+        };
+
+        let pricing = match pricing_by_model.get(&model_name) {
+            Some(pricing) => pricing.clone(),
+            None => {
+                let pricing = pricing_manager.get_enhanced_pricing(&model_name).await;
+                pricing_by_model.insert(model_name.clone(), pricing.clone());
+                pricing
+            }
+        };
+
+        let input_tokens = usage.input_tokens.unwrap_or(0);
+        let creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0);
+        let read_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        let read_savings = read_tokens as f64
+            * (pricing.input_cost_per_mtok - pricing.cache_read_cost_per_mtok)
+            / 1_000_000.0;
+        let write_premium = creation_tokens as f64
+            * (pricing.cache_write_cost_per_mtok() - pricing.input_cost_per_mtok)
+            / 1_000_000.0;
+
+        let row = rows.entry(group.clone()).or_insert_with(|| CacheUsage {
+            name: group.clone(),
+            project: (group_by == CacheGroup::Session).then(|| project_name.clone()),
+            ..CacheUsage::default()
+        });
+        row.message_count += 1;
+        row.input_tokens += input_tokens;
+        row.cache_creation_tokens += creation_tokens;
+        row.cache_read_tokens += read_tokens;
+        row.read_savings_usd += read_savings;
+        row.write_premium_usd += write_premium;
+
+        let Some(message_time) = message_time else {
+            continue;
+        };
+        let date = message_time.date_naive();
+        *row.daily_read_savings_usd.entry(date).or_insert(0.0) += read_savings;
+        *row.daily_write_premium_usd.entry(date).or_insert(0.0) += write_premium;
+
+        if let Some(session_id) = &message.session_id {
+            sessions
+                .entry((session_id.clone(), model_name))
+                .or_default()
+                .push(CacheEvent {
+                    time: message_time,
+                    group,
+                    creation_tokens,
+                    read_tokens,
+                    creation_rate: pricing.cache_write_cost_per_mtok() / 1_000_000.0,
+                });
+        }
+    }
+
+    // Writes after the last cache read of a session/model were never read back
+    for events in sessions.values_mut() {
+        events.sort_by_key(|event| event.time);
+        let last_read = events.iter().rposition(|event| event.read_tokens > 0);
+        let first_unread = last_read.map_or(0, |index| index + 1);
+        for event in &events[first_unread..] {
+            if event.creation_tokens == 0 {
+                continue;
+            }
+            let Some(row) = rows.get_mut(&event.group) else {
+                continue;
+            };
+            let cost = event.creation_tokens as f64 * event.creation_rate;
+            row.unread_write_tokens += event.creation_tokens;
+            row.unread_write_cost_usd += cost;
+            *row.daily_unread_write_usd
+                .entry(event.time.date_naive())
+                .or_insert(0.0) += cost;
+        }
+    }
+
+    let mut rows: Vec<CacheUsage> = rows
+        .into_values()
+        .filter(|row| row.cache_creation_tokens > 0 || row.cache_read_tokens > 0)
+        .collect();
+    for row in &mut rows {
+        row.finish();
+    }
+    rows.sort_by(|a, b| {
+        b.net_savings_usd
+            .total_cmp(&a.net_savings_usd)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(CacheReport {
+        group_by,
+        total: total_row(&rows),
+        rows,
+    })
+}
+
+/// Sum rows into a TOTAL row (also used after currency conversion)
+pub fn total_row(rows: &[CacheUsage]) -> CacheUsage {
+    let mut total = CacheUsage {
+        name: "TOTAL".to_string(),
+        ..CacheUsage::default()
+    };
+    for row in rows {
+        total.add(row);
+    }
+    total.finish();
+    total
+}
+
+fn short_id(id: &str) -> String {
+    if id.len() > 12 {
+        format!("{}...", &id[..12])
+    } else {
+        id.to_string()
+    }
+}
+
+impl OutputFormat for CacheReport {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.rows.is_empty() {
+            return "No cache activity found.".to_string();
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record([
+            match self.group_by {
+                CacheGroup::Project => "Project",
+                CacheGroup::Model => "Model",
+                CacheGroup::Session => "Session",
+            },
+            "Cache Writes",
+            "Cache Reads",
+            "Read/Write",
+            "Hit Rate",
+            "Read Savings",
+            "Write Premium",
+            "Unread Writes",
+            "Net Savings",
+        ]);
+
+        for row in self.rows.iter().chain(std::iter::once(&self.total)) {
+            let name = match (&row.project, self.group_by) {
+                (Some(project), CacheGroup::Session) => {
+                    format!("{} ({project})", short_id(&row.name))
+                }
+                _ => row.name.clone(),
+            };
+            builder.push_record([
+                name,
+                format_number(row.cache_creation_tokens),
+                format_number(row.cache_read_tokens),
+                row.read_write_ratio
+                    .map(|ratio| format!("{}x", format_decimal(ratio, 1)))
+                    .unwrap_or_else(|| "-".to_string()),
+                format!("{}%", format_decimal(row.hit_rate * 100.0, 1)),
+                format_currency(row.read_savings_usd, currency, decimal_places),
+                format_currency(row.write_premium_usd, currency, decimal_places),
+                format!(
+                    "{} ({})",
+                    format_currency(row.unread_write_cost_usd, currency, decimal_places),
+                    format_number(row.unread_write_tokens)
+                ),
+                format_currency_signed(row.net_savings_usd, currency, decimal_places),
+            ]);
+        }
+
+        apply_table_style_with_color(builder.build(), colored, TableType::Cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::models::litellm::PricingSource;
+    use crate::parser::jsonl::{Message, Usage};
+
+    fn message(timestamp: &str, session: &str, creation: u64, read: u64) -> UsageData {
+        UsageData {
+            timestamp: Some(timestamp.to_string()),
+            uuid: None,
+            request_id: None,
+            session_id: Some(session.to_string()),
+            message: Some(Message {
+                id: None,
+                content: None,
+                role: Some("assistant".to_string()),
+                model: Some("claude-sonnet-4-20250514".to_string()),
+                usage: None,
+            }),
+            usage: Some(Usage {
+                input_tokens: Some(1_000),
+                output_tokens: Some(100),
+                cache_creation_input_tokens: Some(creation),
+                cache_read_input_tokens: Some(read),
+            }),
+            cost_usd: None,
+            cwd: None,
+            original_cwd: None,
+            git_branch: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_analyze_cache_savings_and_unread_writes() {
+        let data = vec![
+            (
+                message("2025-06-01T10:00:00Z", "s1", 1_000_000, 0),
+                "alpha".to_string(),
+            ),
+            (
+                message("2025-06-01T10:01:00Z", "s1", 0, 2_000_000),
+                "alpha".to_string(),
+            ),
+            // Written at the end of the session and never read
+            (
+                message("2025-06-01T10:02:00Z", "s1", 500_000, 0),
+                "alpha".to_string(),
+            ),
+        ];
+
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let pricing = pricing_manager
+            .get_enhanced_pricing("claude-sonnet-4-20250514")
+            .await;
+        let filter = UsageFilter {
+            project_name: None,
+            model_name: None,
+            since: None,
+            until: None,
+            branch_name: None,
+        };
+
+        let report = analyze_cache(
+            &tracker,
+            data,
+            &mut pricing_manager,
+            &filter,
+            CacheGroup::Project,
+        )
+        .await
+        .unwrap();
+
+        let alpha = &report.rows[0];
+        assert_eq!(alpha.cache_creation_tokens, 1_500_000);
+        assert_eq!(alpha.cache_read_tokens, 2_000_000);
+        assert_eq!(alpha.read_write_ratio, Some(2_000_000.0 / 1_500_000.0));
+
+        let expected_savings =
+            2.0 * (pricing.input_cost_per_mtok - pricing.cache_read_cost_per_mtok);
+        assert!((alpha.read_savings_usd - expected_savings).abs() < 1e-9);
+        // Static pricing has one cache rate, so writes are priced at 1.25x input
+        assert_eq!(pricing.source, PricingSource::StaticFallback);
+        assert!(pricing.cache_creation_cost_per_mtok < pricing.input_cost_per_mtok);
+        let write_rate = 1.25 * pricing.input_cost_per_mtok;
+        let expected_premium = 1.5 * (write_rate - pricing.input_cost_per_mtok);
+        assert!((alpha.write_premium_usd - expected_premium).abs() < 1e-9);
+        assert!((alpha.net_savings_usd - (expected_savings - expected_premium)).abs() < 1e-9);
+
+        assert_eq!(alpha.unread_write_tokens, 500_000);
+        assert!((alpha.unread_write_cost_usd - 0.5 * write_rate).abs() < 1e-9);
+        assert!((alpha.hit_rate - 2_000_000.0 / 3_503_000.0).abs() < 1e-9);
+        assert_eq!(report.total.cache_read_tokens, 2_000_000);
+    }
+
+    #[test]
+    fn test_cache_hit_rate() {
+        assert_eq!(cache_hit_rate(0, 0, 0), 0.0);
+        assert!((cache_hit_rate(100, 100, 800) - 0.8).abs() < 1e-9);
+    }
+}
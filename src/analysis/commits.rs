@@ -0,0 +1,722 @@
+// Git commit cost attribution: the turns leading up to a commit are charged to it
+use crate::analysis::branches::message_branch;
+use crate::analysis::{TimezoneCalculator, UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::output::OutputFormat;
+use crate::output::table::{TableType, apply_list_table_style_with_color, format_number};
+use crate::parser::jsonl::UsageData;
+use crate::utils::maybe_hide_project_name;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tabled::builder::Builder;
+
+/// Commit subjects longer than this are cut short in tables
+const SUBJECT_WIDTH: usize = 40;
+
+/// Row label for work after the latest commit
+pub const UNCOMMITTED: &str = "uncommitted";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitGroup {
+    Commit,
+    Author,
+    Branch,
+}
+
+impl CommitGroup {
+    pub fn from_arg(group: &str) -> Result<Self> {
+        match group {
+            "commit" => Ok(CommitGroup::Commit),
+            "author" => Ok(CommitGroup::Author),
+            "branch" => Ok(CommitGroup::Branch),
+            _ => {
+                anyhow::bail!("Invalid grouping: {group}. Must be 'commit', 'author' or 'branch'")
+            }
+        }
+    }
+}
+
+/// A commit as read from `git log`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitCommit {
+    pub hash: String,
+    pub author: String,
+    pub branch: Option<String>, // Branch whose history it was read from
+    pub time: DateTime<Utc>,    // Committer time
+    pub subject: String,
+}
+
+/// Top-level directory of the git repository containing `dir`, if any
+pub fn repository_root(dir: &Path) -> Option<PathBuf> {
+    if !dir.is_dir() {
+        return None;
+    }
+    let root = git_output(dir, &["rev-parse", "--show-toplevel"])?;
+    (!root.is_empty()).then(|| PathBuf::from(root))
+}
+
+/// Run `git -C <repository> <args>`, returning trimmed stdout on success
+fn git_output(repository: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether `branch` names a local branch that still exists
+fn branch_exists(repository: &Path, branch: &str) -> bool {
+    git_output(
+        repository,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{branch}"),
+        ],
+    )
+    .is_some()
+}
+
+/// Commits in the history of `branch` (HEAD when `None`), oldest first
+///
+/// A repository without commits yet (unborn HEAD) has an empty history.
+pub fn read_commits(
+    repository: &Path,
+    branch: Option<&str>,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<GitCommit>> {
+    let (revision, branch) = match branch {
+        Some(branch) => (format!("refs/heads/{branch}"), Some(branch.to_string())),
+        None => (
+            "HEAD".to_string(),
+            git_output(repository, &["symbolic-ref", "--short", "--quiet", "HEAD"])
+                .filter(|name| !name.is_empty()),
+        ),
+    };
+    if git_output(
+        repository,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{revision}^{{commit}}"),
+        ],
+    )
+    .is_none()
+    {
+        return Ok(Vec::new());
+    }
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repository).args([
+        "log",
+        revision.as_str(),
+        "--format=%H%x1f%an%x1f%ct%x1f%s",
+    ]);
+    if let Some(since) = since {
+        command.arg(format!("--since=@{}", since.timestamp()));
+    }
+    command.arg("--");
+    let output = command
+        .output()
+        .context("Failed to run git; is it installed and on PATH?")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git log failed in {}: {}",
+            repository.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let mut commits: Vec<GitCommit> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\x1f');
+            let hash = fields.next()?.to_string();
+            let author = fields.next()?.to_string();
+            let time = DateTime::from_timestamp(fields.next()?.parse().ok()?, 0)?;
+            let subject = fields.next().unwrap_or_default().to_string();
+            Some(GitCommit {
+                hash,
+                author,
+                branch: branch.clone(),
+                time,
+                subject,
+            })
+        })
+        .collect();
+    commits.sort_by_key(|commit| commit.time);
+    Ok(commits)
+}
+
+/// Cost of one commit, author or branch
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CommitCost {
+    pub name: String, // Short hash, author or branch; "uncommitted" for work after the last commit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>, // Full hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>, // Local commit date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub commits: usize,
+    pub message_count: u64,
+    pub cost: f64,
+    #[serde(skip)]
+    pub committed_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+}
+
+impl CommitCost {
+    fn add_message(&mut self, time: DateTime<Utc>, cost: f64) {
+        self.message_count += 1;
+        self.cost += cost;
+        *self.daily_cost_usd.entry(time.date_naive()).or_insert(0.0) += cost;
+    }
+
+    fn add(&mut self, other: &CommitCost) {
+        self.commits += other.commits;
+        self.message_count += other.message_count;
+        self.cost += other.cost;
+        for (date, cost) in &other.daily_cost_usd {
+            *self.daily_cost_usd.entry(*date).or_insert(0.0) += cost;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommitReport {
+    pub group_by: CommitGroup,
+    pub rows: Vec<CommitCost>, // Newest commit first, or largest cost first when grouped
+    pub unattributed: CommitCost, // Messages whose cwd is not inside a git repository
+}
+
+/// A priced message and the repository and branch it was written on
+#[derive(Debug, Clone)]
+pub struct RepositoryMessage {
+    pub repository: PathBuf,
+    pub branch: Option<String>, // None: branch unknown or deleted, so HEAD's history
+    pub time: DateTime<Utc>,
+    pub cost: f64,
+}
+
+/// Charge each message to the first commit at or after it on its branch
+///
+/// `commits` holds each repository's history per branch, keyed like the
+/// messages, sorted oldest first. Only commits in the history of the branch a
+/// message was written on can receive it, so work on one branch is never
+/// charged to an unrelated branch committed in the meantime. Messages after
+/// the branch's latest commit are reported as uncommitted work.
+pub fn attribute_messages(
+    messages: &[RepositoryMessage],
+    commits: &HashMap<(PathBuf, Option<String>), Vec<GitCommit>>,
+    group_by: CommitGroup,
+    timezone_calc: &TimezoneCalculator,
+    hidden: bool,
+) -> Vec<CommitCost> {
+    let mut per_commit: HashMap<(&Path, Option<&str>), CommitCost> = HashMap::new();
+    for message in messages {
+        let branch_commits = commits
+            .get(&(message.repository.clone(), message.branch.clone()))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let index = branch_commits.partition_point(|commit| commit.time < message.time);
+        let commit = branch_commits.get(index);
+        per_commit
+            .entry((
+                &message.repository,
+                commit.map(|commit| commit.hash.as_str()),
+            ))
+            .or_insert_with(|| {
+                let repository = message
+                    .repository
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| message.repository.display().to_string());
+                match commit {
+                    Some(commit) => CommitCost {
+                        name: commit.hash.chars().take(8).collect(),
+                        commit: Some(commit.hash.clone()),
+                        repository: Some(maybe_hide_project_name(&repository, hidden)),
+                        author: Some(commit.author.clone()),
+                        branch: commit.branch.clone(),
+                        date: Some(timezone_calc.local_date(commit.time)),
+                        subject: (!hidden).then(|| commit.subject.clone()),
+                        commits: 1,
+                        committed_at: Some(commit.time),
+                        ..Default::default()
+                    },
+                    None => CommitCost {
+                        name: UNCOMMITTED.to_string(),
+                        repository: Some(maybe_hide_project_name(&repository, hidden)),
+                        ..Default::default()
+                    },
+                }
+            })
+            .add_message(message.time, message.cost);
+    }
+
+    let mut rows: Vec<CommitCost> = match group_by {
+        CommitGroup::Commit => per_commit.into_values().collect(),
+        CommitGroup::Author | CommitGroup::Branch => {
+            let mut groups: HashMap<(Option<String>, String), CommitCost> = HashMap::new();
+            for row in per_commit.into_values() {
+                let key = match group_by {
+                    CommitGroup::Author => (
+                        None,
+                        row.author
+                            .clone()
+                            .unwrap_or_else(|| UNCOMMITTED.to_string()),
+                    ),
+                    _ => (
+                        row.repository.clone(),
+                        match (&row.commit, &row.branch) {
+                            (None, _) => UNCOMMITTED.to_string(),
+                            (Some(_), Some(branch)) => branch.clone(),
+                            (Some(_), None) => "-".to_string(),
+                        },
+                    ),
+                };
+                groups
+                    .entry(key.clone())
+                    .or_insert_with(|| CommitCost {
+                        name: key.1.clone(),
+                        repository: key.0.clone(),
+                        ..Default::default()
+                    })
+                    .add(&row);
+            }
+            groups.into_values().collect()
+        }
+    };
+
+    match group_by {
+        // Uncommitted work is the most recent, so it leads
+        CommitGroup::Commit => rows.sort_by(|a, b| {
+            (b.committed_at.is_none(), b.committed_at, &a.repository).cmp(&(
+                a.committed_at.is_none(),
+                a.committed_at,
+                &b.repository,
+            ))
+        }),
+        _ => rows.sort_by(|a, b| b.cost.total_cmp(&a.cost).then(a.name.cmp(&b.name))),
+    }
+    rows
+}
+
+/// Price messages, find their repositories and attribute them to commits
+///
+/// Each distinct `cwd` is resolved to its repository once; commits are read
+/// once per repository and branch. A message's branch is its `gitBranch`;
+/// messages without one, or on a branch that has since been deleted, fall
+/// back to the history of the repository's current HEAD.
+pub async fn commit_costs(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    timezone_calc: &TimezoneCalculator,
+    group_by: CommitGroup,
+    hidden: bool,
+) -> Result<CommitReport> {
+    let mut roots: HashMap<String, Option<PathBuf>> = HashMap::new();
+    let mut branches: HashMap<(PathBuf, String), bool> = HashMap::new();
+    let mut messages = Vec::new();
+    let mut unattributed = CommitCost {
+        name: "outside git".to_string(),
+        ..Default::default()
+    };
+
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let Some(message_time) = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+        if filter.since.is_some_and(|since| message_time < since)
+            || filter.until.is_some_and(|until| message_time > until)
+        {
+            continue;
+        }
+
+        if let Some(ref filter_project) = filter.project_name
+            && project_name != filter_project
+        {
+            continue;
+        }
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(message) {
+            continue;
+        }
+
+        let cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+
+        let repository = message.cwd.as_ref().and_then(|cwd| {
+            roots
+                .entry(cwd.clone())
+                .or_insert_with(|| repository_root(Path::new(cwd)))
+                .clone()
+        });
+        match repository {
+            Some(repository) => {
+                let branch = message_branch(message).filter(|branch| {
+                    *branches
+                        .entry((repository.clone(), branch.to_string()))
+                        .or_insert_with(|| branch_exists(&repository, branch))
+                });
+                messages.push(RepositoryMessage {
+                    branch: branch.map(str::to_string),
+                    repository,
+                    time: message_time,
+                    cost,
+                })
+            }
+            None => unattributed.add_message(message_time, cost),
+        }
+    }
+
+    // Commits before a branch's first message can't receive any cost
+    let mut first_messages: HashMap<(PathBuf, Option<String>), DateTime<Utc>> = HashMap::new();
+    for message in &messages {
+        first_messages
+            .entry((message.repository.clone(), message.branch.clone()))
+            .and_modify(|first| *first = (*first).min(message.time))
+            .or_insert(message.time);
+    }
+    // A history git can't read leaves that branch's messages uncommitted
+    let mut commits = HashMap::new();
+    for ((repository, branch), first_message) in first_messages {
+        let branch_commits =
+            read_commits(&repository, branch.as_deref(), Some(first_message)).unwrap_or_default();
+        commits.insert((repository, branch), branch_commits);
+    }
+
+    Ok(CommitReport {
+        group_by,
+        rows: attribute_messages(&messages, &commits, group_by, timezone_calc, hidden),
+        unattributed,
+    })
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() > width {
+        format!("{}...", text.chars().take(width - 3).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+impl OutputFormat for CommitReport {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.rows.is_empty() {
+            return "No usage inside git repositories found.".to_string();
+        }
+
+        let dash = || "-".to_string();
+        let mut builder = Builder::default();
+        match self.group_by {
+            CommitGroup::Commit => {
+                builder.push_record([
+                    "Commit",
+                    "Date",
+                    "Author",
+                    "Branch",
+                    "Repository",
+                    "Subject",
+                    "Messages",
+                    "Cost",
+                ]);
+                for row in &self.rows {
+                    builder.push_record([
+                        row.name.clone(),
+                        row.date
+                            .map(|date| date.format("%Y-%m-%d").to_string())
+                            .unwrap_or_else(dash),
+                        row.author.clone().unwrap_or_else(dash),
+                        row.branch.clone().unwrap_or_else(dash),
+                        row.repository.clone().unwrap_or_else(dash),
+                        row.subject
+                            .as_deref()
+                            .map(|subject| truncate(subject, SUBJECT_WIDTH))
+                            .unwrap_or_else(dash),
+                        format_number(row.message_count),
+                        format_currency(row.cost, currency, decimal_places),
+                    ]);
+                }
+            }
+            CommitGroup::Author => {
+                builder.push_record(["Author", "Commits", "Messages", "Cost"]);
+                for row in &self.rows {
+                    builder.push_record([
+                        row.name.clone(),
+                        row.commits.to_string(),
+                        format_number(row.message_count),
+                        format_currency(row.cost, currency, decimal_places),
+                    ]);
+                }
+            }
+            CommitGroup::Branch => {
+                builder.push_record(["Branch", "Repository", "Commits", "Messages", "Cost"]);
+                for row in &self.rows {
+                    builder.push_record([
+                        row.name.clone(),
+                        row.repository.clone().unwrap_or_else(dash),
+                        row.commits.to_string(),
+                        format_number(row.message_count),
+                        format_currency(row.cost, currency, decimal_places),
+                    ]);
+                }
+            }
+        }
+
+        let mut output =
+            apply_list_table_style_with_color(builder.build(), colored, TableType::Commits);
+        if self.unattributed.message_count > 0 {
+            output.push_str(&format!(
+                "\n{} message(s) costing {} ran outside a git repository and are not shown.",
+                format_number(self.unattributed.message_count),
+                format_currency(self.unattributed.cost, currency, decimal_places)
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::parser::jsonl::Usage;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 10, hour, 0, 0).unwrap()
+    }
+
+    fn commit(hash: &str, author: &str, branch: &str, hour: u32) -> GitCommit {
+        GitCommit {
+            hash: hash.to_string(),
+            author: author.to_string(),
+            branch: Some(branch.to_string()),
+            time: at(hour),
+            subject: format!("Commit {hash}"),
+        }
+    }
+
+    #[test]
+    fn test_attribute_messages_to_following_commit() {
+        let repo = PathBuf::from("/work/app");
+        let commits = HashMap::from([(
+            (repo.clone(), None),
+            vec![
+                commit("aaaaaaaaaa", "Ana", "main", 10),
+                commit("bbbbbbbbbb", "Bo", "feature", 14),
+            ],
+        )]);
+        let message = |hour, cost| RepositoryMessage {
+            repository: repo.clone(),
+            branch: None,
+            time: at(hour),
+            cost,
+        };
+        // 9h and 10h -> a, 11h and 13h -> b, 15h -> uncommitted
+        let messages = vec![
+            message(9, 1.0),
+            message(10, 2.0),
+            message(11, 4.0),
+            message(13, 8.0),
+            message(15, 16.0),
+        ];
+        let tz = TimezoneCalculator::new("UTC", 0).unwrap();
+
+        let rows = attribute_messages(&messages, &commits, CommitGroup::Commit, &tz, false);
+        let summary: Vec<(&str, u64, f64)> = rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.message_count, row.cost))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("uncommitted", 1, 16.0),
+                ("bbbbbbbb", 2, 12.0),
+                ("aaaaaaaa", 2, 3.0)
+            ]
+        );
+        assert_eq!(rows[1].branch.as_deref(), Some("feature"));
+        assert_eq!(rows[1].repository.as_deref(), Some("app"));
+
+        let rows = attribute_messages(&messages, &commits, CommitGroup::Author, &tz, false);
+        assert_eq!(rows[0].name, "uncommitted");
+        assert_eq!((rows[1].name.as_str(), rows[1].commits), ("Bo", 1));
+    }
+
+    #[test]
+    fn test_attribute_messages_per_branch() {
+        // main and feature commit in turns; each message only sees its own branch
+        let repo = PathBuf::from("/work/app");
+        let branch = |name: &str| Some(name.to_string());
+        let commits = HashMap::from([
+            (
+                (repo.clone(), branch("main")),
+                vec![
+                    commit("m1", "Ana", "main", 10),
+                    commit("m2", "Ana", "main", 14),
+                ],
+            ),
+            (
+                (repo.clone(), branch("feature")),
+                vec![
+                    commit("m1", "Ana", "feature", 10),
+                    commit("f1", "Bo", "feature", 12),
+                    commit("f2", "Bo", "feature", 16),
+                ],
+            ),
+        ]);
+        let message = |name: &str, hour, cost| RepositoryMessage {
+            repository: repo.clone(),
+            branch: branch(name),
+            time: at(hour),
+            cost,
+        };
+        // main: 9h -> m1, 13h -> m2; feature: 11h -> f1, 13h and 15h -> f2
+        let messages = vec![
+            message("main", 9, 1.0),
+            message("feature", 11, 2.0),
+            message("main", 13, 4.0),
+            message("feature", 13, 8.0),
+            message("feature", 15, 16.0),
+        ];
+        let tz = TimezoneCalculator::new("UTC", 0).unwrap();
+
+        let rows = attribute_messages(&messages, &commits, CommitGroup::Commit, &tz, false);
+        let summary: Vec<(&str, Option<&str>, f64)> = rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.branch.as_deref(), row.cost))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("f2", Some("feature"), 24.0),
+                ("m2", Some("main"), 4.0),
+                ("f1", Some("feature"), 2.0),
+                ("m1", Some("main"), 1.0)
+            ]
+        );
+
+        let rows = attribute_messages(&messages, &commits, CommitGroup::Branch, &tz, false);
+        let summary: Vec<(&str, usize, f64)> = rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.commits, row.cost))
+            .collect();
+        assert_eq!(summary, vec![("feature", 2, 26.0), ("main", 2, 5.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_repository_without_commits_is_uncommitted() {
+        let temp_dir = TempDir::new().unwrap();
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(temp_dir.path())
+            .args(["init", "--quiet"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(read_commits(temp_dir.path(), None, None).unwrap(), vec![]);
+
+        let message = |timestamp: &str, branch: Option<&str>| UsageData {
+            timestamp: Some(timestamp.to_string()),
+            uuid: None,
+            request_id: None,
+            session_id: Some("s1".to_string()),
+            message: None,
+            usage: Some(Usage {
+                input_tokens: Some(100),
+                output_tokens: Some(10),
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            }),
+            cost_usd: Some(1.5),
+            cwd: Some(temp_dir.path().display().to_string()),
+            original_cwd: None,
+            git_branch: branch.map(str::to_string),
+        };
+        let data = vec![
+            (message("2025-06-10T09:00:00Z", None), "app".to_string()),
+            (
+                message("2025-06-10T10:00:00Z", Some("main")),
+                "app".to_string(),
+            ),
+        ];
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let tz = TimezoneCalculator::new("UTC", 0).unwrap();
+
+        let report = commit_costs(
+            &tracker,
+            &data,
+            &mut pricing_manager,
+            &UsageFilter::default(),
+            &tz,
+            CommitGroup::Commit,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.rows[0].name, UNCOMMITTED);
+        assert_eq!(
+            (report.rows[0].message_count, report.rows[0].cost),
+            (2, 3.0)
+        );
+    }
+}
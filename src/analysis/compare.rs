@@ -0,0 +1,402 @@
+// Period-over-period comparison: the same aggregation over two date ranges
+use crate::analysis::usage::ProjectUsage;
+use crate::models::currency::{format_currency, format_currency_signed};
+use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
+use crate::output::table::{TableType, apply_table_style_with_color, format_number};
+use anyhow::Result;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tabled::builder::Builder;
+
+/// What each comparison row aggregates over
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompareGroup {
+    Project,
+    Model,
+}
+
+impl CompareGroup {
+    pub fn from_arg(group: &str) -> Result<Self> {
+        match group {
+            "project" => Ok(CompareGroup::Project),
+            "model" => Ok(CompareGroup::Model),
+            _ => anyhow::bail!("Invalid grouping: {group}. Must be 'project' or 'model'"),
+        }
+    }
+}
+
+/// A named timeframe or explicit date range, as local calendar dates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparePeriod {
+    pub label: String,
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate, // Inclusive
+}
+
+/// Resolve a period spec relative to `today`
+///
+/// Accepts `today`, `yesterday`, `this-week`, `last-week`, `this-month`,
+/// `last-month`, a month (`2025-06`), a day (`2025-06-01`) or an inclusive
+/// range (`2025-06-01..2025-06-15`).
+pub fn parse_period(spec: &str, today: NaiveDate) -> Result<ComparePeriod> {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday().into());
+    let month_start = first_of_month(today);
+
+    let (first_day, last_day) = match spec {
+        "today" => (today, today),
+        "yesterday" => {
+            let yesterday = today - Duration::days(1);
+            (yesterday, yesterday)
+        }
+        "this-week" => (monday, monday + Duration::days(6)),
+        "last-week" => (monday - Duration::days(7), monday - Duration::days(1)),
+        "this-month" => (month_start, last_of_month(month_start)),
+        "last-month" => {
+            let previous = month_start
+                .checked_sub_months(Months::new(1))
+                .unwrap_or(month_start);
+            (previous, last_of_month(previous))
+        }
+        _ => {
+            if let Some((first, last)) = spec.split_once("..") {
+                (parse_day(first)?, parse_day(last)?)
+            } else if let Ok(day) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+                (day, day)
+            } else if let Ok(month) = NaiveDate::parse_from_str(&format!("{spec}-01"), "%Y-%m-%d") {
+                (month, last_of_month(month))
+            } else {
+                anyhow::bail!(
+                    "Invalid period: {spec}. Use today, yesterday, this-week, last-week, this-month, last-month, YYYY-MM, YYYY-MM-DD or YYYY-MM-DD..YYYY-MM-DD"
+                );
+            }
+        }
+    };
+
+    if last_day < first_day {
+        anyhow::bail!("Invalid period: {spec} ends before it starts");
+    }
+
+    Ok(ComparePeriod {
+        label: spec.to_string(),
+        first_day,
+        last_day,
+    })
+}
+
+fn parse_day(day: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date: {day}. Use YYYY-MM-DD"))
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1).expect("Valid first day of month")
+}
+
+fn last_of_month(month_start: NaiveDate) -> NaiveDate {
+    month_start
+        .checked_add_months(Months::new(1))
+        .map_or(month_start, |next| next - Duration::days(1))
+}
+
+/// Usage totals for one project or model within one period
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PeriodTotals {
+    pub tokens: u64, // Input + output, as in the summary tables
+    pub messages: u64,
+    pub cost: f64,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+}
+
+/// Sum filtered project usage into one entry per project or model
+pub fn group_totals(usage: &[ProjectUsage], group: CompareGroup) -> BTreeMap<String, PeriodTotals> {
+    let mut totals: BTreeMap<String, PeriodTotals> = BTreeMap::new();
+    for project in usage {
+        match group {
+            CompareGroup::Project => {
+                let entry = totals.entry(project.project_name.clone()).or_default();
+                entry.tokens += project.total_input_tokens + project.total_output_tokens;
+                entry.messages += project.message_count;
+                entry.cost += project.total_cost_usd;
+                for (date, cost) in &project.daily_cost_usd {
+                    *entry.daily_cost_usd.entry(*date).or_insert(0.0) += cost;
+                }
+            }
+            CompareGroup::Model => {
+                for model in project.model_usage.values() {
+                    let entry = totals.entry(model.model_name.clone()).or_default();
+                    entry.tokens += model.input_tokens + model.output_tokens;
+                    entry.messages += model.message_count;
+                    entry.cost += model.cost_usd;
+                    for (date, cost) in &model.daily_cost_usd {
+                        *entry.daily_cost_usd.entry(*date).or_insert(0.0) += cost;
+                    }
+                }
+            }
+        }
+    }
+    totals
+}
+
+/// Change from the baseline to the current period (percentages are None without a baseline)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompareDelta {
+    pub tokens: i64,
+    pub tokens_percent: Option<f64>,
+    pub messages: i64,
+    pub messages_percent: Option<f64>,
+    pub cost: f64,
+    pub cost_percent: Option<f64>,
+}
+
+impl CompareDelta {
+    fn between(baseline: &PeriodTotals, current: &PeriodTotals) -> Self {
+        let percent = |before: f64, after: f64| {
+            (before.abs() > f64::EPSILON).then(|| (after - before) / before * 100.0)
+        };
+        Self {
+            tokens: current.tokens as i64 - baseline.tokens as i64,
+            tokens_percent: percent(baseline.tokens as f64, current.tokens as f64),
+            messages: current.messages as i64 - baseline.messages as i64,
+            messages_percent: percent(baseline.messages as f64, current.messages as f64),
+            cost: current.cost - baseline.cost,
+            cost_percent: percent(baseline.cost, current.cost),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompareRow {
+    pub name: String,
+    pub baseline: PeriodTotals,
+    pub current: PeriodTotals,
+    pub delta: CompareDelta,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodComparison {
+    pub group_by: CompareGroup,
+    pub baseline_period: ComparePeriod,
+    pub current_period: ComparePeriod,
+    pub rows: Vec<CompareRow>, // Biggest cost change first
+    pub total: CompareRow,
+}
+
+/// Pair up both periods' totals; names missing from one period count as zero there
+pub fn compare_periods(
+    group_by: CompareGroup,
+    baseline_period: ComparePeriod,
+    current_period: ComparePeriod,
+    baseline: &BTreeMap<String, PeriodTotals>,
+    current: &BTreeMap<String, PeriodTotals>,
+) -> PeriodComparison {
+    let names: BTreeSet<&String> = baseline.keys().chain(current.keys()).collect();
+    let mut rows: Vec<CompareRow> = names
+        .into_iter()
+        .map(|name| {
+            let before = baseline.get(name).cloned().unwrap_or_default();
+            let after = current.get(name).cloned().unwrap_or_default();
+            CompareRow {
+                name: name.clone(),
+                delta: CompareDelta::between(&before, &after),
+                baseline: before,
+                current: after,
+            }
+        })
+        .collect();
+
+    rows.sort_by(|a, b| {
+        b.delta
+            .cost
+            .abs()
+            .total_cmp(&a.delta.cost.abs())
+            .then_with(|| b.delta.tokens.abs().cmp(&a.delta.tokens.abs()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let sum = |side: fn(&CompareRow) -> &PeriodTotals| PeriodTotals {
+        tokens: rows.iter().map(|r| side(r).tokens).sum(),
+        messages: rows.iter().map(|r| side(r).messages).sum(),
+        cost: rows.iter().fold(0.0, |total, r| total + side(r).cost),
+        daily_cost_usd: BTreeMap::new(),
+    };
+    let total_baseline = sum(|r| &r.baseline);
+    let total_current = sum(|r| &r.current);
+    let total = CompareRow {
+        name: "TOTAL".to_string(),
+        delta: CompareDelta::between(&total_baseline, &total_current),
+        baseline: total_baseline,
+        current: total_current,
+    };
+
+    PeriodComparison {
+        group_by,
+        baseline_period,
+        current_period,
+        rows,
+        total,
+    }
+}
+
+fn format_percent(percent: Option<f64>, changed: bool) -> String {
+    match percent {
+        Some(percent) => {
+            let sign = if percent < 0.0 { "-" } else { "+" };
+            format!("{sign}{}%", format_decimal(percent.abs(), 1))
+        }
+        None if changed => "new".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn format_count_delta(delta: i64, percent: Option<f64>) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!(
+        "{sign}{} ({})",
+        format_number(delta.unsigned_abs()),
+        format_percent(percent, delta != 0)
+    )
+}
+
+impl OutputFormat for PeriodComparison {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.rows.is_empty() {
+            return "No usage data found.".to_string();
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record([
+            match self.group_by {
+                CompareGroup::Project => "Project",
+                CompareGroup::Model => "Model",
+            },
+            "Tokens",
+            "Δ Tokens",
+            "Messages",
+            "Δ Messages",
+            "Cost",
+            "Δ Cost",
+        ]);
+
+        for row in self.rows.iter().chain(std::iter::once(&self.total)) {
+            builder.push_record([
+                row.name.clone(),
+                format!(
+                    "{} → {}",
+                    format_number(row.baseline.tokens),
+                    format_number(row.current.tokens)
+                ),
+                format_count_delta(row.delta.tokens, row.delta.tokens_percent),
+                format!(
+                    "{} → {}",
+                    format_number(row.baseline.messages),
+                    format_number(row.current.messages)
+                ),
+                format_count_delta(row.delta.messages, row.delta.messages_percent),
+                format!(
+                    "{} → {}",
+                    format_currency(row.baseline.cost, currency, decimal_places),
+                    format_currency(row.current.cost, currency, decimal_places)
+                ),
+                format!(
+                    "{} ({})",
+                    format_currency_signed(row.delta.cost, currency, decimal_places),
+                    format_percent(row.delta.cost_percent, row.delta.cost.abs() > f64::EPSILON)
+                ),
+            ]);
+        }
+
+        apply_table_style_with_color(builder.build(), colored, TableType::Compare)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(d: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()
+    }
+
+    fn totals(tokens: u64, messages: u64, cost: f64) -> PeriodTotals {
+        PeriodTotals {
+            tokens,
+            messages,
+            cost,
+            daily_cost_usd: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_period_specs() {
+        // A Wednesday
+        let today = date("2025-06-11");
+
+        let week = parse_period("last-week", today).unwrap();
+        assert_eq!(week.first_day, date("2025-06-02"));
+        assert_eq!(week.last_day, date("2025-06-08"));
+
+        let month = parse_period("last-month", today).unwrap();
+        assert_eq!(month.first_day, date("2025-05-01"));
+        assert_eq!(month.last_day, date("2025-05-31"));
+
+        let february = parse_period("2024-02", today).unwrap();
+        assert_eq!(february.last_day, date("2024-02-29"));
+
+        let range = parse_period("2025-06-01..2025-06-15", today).unwrap();
+        assert_eq!(range.first_day, date("2025-06-01"));
+        assert_eq!(range.last_day, date("2025-06-15"));
+
+        assert!(parse_period("2025-06-15..2025-06-01", today).is_err());
+        assert!(parse_period("fortnight", today).is_err());
+    }
+
+    #[test]
+    fn test_compare_periods_sorts_by_biggest_change() {
+        let today = date("2025-06-11");
+        let baseline = BTreeMap::from([
+            ("alpha".to_string(), totals(1000, 10, 5.0)),
+            ("beta".to_string(), totals(500, 5, 2.0)),
+        ]);
+        let current = BTreeMap::from([
+            ("alpha".to_string(), totals(1500, 12, 6.0)),
+            ("gamma".to_string(), totals(2000, 8, 4.0)),
+        ]);
+
+        let comparison = compare_periods(
+            CompareGroup::Project,
+            parse_period("last-week", today).unwrap(),
+            parse_period("this-week", today).unwrap(),
+            &baseline,
+            &current,
+        );
+
+        let names: Vec<&str> = comparison.rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["gamma", "beta", "alpha"]);
+
+        let gamma = &comparison.rows[0];
+        assert_eq!(gamma.delta.tokens, 2000);
+        assert_eq!(gamma.delta.cost_percent, None);
+
+        let beta = &comparison.rows[1];
+        assert_eq!(beta.delta.messages, -5);
+        assert!((beta.delta.cost_percent.unwrap() + 100.0).abs() < 1e-9);
+
+        assert_eq!(comparison.total.baseline.tokens, 1500);
+        assert_eq!(comparison.total.current.tokens, 3500);
+        assert!((comparison.total.delta.cost - 3.0).abs() < 1e-9);
+        assert!((comparison.total.delta.cost_percent.unwrap() - 3.0 / 7.0 * 100.0).abs() < 1e-9);
+    }
+}
@@ -1,9 +1,14 @@
+use crate::analysis::anomalies::RobustBaseline;
+use crate::analysis::cache::cache_hit_rate;
+use crate::analysis::{UsageFilter, UsageTracker};
+use crate::models::PricingManager;
 use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
 use crate::parser::jsonl::UsageData;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Represents a conversation with all its messages and metadata
 #[derive(Debug, Clone, Serialize)]
@@ -35,6 +40,9 @@ pub struct ConversationInsight {
     pub end_time: DateTime<Utc>,
     pub duration_minutes: f64,
     pub cache_hit_rate: f32,
+    pub outlier_score: Option<f64>, // Set when cost is an outlier among the project's conversations
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
 }
 
 /// Model usage within a conversation
@@ -54,6 +62,192 @@ pub struct ConversationModelUsage {
 #[derive(Debug, Clone, Serialize)]
 pub struct ConversationInsightList(pub Vec<ConversationInsight>);
 
+impl ConversationInsightList {
+    /// Score each conversation's cost against the other conversations in its project
+    pub fn flag_outliers(&mut self, threshold: f64) {
+        let mut costs_by_project: HashMap<String, Vec<f64>> = HashMap::new();
+        for insight in &self.0 {
+            costs_by_project
+                .entry(insight.project_name.clone())
+                .or_default()
+                .push(insight.total_cost);
+        }
+        let baselines: HashMap<String, RobustBaseline> = costs_by_project
+            .into_iter()
+            .filter_map(|(project, costs)| {
+                RobustBaseline::from_values(&costs).map(|baseline| (project, baseline))
+            })
+            .collect();
+
+        for insight in &mut self.0 {
+            insight.outlier_score = baselines
+                .get(&insight.project_name)
+                .map(|baseline| baseline.score(insight.total_cost))
+                .filter(|score| *score > threshold);
+        }
+    }
+}
+
+/// Build one insight per session from filtered usage, most recent first
+///
+/// Costs use the embedded `costUSD` when present and enhanced pricing otherwise.
+/// The efficiency score is the share of all processed tokens (prompt, cache and
+/// output) that were output tokens. Messages without a session ID are skipped.
+pub async fn analyze_conversations(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+) -> Result<ConversationInsightList> {
+    let mut insights: HashMap<String, ConversationInsight> = HashMap::new();
+
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let Some(session_id) = &message.session_id else {
+            continue;
+        };
+        let Some(message_time) = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+        if filter.since.is_some_and(|since| message_time < since)
+            || filter.until.is_some_and(|until| message_time > until)
+        {
+            continue;
+        }
+
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(message) {
+            continue;
+        }
+
+        let cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+        let input_tokens = usage.input_tokens.unwrap_or(0);
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        let cache_creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0);
+        let cache_read_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+
+        let insight = insights
+            .entry(session_id.clone())
+            .or_insert_with(|| ConversationInsight {
+                conversation_id: session_id.clone(),
+                project_name: project_name.clone(),
+                total_cost: 0.0,
+                message_count: 0,
+                total_input_tokens: 0,
+                total_output_tokens: 0,
+                total_cache_creation_tokens: 0,
+                total_cache_read_tokens: 0,
+                efficiency_score: 0.0,
+                cost_per_message: 0.0,
+                cost_per_token: 0.0,
+                model_usage: HashMap::new(),
+                start_time: message_time,
+                end_time: message_time,
+                duration_minutes: 0.0,
+                cache_hit_rate: 0.0,
+                outlier_score: None,
+                daily_cost_usd: BTreeMap::new(),
+            });
+        insight.total_cost += cost;
+        insight.message_count += 1;
+        insight.total_input_tokens += input_tokens;
+        insight.total_output_tokens += output_tokens;
+        insight.total_cache_creation_tokens += cache_creation_tokens;
+        insight.total_cache_read_tokens += cache_read_tokens;
+        insight.start_time = insight.start_time.min(message_time);
+        insight.end_time = insight.end_time.max(message_time);
+        *insight
+            .daily_cost_usd
+            .entry(message_time.date_naive())
+            .or_insert(0.0) += cost;
+
+        let model_usage = insight
+            .model_usage
+            .entry(model_name.clone())
+            .or_insert_with(|| ConversationModelUsage {
+                model_name,
+                message_count: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                cost_usd: 0.0,
+                cost_percentage: 0.0,
+            });
+        model_usage.message_count += 1;
+        model_usage.input_tokens += input_tokens;
+        model_usage.output_tokens += output_tokens;
+        model_usage.cache_creation_tokens += cache_creation_tokens;
+        model_usage.cache_read_tokens += cache_read_tokens;
+        model_usage.cost_usd += cost;
+    }
+
+    let mut insights: Vec<ConversationInsight> = insights.into_values().collect();
+    for insight in &mut insights {
+        let prompt_tokens = insight.total_input_tokens
+            + insight.total_cache_creation_tokens
+            + insight.total_cache_read_tokens;
+        let all_tokens = prompt_tokens + insight.total_output_tokens;
+        insight.cost_per_message = insight.total_cost / insight.message_count as f64;
+        insight.cost_per_token = if all_tokens > 0 {
+            insight.total_cost / all_tokens as f64
+        } else {
+            0.0
+        };
+        insight.efficiency_score = if all_tokens > 0 {
+            (insight.total_output_tokens as f64 / all_tokens as f64 * 100.0) as f32
+        } else {
+            0.0
+        };
+        insight.cache_hit_rate = cache_hit_rate(
+            insight.total_input_tokens,
+            insight.total_cache_creation_tokens,
+            insight.total_cache_read_tokens,
+        ) as f32;
+        insight.duration_minutes =
+            (insight.end_time - insight.start_time).num_seconds() as f64 / 60.0;
+        for model_usage in insight.model_usage.values_mut() {
+            model_usage.cost_percentage = if insight.total_cost > 0.0 {
+                (model_usage.cost_usd / insight.total_cost * 100.0) as f32
+            } else {
+                0.0
+            };
+        }
+    }
+    insights.sort_by(|a, b| {
+        b.start_time
+            .cmp(&a.start_time)
+            .then_with(|| a.conversation_id.cmp(&b.conversation_id))
+    });
+
+    let mut list = ConversationInsightList(insights);
+    list.flag_outliers(crate::analysis::anomalies::DEFAULT_THRESHOLD);
+    Ok(list)
+}
+
 impl OutputFormat for ConversationInsightList {
     fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(&self.0)
@@ -86,6 +280,8 @@ impl OutputFormat for ConversationInsightList {
             efficiency: String,
             #[tabled(rename = "Models")]
             models: String,
+            #[tabled(rename = "Outliers")]
+            outliers: String,
             #[tabled(rename = "Duration")]
             duration: String,
         }
@@ -108,9 +304,9 @@ impl OutputFormat for ConversationInsightList {
                 };
 
                 let duration_str = if insight.duration_minutes > 60.0 {
-                    format!("{:.1}h", insight.duration_minutes / 60.0)
+                    format!("{}h", format_decimal(insight.duration_minutes / 60.0, 1))
                 } else {
-                    format!("{:.1}m", insight.duration_minutes)
+                    format!("{}m", format_decimal(insight.duration_minutes, 1))
                 };
 
                 ConversationRow {
@@ -122,8 +318,15 @@ impl OutputFormat for ConversationInsightList {
                         currency,
                         decimal_places,
                     ),
-                    efficiency: format!("{:.1}%", insight.efficiency_score),
+                    efficiency: format!(
+                        "{}%",
+                        format_decimal(f64::from(insight.efficiency_score), 1)
+                    ),
                     models: models_str,
+                    outliers: insight
+                        .outlier_score
+                        .map(|score| format_decimal(score, 1))
+                        .unwrap_or_else(|| "-".to_string()),
                     duration: duration_str,
                 }
             })
@@ -132,3 +335,130 @@ impl OutputFormat for ConversationInsightList {
         apply_table_style_with_color(Table::new(rows), colored, TableType::Conversations)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn insight(project: &str, cost: f64) -> ConversationInsight {
+        let time = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        ConversationInsight {
+            conversation_id: format!("{project}-{cost}"),
+            project_name: project.to_string(),
+            total_cost: cost,
+            message_count: 1,
+            total_input_tokens: 0,
+            total_output_tokens: 0,
+            total_cache_creation_tokens: 0,
+            total_cache_read_tokens: 0,
+            efficiency_score: 0.0,
+            cost_per_message: cost,
+            cost_per_token: 0.0,
+            model_usage: HashMap::new(),
+            start_time: time,
+            end_time: time,
+            duration_minutes: 0.0,
+            cache_hit_rate: 0.0,
+            outlier_score: None,
+            daily_cost_usd: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_flag_outliers_per_project() {
+        let mut list = ConversationInsightList(vec![
+            insight("alpha", 1.0),
+            insight("alpha", 1.2),
+            insight("alpha", 0.9),
+            insight("alpha", 1.1),
+            insight("alpha", 12.0),
+            // Too few conversations to judge
+            insight("beta", 0.1),
+            insight("beta", 40.0),
+        ]);
+
+        list.flag_outliers(3.5);
+
+        let flagged: Vec<&str> = list
+            .0
+            .iter()
+            .filter(|i| i.outlier_score.is_some())
+            .map(|i| i.conversation_id.as_str())
+            .collect();
+        assert_eq!(flagged, vec!["alpha-12"]);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_conversations_groups_by_session() {
+        use crate::analysis::CostCalculationMode;
+        use crate::parser::jsonl::{Message, Usage};
+
+        let message = |session: &str, timestamp: &str, model: &str, cost: f64| {
+            (
+                UsageData {
+                    timestamp: Some(timestamp.to_string()),
+                    uuid: None,
+                    request_id: None,
+                    session_id: Some(session.to_string()),
+                    message: Some(Message {
+                        model: Some(model.to_string()),
+                        ..Default::default()
+                    }),
+                    usage: Some(Usage {
+                        input_tokens: Some(100),
+                        output_tokens: Some(50),
+                        cache_creation_input_tokens: Some(0),
+                        cache_read_input_tokens: Some(100),
+                    }),
+                    cost_usd: Some(cost),
+                    cwd: None,
+                    original_cwd: None,
+                    git_branch: None,
+                },
+                "alpha".to_string(),
+            )
+        };
+        let data = vec![
+            message(
+                "s1",
+                "2025-06-01T10:00:00Z",
+                "claude-sonnet-4-20250514",
+                1.0,
+            ),
+            message("s1", "2025-06-01T10:30:00Z", "claude-opus-4-20250514", 3.0),
+            message(
+                "s2",
+                "2025-06-02T09:00:00Z",
+                "claude-sonnet-4-20250514",
+                0.5,
+            ),
+        ];
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+
+        let list = analyze_conversations(
+            &tracker,
+            &data,
+            &mut pricing_manager,
+            &UsageFilter::default(),
+        )
+        .await
+        .unwrap();
+
+        // Most recent session first
+        assert_eq!(list.0.len(), 2);
+        assert_eq!(list.0[0].conversation_id, "s2");
+        let s1 = &list.0[1];
+        assert_eq!(s1.message_count, 2);
+        assert_eq!(s1.total_cost, 4.0);
+        assert_eq!(s1.cost_per_message, 2.0);
+        assert_eq!(s1.duration_minutes, 30.0);
+        assert_eq!(s1.efficiency_score, 20.0);
+        assert_eq!(s1.cache_hit_rate, 0.5);
+        assert_eq!(
+            s1.model_usage["claude-opus-4-20250514"].cost_percentage,
+            75.0
+        );
+    }
+}
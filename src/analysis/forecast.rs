@@ -0,0 +1,435 @@
+// Month-end spend forecasting from the daily cost series
+use crate::analysis::{TimezoneCalculator, UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// z-score for the two-sided 90% band around the projection
+const BAND_Z: f64 = 1.645;
+
+/// How the daily run rate for the rest of the month is estimated
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForecastMethod {
+    /// Average of the complete days so far
+    Linear,
+    /// Average per weekday, so quiet weekends stay quiet
+    Weekday,
+    /// Exponentially smoothed level, favouring recent days
+    Exponential,
+}
+
+impl ForecastMethod {
+    pub fn from_config_str(method: &str) -> Result<Self> {
+        match method {
+            "linear" => Ok(ForecastMethod::Linear),
+            "weekday" => Ok(ForecastMethod::Weekday),
+            "exponential" => Ok(ForecastMethod::Exponential),
+            _ => anyhow::bail!(
+                "Invalid forecast method: {method}. Must be 'linear', 'weekday', or 'exponential'"
+            ),
+        }
+    }
+}
+
+/// Projected month-end total with a 90% confidence band
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthForecast {
+    pub method: ForecastMethod,
+    pub month_end: NaiveDate,
+    pub days_elapsed: u32,
+    pub days_remaining: u32,
+    pub month_to_date: f64,
+    pub daily_rate: f64, // Expected spend per remaining day
+    pub projected_total: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence: f64,
+}
+
+impl MonthForecast {
+    /// Re-express in another currency: month-to-date as already converted,
+    /// the projected remainder at `rate`
+    pub fn convert(&mut self, month_to_date: f64, rate: f64) {
+        let to_date_usd = self.month_to_date;
+        let rescale = |amount: f64| month_to_date + (amount - to_date_usd) * rate;
+        self.projected_total = rescale(self.projected_total);
+        self.lower = rescale(self.lower);
+        self.upper = rescale(self.upper);
+        self.daily_rate *= rate;
+        self.month_to_date = month_to_date;
+    }
+
+    /// One-line summary printed under the `this-month` table
+    pub fn to_footer(&self, currency: &str, decimal_places: u8) -> String {
+        let method = match self.method {
+            ForecastMethod::Linear => "linear",
+            ForecastMethod::Weekday => "weekday",
+            ForecastMethod::Exponential => "exponential",
+        };
+        if self.days_remaining == 0 {
+            return format!(
+                "Forecast ({method}): {} at month end (last day)",
+                format_currency(self.projected_total, currency, decimal_places)
+            );
+        }
+        format!(
+            "Forecast ({method}): {} at month end, {:.0}% range {} – {} ({}/day over {} remaining days)",
+            format_currency(self.projected_total, currency, decimal_places),
+            self.confidence * 100.0,
+            format_currency(self.lower, currency, decimal_places),
+            format_currency(self.upper, currency, decimal_places),
+            format_currency(self.daily_rate, currency, decimal_places),
+            self.days_remaining
+        )
+    }
+}
+
+/// Daily cost series in USD for the forecast, keyed by local date
+///
+/// Costs come from the same path as the month-to-date total (embedded cost,
+/// else enhanced pricing) and days follow the configured timezone, so the
+/// series lines up with the forecast's "today".
+pub async fn daily_cost_series(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    timezone_calc: &TimezoneCalculator,
+) -> Result<BTreeMap<NaiveDate, f64>> {
+    let mut series = BTreeMap::new();
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let Some(message_time) = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+        if filter.since.is_some_and(|since| message_time < since)
+            || filter.until.is_some_and(|until| message_time > until)
+        {
+            continue;
+        }
+        if let Some(ref filter_project) = filter.project_name
+            && project_name != filter_project
+        {
+            continue;
+        }
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(message) {
+            continue;
+        }
+
+        let cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+        *series
+            .entry(timezone_calc.local_date(message_time))
+            .or_insert(0.0) += cost;
+    }
+    Ok(series)
+}
+
+/// Forecast the month containing `today`
+///
+/// `daily_costs` is the month's daily series; days without usage count as zero.
+/// Today is incomplete, so it only feeds the estimate on the first of the month.
+pub fn forecast_month(
+    daily_costs: &BTreeMap<NaiveDate, f64>,
+    month_to_date: f64,
+    today: NaiveDate,
+    method: ForecastMethod,
+    smoothing_alpha: f64,
+) -> MonthForecast {
+    let month_start = today.with_day(1).expect("Valid first day of month");
+    let month_end = month_start
+        .checked_add_months(Months::new(1))
+        .map(|next| next - Duration::days(1))
+        .unwrap_or(today);
+
+    let history_end = if today > month_start {
+        today - Duration::days(1)
+    } else {
+        today
+    };
+    let series: Vec<(NaiveDate, f64)> = month_start
+        .iter_days()
+        .take_while(|day| *day <= history_end)
+        .map(|day| (day, daily_costs.get(&day).copied().unwrap_or(0.0)))
+        .collect();
+
+    let remaining_days: Vec<NaiveDate> = today
+        .iter_days()
+        .skip(1)
+        .take_while(|day| *day <= month_end)
+        .collect();
+
+    let mean = series.iter().map(|(_, cost)| cost).sum::<f64>() / series.len().max(1) as f64;
+
+    // Expected spend for each remaining day, plus the residuals of the fit
+    let (expected_remaining, residuals): (f64, Vec<f64>) = match method {
+        ForecastMethod::Linear => (
+            mean * remaining_days.len() as f64,
+            series.iter().map(|(_, cost)| cost - mean).collect(),
+        ),
+        ForecastMethod::Weekday => {
+            let mut by_weekday: BTreeMap<u32, (f64, u32)> = BTreeMap::new();
+            for (day, cost) in &series {
+                let entry = by_weekday
+                    .entry(day.weekday().num_days_from_monday())
+                    .or_default();
+                entry.0 += cost;
+                entry.1 += 1;
+            }
+            let weekday_mean = |day: &NaiveDate| {
+                by_weekday
+                    .get(&day.weekday().num_days_from_monday())
+                    .map_or(mean, |(total, count)| total / f64::from(*count))
+            };
+            (
+                remaining_days.iter().map(weekday_mean).sum(),
+                series
+                    .iter()
+                    .map(|(day, cost)| cost - weekday_mean(day))
+                    .collect(),
+            )
+        }
+        ForecastMethod::Exponential => {
+            let alpha = smoothing_alpha.clamp(0.01, 1.0);
+            let mut level = series.first().map_or(0.0, |(_, cost)| *cost);
+            let mut residuals = Vec::new();
+            for (_, cost) in series.iter().skip(1) {
+                residuals.push(cost - level);
+                level = alpha * cost + (1.0 - alpha) * level;
+            }
+            (level * remaining_days.len() as f64, residuals)
+        }
+    };
+
+    let std_dev = if residuals.len() > 1 {
+        let variance = residuals.iter().map(|r| r * r).sum::<f64>() / (residuals.len() - 1) as f64;
+        variance.sqrt()
+    } else {
+        0.0
+    };
+    let margin = BAND_Z * std_dev * (remaining_days.len() as f64).sqrt();
+    let projected_total = month_to_date + expected_remaining;
+
+    MonthForecast {
+        method,
+        month_end,
+        days_elapsed: today.day(),
+        days_remaining: remaining_days.len() as u32,
+        month_to_date,
+        daily_rate: if remaining_days.is_empty() {
+            0.0
+        } else {
+            expected_remaining / remaining_days.len() as f64
+        },
+        projected_total,
+        lower: (projected_total - margin).max(month_to_date),
+        upper: projected_total + margin,
+        confidence: 0.9,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(d: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()
+    }
+
+    // June 2025 starts on a Sunday; weekdays cost 10, weekends cost 0
+    fn june_series(until_day: u32) -> BTreeMap<NaiveDate, f64> {
+        (1..=until_day)
+            .map(|d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap())
+            .map(|day| {
+                let cost = if day.weekday().num_days_from_monday() < 5 {
+                    10.0
+                } else {
+                    0.0
+                };
+                (day, cost)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_forecast_uses_complete_days() {
+        let daily = BTreeMap::from([(date("2025-06-01"), 10.0), (date("2025-06-02"), 30.0)]);
+        // Today (the 3rd) has 5 so far and is excluded from the rate
+        let daily = {
+            let mut d = daily;
+            d.insert(date("2025-06-03"), 5.0);
+            d
+        };
+
+        let forecast = forecast_month(
+            &daily,
+            45.0,
+            date("2025-06-03"),
+            ForecastMethod::Linear,
+            0.3,
+        );
+
+        assert_eq!(forecast.month_end, date("2025-06-30"));
+        assert_eq!(forecast.days_remaining, 27);
+        assert!((forecast.daily_rate - 20.0).abs() < 1e-9);
+        assert!((forecast.projected_total - (45.0 + 27.0 * 20.0)).abs() < 1e-9);
+        assert!(forecast.lower < forecast.projected_total);
+        assert!(forecast.upper > forecast.projected_total);
+        assert!(forecast.lower >= 45.0);
+    }
+
+    #[test]
+    fn test_weekday_forecast_keeps_weekends_quiet() {
+        let daily = june_series(14);
+        let month_to_date: f64 = daily.values().sum();
+
+        let weekday = forecast_month(
+            &daily,
+            month_to_date,
+            date("2025-06-14"),
+            ForecastMethod::Weekday,
+            0.3,
+        );
+        // 16 days remain: 11 weekdays at 10, 5 weekend days at 0; a perfect fit has no band
+        assert!((weekday.projected_total - (month_to_date + 110.0)).abs() < 1e-9);
+        assert!((weekday.upper - weekday.lower).abs() < 1e-9);
+
+        let linear = forecast_month(
+            &daily,
+            month_to_date,
+            date("2025-06-14"),
+            ForecastMethod::Linear,
+            0.3,
+        );
+        assert!(linear.upper - linear.lower > 0.0);
+    }
+
+    #[test]
+    fn test_exponential_forecast_follows_recent_days() {
+        let mut daily = BTreeMap::new();
+        for d in 1..=5 {
+            daily.insert(NaiveDate::from_ymd_opt(2025, 6, d).unwrap(), 0.0);
+        }
+        for d in 6..=10 {
+            daily.insert(NaiveDate::from_ymd_opt(2025, 6, d).unwrap(), 10.0);
+        }
+
+        let smoothed = forecast_month(
+            &daily,
+            50.0,
+            date("2025-06-11"),
+            ForecastMethod::Exponential,
+            0.5,
+        );
+        let linear = forecast_month(
+            &daily,
+            50.0,
+            date("2025-06-11"),
+            ForecastMethod::Linear,
+            0.5,
+        );
+        assert!(smoothed.daily_rate > linear.daily_rate);
+    }
+
+    #[test]
+    fn test_convert_rescales_remainder() {
+        let mut forecast = forecast_month(
+            &june_series(10),
+            70.0,
+            date("2025-06-11"),
+            ForecastMethod::Linear,
+            0.3,
+        );
+        let remainder = forecast.projected_total - 70.0;
+        forecast.convert(140.0, 2.0);
+        assert!((forecast.projected_total - (140.0 + remainder * 2.0)).abs() < 1e-9);
+        assert_eq!(forecast.month_to_date, 140.0);
+    }
+
+    #[tokio::test]
+    async fn test_daily_cost_series_uses_local_days_and_enhanced_pricing() {
+        use crate::analysis::CostCalculationMode;
+        use crate::parser::jsonl::{Message, Usage};
+
+        let message = |timestamp: &str, cost_usd: Option<f64>| UsageData {
+            timestamp: Some(timestamp.to_string()),
+            uuid: None,
+            request_id: None,
+            session_id: None,
+            message: Some(Message {
+                id: None,
+                content: None,
+                model: Some("claude-sonnet-4-20250514".to_string()),
+                role: Some("assistant".to_string()),
+                usage: None,
+            }),
+            usage: Some(Usage {
+                input_tokens: Some(1_000_000),
+                output_tokens: Some(0),
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            }),
+            cost_usd,
+            cwd: None,
+            original_cwd: None,
+            git_branch: None,
+        };
+        // 20:00 UTC is already the next day in Tokyo (UTC+9)
+        let data = vec![
+            (
+                message("2025-06-01T20:00:00Z", Some(2.0)),
+                "app".to_string(),
+            ),
+            (message("2025-06-01T10:00:00Z", None), "app".to_string()),
+        ];
+
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let tz = TimezoneCalculator::new("Asia/Tokyo", 0).unwrap();
+        let series = daily_cost_series(
+            &tracker,
+            &data,
+            &mut pricing_manager,
+            &UsageFilter::default(),
+            &tz,
+        )
+        .await
+        .unwrap();
+
+        let (priced, _) = pricing_manager
+            .calculate_enhanced_cost("claude-sonnet-4-20250514", 1_000_000, 0, 0, 0)
+            .await;
+        assert_eq!(
+            series,
+            BTreeMap::from([(date("2025-06-01"), priced), (date("2025-06-02"), 2.0)])
+        );
+    }
+}
@@ -0,0 +1,373 @@
+// Hour-of-day / day-of-week usage heatmap in the configured timezone
+use crate::analysis::{TimezoneCalculator, UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::output::OutputFormat;
+use crate::output::export::{csv_number, to_csv};
+use crate::output::locale::current_locale;
+use crate::output::table::{TableType, apply_list_table_style_with_color, format_number};
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tabled::builder::Builder;
+
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Value accumulated in each heatmap cell
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatmapMetric {
+    Cost,
+    Tokens, // Input + output tokens
+}
+
+impl HeatmapMetric {
+    pub fn from_arg(metric: &str) -> Result<Self> {
+        match metric {
+            "cost" => Ok(HeatmapMetric::Cost),
+            "tokens" => Ok(HeatmapMetric::Tokens),
+            _ => anyhow::bail!("Invalid metric: {metric}. Must be 'cost' or 'tokens'"),
+        }
+    }
+}
+
+/// One weekday's 24 hourly values
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeatmapRow {
+    pub day: String,
+    pub hours: Vec<f64>, // Index = local hour 0-23
+    pub total: f64,
+}
+
+/// 7×24 grid of usage, Monday first, bucketed by local wall-clock time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageHeatmap {
+    pub metric: HeatmapMetric,
+    pub timezone: String,
+    pub rows: Vec<HeatmapRow>,
+    pub total: f64,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+}
+
+impl UsageHeatmap {
+    fn empty(metric: HeatmapMetric, timezone: &str) -> Self {
+        UsageHeatmap {
+            metric,
+            timezone: timezone.to_string(),
+            rows: WEEKDAYS
+                .iter()
+                .map(|day| HeatmapRow {
+                    day: day.to_string(),
+                    hours: vec![0.0; 24],
+                    total: 0.0,
+                })
+                .collect(),
+            total: 0.0,
+            daily_cost_usd: BTreeMap::new(),
+        }
+    }
+
+    /// Multiply every cell by `factor` (e.g. an effective currency rate)
+    pub fn scale(&mut self, factor: f64) {
+        for row in &mut self.rows {
+            for value in &mut row.hours {
+                *value *= factor;
+            }
+            row.total *= factor;
+        }
+        self.total *= factor;
+    }
+
+    /// Busiest cell as (weekday index, hour, value)
+    pub fn peak(&self) -> Option<(usize, usize, f64)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(day, row)| {
+                row.hours
+                    .iter()
+                    .enumerate()
+                    .map(move |(hour, value)| (day, hour, *value))
+            })
+            .filter(|(_, _, value)| *value > 0.0)
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    fn format_value(&self, value: f64, currency: &str, decimal_places: u8) -> String {
+        match self.metric {
+            HeatmapMetric::Cost => format_currency(value, currency, decimal_places),
+            HeatmapMetric::Tokens => format_number(value.round() as u64),
+        }
+    }
+
+    /// CSV with one row per weekday and one column per hour
+    pub fn to_csv(&self, decimal_places: u8) -> String {
+        let locale = current_locale();
+        let places = match self.metric {
+            HeatmapMetric::Cost => decimal_places as usize,
+            HeatmapMetric::Tokens => 0,
+        };
+        let header: Vec<String> = std::iter::once("day".to_string())
+            .chain((0..24).map(|hour| format!("{hour:02}")))
+            .chain(std::iter::once("total".to_string()))
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                std::iter::once(row.day.clone())
+                    .chain(
+                        row.hours
+                            .iter()
+                            .map(|value| csv_number(*value, places, &locale)),
+                    )
+                    .chain(std::iter::once(csv_number(row.total, places, &locale)))
+                    .collect()
+            })
+            .collect();
+        to_csv(&header, &rows)
+    }
+}
+
+/// Shade for a cell relative to the busiest cell
+fn shade(value: f64, peak: f64) -> &'static str {
+    if value <= 0.0 || peak <= 0.0 {
+        return "··";
+    }
+    let ratio = value / peak;
+    if ratio <= 0.25 {
+        "░░"
+    } else if ratio <= 0.5 {
+        "▒▒"
+    } else if ratio <= 0.75 {
+        "▓▓"
+    } else {
+        "██"
+    }
+}
+
+/// Bucket filtered usage by local weekday and hour
+///
+/// Costs use the embedded `costUSD` when present and enhanced pricing otherwise.
+pub async fn build_heatmap(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    timezone_calc: &TimezoneCalculator,
+    timezone_name: &str,
+    metric: HeatmapMetric,
+) -> Result<UsageHeatmap> {
+    let mut heatmap = UsageHeatmap::empty(metric, timezone_name);
+
+    for (message, _) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        // Messages without a usable timestamp can't be placed on the grid
+        let Some(message_time) = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+        if filter.since.is_some_and(|since| message_time < since)
+            || filter.until.is_some_and(|until| message_time > until)
+        {
+            continue;
+        }
+
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(message) {
+            continue;
+        }
+
+        let value = match metric {
+            HeatmapMetric::Cost => {
+                let cost = match message.cost_usd {
+                    Some(embedded_cost) => embedded_cost,
+                    None => {
+                        usage_tracker
+                            .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                            .await?
+                            .0
+                    }
+                };
+                *heatmap
+                    .daily_cost_usd
+                    .entry(message_time.date_naive())
+                    .or_insert(0.0) += cost;
+                cost
+            }
+            HeatmapMetric::Tokens => {
+                (usage.input_tokens.unwrap_or(0) + usage.output_tokens.unwrap_or(0)) as f64
+            }
+        };
+
+        let (day, hour) = timezone_calc.local_weekday_hour(message_time);
+        heatmap.rows[day].hours[hour] += value;
+        heatmap.rows[day].total += value;
+        heatmap.total += value;
+    }
+
+    Ok(heatmap)
+}
+
+impl OutputFormat for UsageHeatmap {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        let Some((peak_day, peak_hour, peak)) = self.peak() else {
+            return "No usage data found.".to_string();
+        };
+
+        let mut builder = Builder::default();
+        builder.push_record(
+            std::iter::once(String::new())
+                .chain((0..24).map(|hour| format!("{hour:02}")))
+                .chain(std::iter::once("Total".to_string())),
+        );
+        for row in &self.rows {
+            builder.push_record(
+                std::iter::once(row.day.clone())
+                    .chain(
+                        row.hours
+                            .iter()
+                            .map(|value| shade(*value, peak).to_string()),
+                    )
+                    .chain(std::iter::once(self.format_value(
+                        row.total,
+                        currency,
+                        decimal_places,
+                    ))),
+            );
+        }
+
+        let mut output =
+            apply_list_table_style_with_color(builder.build(), colored, TableType::Heatmap);
+        output.push_str(&format!(
+            "\n\n░ ≤25%  ▒ ≤50%  ▓ ≤75%  █ >75% of the busiest hour ({} {:02}:00, {}) · times in {}",
+            WEEKDAYS[peak_day],
+            peak_hour,
+            self.format_value(peak, currency, decimal_places),
+            self.timezone
+        ));
+        output.push_str(&format!(
+            "\nTotal: {}",
+            self.format_value(self.total, currency, decimal_places)
+        ));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::parser::jsonl::{Message, Usage};
+
+    fn message(timestamp: &str, cost_usd: f64) -> (UsageData, String) {
+        (
+            UsageData {
+                timestamp: Some(timestamp.to_string()),
+                uuid: None,
+                request_id: None,
+                session_id: None,
+                message: Some(Message {
+                    model: Some("claude-sonnet-4-20250514".to_string()),
+                    ..Default::default()
+                }),
+                usage: Some(Usage {
+                    input_tokens: Some(100),
+                    output_tokens: Some(50),
+                    cache_creation_input_tokens: None,
+                    cache_read_input_tokens: None,
+                }),
+                cost_usd: Some(cost_usd),
+                cwd: None,
+                original_cwd: None,
+                git_branch: None,
+            },
+            "alpha".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_heatmap_buckets_by_local_weekday_and_hour() {
+        let data = vec![
+            // Monday 2025-06-09 23:30 UTC is Tuesday 08:30 in Tokyo
+            message("2025-06-09T23:30:00Z", 2.0),
+            message("2025-06-09T23:45:00Z", 1.0),
+            // Sunday 2025-06-15 02:00 UTC is Sunday 11:00 in Tokyo
+            message("2025-06-15T02:00:00Z", 0.5),
+        ];
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let timezone_calc = TimezoneCalculator::new("Asia/Tokyo", 0).unwrap();
+        let filter = UsageFilter {
+            project_name: None,
+            model_name: None,
+            since: None,
+            until: None,
+            branch_name: None,
+        };
+
+        let heatmap = build_heatmap(
+            &tracker,
+            &data,
+            &mut pricing_manager,
+            &filter,
+            &timezone_calc,
+            "Asia/Tokyo",
+            HeatmapMetric::Cost,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(heatmap.rows[1].hours[8], 3.0);
+        assert_eq!(heatmap.rows[6].hours[11], 0.5);
+        assert_eq!(heatmap.rows[0].total, 0.0);
+        assert_eq!(heatmap.total, 3.5);
+        assert_eq!(heatmap.peak(), Some((1, 8, 3.0)));
+
+        let tokens = build_heatmap(
+            &tracker,
+            &data,
+            &mut pricing_manager,
+            &filter,
+            &timezone_calc,
+            "Asia/Tokyo",
+            HeatmapMetric::Tokens,
+        )
+        .await
+        .unwrap();
+        assert_eq!(tokens.rows[1].hours[8], 300.0);
+
+        let csv = heatmap.to_csv(2);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].starts_with("day,00,01"));
+        assert!(lines[2].starts_with("Tue,0.00,"));
+        assert!(lines[2].ends_with(",3.00"));
+    }
+}
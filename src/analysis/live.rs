@@ -0,0 +1,346 @@
+// Live cost meter: today's spend, recent burn rate and the active session
+use crate::analysis::{TimezoneCalculator, UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
+use crate::output::table::format_number;
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Burn rate is the cost of messages in this many trailing minutes, per hour
+pub const BURN_RATE_WINDOW_MINUTES: i64 = 60;
+
+/// Most recently active session today
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActiveSession {
+    pub session_id: String,
+    pub project_name: String,
+    pub cost: f64,
+    pub message_count: u64,
+    pub started_at: DateTime<Utc>,
+    pub last_message_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LiveModelCost {
+    pub model_name: String,
+    pub cost: f64,
+}
+
+/// Snapshot shown by `ccost live`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LiveSummary {
+    pub today_cost: f64,
+    pub today_messages: u64,
+    pub today_tokens: u64, // Input + output tokens
+    pub burn_rate_per_hour: f64,
+    pub active_session: Option<ActiveSession>,
+    pub last_message_at: Option<DateTime<Utc>>,
+    pub models: Vec<LiveModelCost>, // Most expensive first
+    #[serde(skip)]
+    pub last_message_local: Option<String>, // Wall-clock time in the configured timezone
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+}
+
+impl LiveSummary {
+    /// Multiply every amount by `factor` (e.g. an effective currency rate)
+    pub fn scale(&mut self, factor: f64) {
+        self.today_cost *= factor;
+        self.burn_rate_per_hour *= factor;
+        if let Some(session) = &mut self.active_session {
+            session.cost *= factor;
+        }
+        for model in &mut self.models {
+            model.cost *= factor;
+        }
+    }
+}
+
+/// Summarize filtered usage for the live meter
+///
+/// `filter` should cover today; the burn rate only counts messages in the last
+/// `BURN_RATE_WINDOW_MINUTES` before `now`. Costs use the embedded `costUSD`
+/// when present and enhanced pricing otherwise.
+pub async fn build_live_summary(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    timezone_calc: &TimezoneCalculator,
+    now: DateTime<Utc>,
+) -> Result<LiveSummary> {
+    let window_start = now - chrono::Duration::minutes(BURN_RATE_WINDOW_MINUTES);
+    let mut summary = LiveSummary {
+        today_cost: 0.0,
+        today_messages: 0,
+        today_tokens: 0,
+        burn_rate_per_hour: 0.0,
+        active_session: None,
+        last_message_at: None,
+        models: Vec::new(),
+        last_message_local: None,
+        daily_cost_usd: BTreeMap::new(),
+    };
+    let mut model_costs: HashMap<String, f64> = HashMap::new();
+    let mut sessions: HashMap<String, ActiveSession> = HashMap::new();
+    let mut window_cost = 0.0;
+
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let Some(message_time) = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+        if filter.since.is_some_and(|since| message_time < since)
+            || filter.until.is_some_and(|until| message_time > until)
+        {
+            continue;
+        }
+
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(message) {
+            continue;
+        }
+
+        let cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+
+        summary.today_cost += cost;
+        summary.today_messages += 1;
+        summary.today_tokens += usage.input_tokens.unwrap_or(0) + usage.output_tokens.unwrap_or(0);
+        summary.last_message_at = summary.last_message_at.max(Some(message_time));
+        *summary
+            .daily_cost_usd
+            .entry(message_time.date_naive())
+            .or_insert(0.0) += cost;
+        *model_costs.entry(model_name).or_insert(0.0) += cost;
+        if message_time > window_start && message_time <= now {
+            window_cost += cost;
+        }
+
+        if let Some(session_id) = &message.session_id {
+            let session = sessions
+                .entry(session_id.clone())
+                .or_insert_with(|| ActiveSession {
+                    session_id: session_id.clone(),
+                    project_name: project_name.clone(),
+                    cost: 0.0,
+                    message_count: 0,
+                    started_at: message_time,
+                    last_message_at: message_time,
+                });
+            session.cost += cost;
+            session.message_count += 1;
+            session.started_at = session.started_at.min(message_time);
+            session.last_message_at = session.last_message_at.max(message_time);
+        }
+    }
+
+    summary.burn_rate_per_hour = window_cost * 60.0 / BURN_RATE_WINDOW_MINUTES as f64;
+    summary.active_session = sessions
+        .into_values()
+        .max_by(|a, b| a.last_message_at.cmp(&b.last_message_at));
+    summary.last_message_local = summary.last_message_at.map(|time| {
+        timezone_calc
+            .local_datetime(time)
+            .format("%H:%M:%S")
+            .to_string()
+    });
+    summary.models = model_costs
+        .into_iter()
+        .map(|(model_name, cost)| LiveModelCost { model_name, cost })
+        .collect();
+    summary.models.sort_by(|a, b| {
+        b.cost
+            .total_cmp(&a.cost)
+            .then_with(|| a.model_name.cmp(&b.model_name))
+    });
+
+    Ok(summary)
+}
+
+fn label(text: &str, colored: bool) -> String {
+    if colored {
+        format!("\x1b[1m{text:<13}\x1b[0m")
+    } else {
+        format!("{text:<13}")
+    }
+}
+
+impl OutputFormat for LiveSummary {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.today_messages == 0 {
+            return "No usage yet today.".to_string();
+        }
+
+        let mut lines = vec![
+            format!(
+                "{}{} · {} messages · {} tokens",
+                label("Today", colored),
+                format_currency(self.today_cost, currency, decimal_places),
+                format_number(self.today_messages),
+                format_number(self.today_tokens)
+            ),
+            format!(
+                "{}{}/h (last {} min)",
+                label("Burn rate", colored),
+                format_currency(self.burn_rate_per_hour, currency, decimal_places),
+                BURN_RATE_WINDOW_MINUTES
+            ),
+        ];
+        if let Some(session) = &self.active_session {
+            let minutes =
+                (session.last_message_at - session.started_at).num_seconds() as f64 / 60.0;
+            let duration = if minutes >= 60.0 {
+                format!("{}h", format_decimal(minutes / 60.0, 1))
+            } else {
+                format!("{}m", format_decimal(minutes, 1))
+            };
+            lines.push(format!(
+                "{}{} · {} · {} · {} messages · {}",
+                label("Session", colored),
+                session.project_name,
+                session.session_id.chars().take(8).collect::<String>(),
+                format_currency(session.cost, currency, decimal_places),
+                format_number(session.message_count),
+                duration
+            ));
+        }
+        if let Some(time) = &self.last_message_local {
+            lines.push(format!("{}{}", label("Last message", colored), time));
+        }
+        lines.push(format!(
+            "{}{}",
+            label("Models", colored),
+            self.models
+                .iter()
+                .map(|model| format!(
+                    "{} {}",
+                    model.model_name,
+                    format_currency(model.cost, currency, decimal_places)
+                ))
+                .collect::<Vec<_>>()
+                .join(" · ")
+        ));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::parser::jsonl::{Message, Usage};
+    use chrono::TimeZone;
+
+    fn message(session: &str, timestamp: &str, model: &str, cost: f64) -> (UsageData, String) {
+        (
+            UsageData {
+                timestamp: Some(timestamp.to_string()),
+                uuid: None,
+                request_id: None,
+                session_id: Some(session.to_string()),
+                message: Some(Message {
+                    model: Some(model.to_string()),
+                    ..Default::default()
+                }),
+                usage: Some(Usage {
+                    input_tokens: Some(100),
+                    output_tokens: Some(50),
+                    cache_creation_input_tokens: None,
+                    cache_read_input_tokens: None,
+                }),
+                cost_usd: Some(cost),
+                cwd: None,
+                original_cwd: None,
+                git_branch: None,
+            },
+            "alpha".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_live_summary_burn_rate_and_active_session() {
+        let data = vec![
+            message("s1", "2025-06-09T08:00:00Z", "claude-opus-4-20250514", 4.0),
+            message(
+                "s2",
+                "2025-06-09T11:20:00Z",
+                "claude-sonnet-4-20250514",
+                1.0,
+            ),
+            message(
+                "s2",
+                "2025-06-09T11:50:00Z",
+                "claude-sonnet-4-20250514",
+                0.5,
+            ),
+            // Yesterday, outside the filter
+            message("s0", "2025-06-08T23:00:00Z", "claude-opus-4-20250514", 9.0),
+        ];
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let timezone_calc = TimezoneCalculator::new("UTC", 0).unwrap();
+        let filter = UsageFilter {
+            since: Some(Utc.with_ymd_and_hms(2025, 6, 9, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        let now = Utc.with_ymd_and_hms(2025, 6, 9, 12, 0, 0).unwrap();
+
+        let summary = build_live_summary(
+            &tracker,
+            &data,
+            &mut pricing_manager,
+            &filter,
+            &timezone_calc,
+            now,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.today_cost, 5.5);
+        assert_eq!(summary.today_messages, 3);
+        assert_eq!(summary.today_tokens, 450);
+        assert_eq!(summary.burn_rate_per_hour, 1.5);
+        let session = summary.active_session.as_ref().unwrap();
+        assert_eq!(session.session_id, "s2");
+        assert_eq!(session.cost, 1.5);
+        assert_eq!(summary.models[0].model_name, "claude-opus-4-20250514");
+        assert_eq!(summary.last_message_local.as_deref(), Some("11:50:00"));
+    }
+}
@@ -1,10 +1,23 @@
 // Analysis module
+pub mod alerts;
+pub mod anomalies;
+pub mod branches;
+pub mod budget;
+pub mod cache;
+pub mod commits;
+pub mod compare;
 pub mod conversations;
+pub mod forecast;
+pub mod heatmap;
+pub mod live;
 pub mod optimization;
+pub mod plans;
 pub mod projects;
+pub mod statusline;
 pub mod timeline;
 pub mod timezone;
 pub mod usage;
+pub mod whatif;
 
 // Re-export key types for easier access
 pub use timezone::TimezoneCalculator;
@@ -24,6 +37,12 @@ pub struct DailyUsage {
     pub total_cost_usd: f64,
     pub message_count: u64,
     pub projects_count: usize,
+    #[serde(skip)]
+    pub usage_date: Option<chrono::NaiveDate>, // Calendar date behind the formatted `date`
+    #[serde(skip)]
+    pub model_cost_usd: std::collections::BTreeMap<String, f64>, // USD cost per model, for charts
+    #[serde(skip)]
+    pub project_cost_usd: std::collections::BTreeMap<String, f64>, // USD cost per project, for charts
 }
 
 // Wrapper for daily usage vector to implement OutputFormat
@@ -121,6 +140,7 @@ mod integration_tests {
                 cost_usd: Some(0.75), // Has embedded cost
                 cwd: None,
                 original_cwd: None,
+                git_branch: None,
             },
             // Message without embedded cost
             UsageData {
@@ -141,6 +161,7 @@ mod integration_tests {
                 cost_usd: None, // No embedded cost - will calculate (currently 0.0)
                 cwd: None,
                 original_cwd: None,
+                git_branch: None,
             },
         ];
 
@@ -162,4 +183,28 @@ mod integration_tests {
         assert_eq!(usage.total_input_tokens, 300);
         assert_eq!(usage.total_output_tokens, 150);
     }
+
+    #[test]
+    fn test_summarize_pricing_sources() {
+        use crate::analysis::usage::summarize_pricing_sources;
+        use crate::models::PricingSource;
+
+        assert_eq!(summarize_pricing_sources(&[]), None);
+        assert_eq!(
+            summarize_pricing_sources(&[PricingSource::LiteLLM, PricingSource::LiteLLM]),
+            Some("Live (LiteLLM)".to_string())
+        );
+        assert_eq!(
+            summarize_pricing_sources(&[PricingSource::StaticFallback]),
+            Some("Static".to_string())
+        );
+        assert_eq!(
+            summarize_pricing_sources(&[
+                PricingSource::LiteLLM,
+                PricingSource::Embedded,
+                PricingSource::LiteLLM,
+            ]),
+            Some("Mixed (2 live, 1 embedded)".to_string())
+        );
+    }
 }
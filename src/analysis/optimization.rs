@@ -1,2 +1,556 @@
-// This file has been intentionally left empty to remove dead code warnings.
-// The optimization functionality is not currently used by the main application.
+// Rules-based cost optimization recommendations over recorded usage
+use crate::analysis::{UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::models::litellm::EnhancedModelPricing;
+use crate::output::OutputFormat;
+use crate::output::table::{TableType, apply_list_table_style_with_color, format_number};
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use tabled::builder::Builder;
+
+/// Model suggested in place of Opus for short turns
+pub const DOWNGRADE_MODEL: &str = "claude-sonnet-4-20250514";
+/// Turns producing at most this many output tokens count as short
+pub const SHORT_TURN_OUTPUT_TOKENS: u64 = 500;
+/// Fewest short Opus turns in a project before suggesting a switch
+pub const MIN_SHORT_TURNS: u64 = 5;
+/// Sessions writing at least this many cache tokens without reads are flagged
+pub const CACHE_HEAVY_WRITE_TOKENS: u64 = 50_000;
+/// Prompt size (input + cache tokens) above which a turn counts as long-context
+pub const LONG_CONTEXT_TOKENS: u64 = 150_000;
+/// Context a turn is assumed to carry after /compact
+pub const COMPACT_CONTEXT_TOKENS: u64 = 40_000;
+/// Fewest long-context turns in a session before suggesting /compact
+pub const MIN_LONG_TURNS: u64 = 3;
+/// Recommendations saving less than this (USD) are dropped as noise
+pub const MIN_SAVING_USD: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationKind {
+    ModelDowngrade,    // Short, low-output Opus turns that a cheaper model could handle
+    UnreadCacheWrites, // Sessions paying the cache write premium without ever reading
+    LongSession,       // Sessions carrying very large contexts turn after turn
+}
+
+/// One suggestion with the spend it targets and what it could save
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Recommendation {
+    pub kind: RecommendationKind,
+    pub project: String,
+    pub subject: String, // Model for downgrades, session ID otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_model: Option<String>,
+    pub message_count: u64, // Messages the rule matched
+    pub tokens: u64,        // Output tokens, cache write tokens or excess context tokens
+    pub current_cost: f64,
+    pub estimated_saving: f64,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+    #[serde(skip)]
+    pub daily_saving_usd: BTreeMap<NaiveDate, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecommendationList {
+    pub recommendations: Vec<Recommendation>, // Largest saving first
+    pub total_estimated_saving: f64,
+}
+
+impl RecommendationList {
+    fn new(recommendations: Vec<Recommendation>) -> Self {
+        let total_estimated_saving = recommendations
+            .iter()
+            .fold(0.0, |total, r| total + r.estimated_saving);
+        RecommendationList {
+            recommendations,
+            total_estimated_saving,
+        }
+    }
+
+    /// Recompute the total after amounts change (e.g. currency conversion)
+    pub fn refresh_total(&mut self) {
+        self.total_estimated_saving = self
+            .recommendations
+            .iter()
+            .fold(0.0, |total, r| total + r.estimated_saving);
+    }
+}
+
+/// Running totals for one rule's subject
+#[derive(Default)]
+struct Finding {
+    project: String,
+    message_count: u64,
+    tokens: u64,
+    current_cost: f64,
+    saving: f64,
+    cache_read_tokens: u64,
+    daily_cost_usd: BTreeMap<NaiveDate, f64>,
+    daily_saving_usd: BTreeMap<NaiveDate, f64>,
+}
+
+impl Finding {
+    fn record(&mut self, date: Option<NaiveDate>, tokens: u64, cost: f64, saving: f64) {
+        self.message_count += 1;
+        self.tokens += tokens;
+        self.current_cost += cost;
+        self.saving += saving;
+        if let Some(date) = date {
+            *self.daily_cost_usd.entry(date).or_insert(0.0) += cost;
+            *self.daily_saving_usd.entry(date).or_insert(0.0) += saving;
+        }
+    }
+
+    fn into_recommendation(
+        self,
+        kind: RecommendationKind,
+        subject: String,
+        suggested_model: Option<String>,
+    ) -> Recommendation {
+        Recommendation {
+            kind,
+            project: self.project,
+            subject,
+            suggested_model,
+            message_count: self.message_count,
+            tokens: self.tokens,
+            current_cost: self.current_cost,
+            estimated_saving: self.saving,
+            daily_cost_usd: self.daily_cost_usd,
+            daily_saving_usd: self.daily_saving_usd,
+        }
+    }
+}
+
+/// Cost of a turn's prompt side (input and cache tokens) in USD
+fn prompt_cost(pricing: &EnhancedModelPricing, input: u64, creation: u64, read: u64) -> f64 {
+    pricing.calculate_cost(input, 0, creation, read)
+}
+
+/// Run every rule over the filtered usage and rank the results by saving
+///
+/// Savings are estimates priced with `PricingManager`: a downgrade re-prices the
+/// matched turns on `DOWNGRADE_MODEL`, unread cache writes save the premium over
+/// plain input, and /compact trims each long turn's prompt to
+/// `COMPACT_CONTEXT_TOKENS`.
+pub async fn recommend_optimizations(
+    usage_tracker: &UsageTracker,
+    data: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+) -> Result<RecommendationList> {
+    let mut pricing_by_model: HashMap<String, EnhancedModelPricing> = HashMap::new();
+    let downgrade_pricing = pricing_manager.get_enhanced_pricing(DOWNGRADE_MODEL).await;
+
+    let mut short_turns: BTreeMap<(String, String), Finding> = BTreeMap::new();
+    let mut cache_writes: BTreeMap<String, Finding> = BTreeMap::new();
+    let mut long_turns: BTreeMap<String, Finding> = BTreeMap::new();
+
+    for (message, project_name) in data {
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let message_time = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok());
+
+        // Apply timestamp filtering
+        if let Some(message_time) = message_time
+            && (filter.since.is_some_and(|since| message_time < since)
+                || filter.until.is_some_and(|until| message_time > until))
+        {
+            continue;
+        }
+
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+        if !filter.matches_branch(message) {
+            continue;
+        }
+
+        let pricing = match pricing_by_model.get(&model_name) {
+            Some(pricing) => pricing.clone(),
+            None => {
+                let pricing = pricing_manager.get_enhanced_pricing(&model_name).await;
+                pricing_by_model.insert(model_name.clone(), pricing.clone());
+                pricing
+            }
+        };
+
+        let date = message_time.map(|t| t.date_naive());
+        let input_tokens = usage.input_tokens.unwrap_or(0);
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        let creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0);
+        let read_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        let cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+
+        // Rule: short, low-output Opus turns
+        if model_name.to_lowercase().contains("opus") && output_tokens <= SHORT_TURN_OUTPUT_TOKENS {
+            let downgraded = downgrade_pricing.calculate_cost(
+                input_tokens,
+                output_tokens,
+                creation_tokens,
+                read_tokens,
+            );
+            let finding = short_turns
+                .entry((project_name.clone(), model_name.clone()))
+                .or_default();
+            finding.project = project_name.clone();
+            finding.record(date, output_tokens, cost, (cost - downgraded).max(0.0));
+        }
+
+        let Some(session_id) = &message.session_id else {
+            continue;
+        };
+
+        // Rule: cache writes that the session never reads
+        let finding = cache_writes.entry(session_id.clone()).or_default();
+        finding.project = project_name.clone();
+        finding.cache_read_tokens += read_tokens;
+        let write_cost = creation_tokens as f64 * pricing.cache_write_cost_per_mtok() / 1_000_000.0;
+        let write_premium =
+            write_cost - creation_tokens as f64 * pricing.input_cost_per_mtok / 1_000_000.0;
+        finding.record(date, creation_tokens, write_cost, write_premium);
+
+        // Rule: long-context turns that /compact would shrink
+        let context_tokens = input_tokens + creation_tokens + read_tokens;
+        if context_tokens > LONG_CONTEXT_TOKENS {
+            let full = prompt_cost(&pricing, input_tokens, creation_tokens, read_tokens);
+            let excess = context_tokens - COMPACT_CONTEXT_TOKENS;
+            let saving = full * excess as f64 / context_tokens as f64;
+            let finding = long_turns.entry(session_id.clone()).or_default();
+            finding.project = project_name.clone();
+            finding.record(date, excess, cost, saving);
+        }
+    }
+
+    let mut recommendations = Vec::new();
+    for ((_, model_name), finding) in short_turns {
+        if finding.message_count >= MIN_SHORT_TURNS && finding.saving >= MIN_SAVING_USD {
+            recommendations.push(finding.into_recommendation(
+                RecommendationKind::ModelDowngrade,
+                model_name,
+                Some(DOWNGRADE_MODEL.to_string()),
+            ));
+        }
+    }
+    for (session_id, finding) in cache_writes {
+        if finding.cache_read_tokens == 0
+            && finding.tokens >= CACHE_HEAVY_WRITE_TOKENS
+            && finding.saving >= MIN_SAVING_USD
+        {
+            recommendations.push(finding.into_recommendation(
+                RecommendationKind::UnreadCacheWrites,
+                session_id,
+                None,
+            ));
+        }
+    }
+    for (session_id, finding) in long_turns {
+        if finding.message_count >= MIN_LONG_TURNS && finding.saving >= MIN_SAVING_USD {
+            recommendations.push(finding.into_recommendation(
+                RecommendationKind::LongSession,
+                session_id,
+                None,
+            ));
+        }
+    }
+
+    recommendations.sort_by(|a, b| {
+        b.estimated_saving
+            .total_cmp(&a.estimated_saving)
+            .then_with(|| a.kind.cmp(&b.kind))
+    });
+
+    Ok(RecommendationList::new(recommendations))
+}
+
+fn short_id(id: &str) -> String {
+    if id.len() > 12 {
+        format!("{}...", &id[..12])
+    } else {
+        id.to_string()
+    }
+}
+
+/// One-line, human-readable form of a recommendation
+pub fn describe_recommendation(
+    recommendation: &Recommendation,
+    currency: &str,
+    decimal_places: u8,
+) -> String {
+    let saving = format_currency(recommendation.estimated_saving, currency, decimal_places);
+    match recommendation.kind {
+        RecommendationKind::ModelDowngrade => format!(
+            "{} used for {} short turns (≤{} output tokens) in project {} — {} would save {saving}",
+            recommendation.subject,
+            format_number(recommendation.message_count),
+            SHORT_TURN_OUTPUT_TOKENS,
+            recommendation.project,
+            recommendation
+                .suggested_model
+                .as_deref()
+                .unwrap_or(DOWNGRADE_MODEL),
+        ),
+        RecommendationKind::UnreadCacheWrites => format!(
+            "Session {} ({}) wrote {} cache tokens and never read them — skipping the cache would save {saving}",
+            short_id(&recommendation.subject),
+            recommendation.project,
+            format_number(recommendation.tokens),
+        ),
+        RecommendationKind::LongSession => format!(
+            "Session {} ({}) ran {} turns above {} context tokens — /compact could save {saving}",
+            short_id(&recommendation.subject),
+            recommendation.project,
+            format_number(recommendation.message_count),
+            format_number(LONG_CONTEXT_TOKENS),
+        ),
+    }
+}
+
+impl OutputFormat for RecommendationList {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.recommendations.is_empty() {
+            return "No optimization opportunities found.".to_string();
+        }
+
+        let mut builder = Builder::default();
+        builder.push_record([
+            "Kind",
+            "Project",
+            "Subject",
+            "Messages",
+            "Cost",
+            "Est. Saving",
+        ]);
+        for recommendation in &self.recommendations {
+            let kind = match recommendation.kind {
+                RecommendationKind::ModelDowngrade => "model",
+                RecommendationKind::UnreadCacheWrites => "cache",
+                RecommendationKind::LongSession => "compact",
+            };
+            let subject = match recommendation.kind {
+                RecommendationKind::ModelDowngrade => format!(
+                    "{} → {}",
+                    recommendation.subject,
+                    recommendation
+                        .suggested_model
+                        .as_deref()
+                        .unwrap_or(DOWNGRADE_MODEL)
+                ),
+                _ => short_id(&recommendation.subject),
+            };
+            builder.push_record([
+                kind.to_string(),
+                recommendation.project.clone(),
+                subject,
+                format_number(recommendation.message_count),
+                format_currency(recommendation.current_cost, currency, decimal_places),
+                format_currency(recommendation.estimated_saving, currency, decimal_places),
+            ]);
+        }
+
+        let mut output =
+            apply_list_table_style_with_color(builder.build(), colored, TableType::Optimization);
+        output.push('\n');
+        for recommendation in &self.recommendations {
+            output.push_str(&format!(
+                "\n• {}",
+                describe_recommendation(recommendation, currency, decimal_places)
+            ));
+        }
+        output.push_str(&format!(
+            "\n\nEstimated total saving: {}",
+            format_currency(self.total_estimated_saving, currency, decimal_places)
+        ));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::parser::jsonl::{Message, Usage};
+
+    fn message(
+        timestamp: &str,
+        session: &str,
+        model: &str,
+        tokens: (u64, u64, u64, u64),
+    ) -> (UsageData, String) {
+        let (input, output, creation, read) = tokens;
+        (
+            UsageData {
+                timestamp: Some(timestamp.to_string()),
+                uuid: None,
+                request_id: None,
+                session_id: Some(session.to_string()),
+                message: Some(Message {
+                    model: Some(model.to_string()),
+                    ..Default::default()
+                }),
+                usage: Some(Usage {
+                    input_tokens: Some(input),
+                    output_tokens: Some(output),
+                    cache_creation_input_tokens: Some(creation),
+                    cache_read_input_tokens: Some(read),
+                }),
+                cost_usd: None,
+                cwd: None,
+                original_cwd: None,
+                git_branch: None,
+            },
+            "alpha".to_string(),
+        )
+    }
+
+    fn no_filter() -> UsageFilter {
+        UsageFilter {
+            project_name: None,
+            model_name: None,
+            since: None,
+            until: None,
+            branch_name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recommends_downgrade_for_short_opus_turns() {
+        let data: Vec<_> = (0..6)
+            .map(|i| {
+                message(
+                    &format!("2025-06-09T10:0{i}:00Z"),
+                    "s1",
+                    "claude-opus-4-20250514",
+                    (20_000, 200, 0, 0),
+                )
+            })
+            .collect();
+
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let list = recommend_optimizations(&tracker, &data, &mut pricing_manager, &no_filter())
+            .await
+            .unwrap();
+
+        assert_eq!(list.recommendations.len(), 1);
+        let downgrade = &list.recommendations[0];
+        assert_eq!(downgrade.kind, RecommendationKind::ModelDowngrade);
+        assert_eq!(downgrade.message_count, 6);
+
+        let opus = pricing_manager
+            .get_enhanced_pricing("claude-opus-4-20250514")
+            .await;
+        let sonnet = pricing_manager.get_enhanced_pricing(DOWNGRADE_MODEL).await;
+        let expected = 6.0
+            * (opus.calculate_cost(20_000, 200, 0, 0) - sonnet.calculate_cost(20_000, 200, 0, 0));
+        assert!((downgrade.estimated_saving - expected).abs() < 1e-9);
+        assert!((list.total_estimated_saving - expected).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_recommends_compact_and_skips_read_caches() {
+        let data = vec![
+            // Long context on every turn, and its cache writes are read back
+            message(
+                "2025-06-09T10:00:00Z",
+                "long",
+                DOWNGRADE_MODEL,
+                (1_000, 2_000, 200_000, 0),
+            ),
+            message(
+                "2025-06-09T10:01:00Z",
+                "long",
+                DOWNGRADE_MODEL,
+                (1_000, 2_000, 0, 200_000),
+            ),
+            message(
+                "2025-06-09T10:02:00Z",
+                "long",
+                DOWNGRADE_MODEL,
+                (1_000, 2_000, 0, 200_000),
+            ),
+        ];
+
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let list = recommend_optimizations(&tracker, &data, &mut pricing_manager, &no_filter())
+            .await
+            .unwrap();
+
+        let kinds: Vec<_> = list.recommendations.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, vec![RecommendationKind::LongSession]);
+        assert_eq!(list.recommendations[0].message_count, 3);
+        assert_eq!(
+            list.recommendations[0].tokens,
+            3 * (201_000 - COMPACT_CONTEXT_TOKENS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unread_cache_writes_match_cache_report_on_static_pricing() {
+        // A single write that the session never reads back
+        let data = vec![message(
+            "2025-06-09T10:00:00Z",
+            "s1",
+            DOWNGRADE_MODEL,
+            (1_000, 500, 1_000_000, 0),
+        )];
+
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let list = recommend_optimizations(&tracker, &data, &mut pricing_manager, &no_filter())
+            .await
+            .unwrap();
+        let cache = crate::analysis::cache::analyze_cache(
+            &tracker,
+            data,
+            &mut pricing_manager,
+            &no_filter(),
+            crate::analysis::cache::CacheGroup::Session,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(list.recommendations.len(), 1);
+        let unread = &list.recommendations[0];
+        assert_eq!(unread.kind, RecommendationKind::UnreadCacheWrites);
+        // Static Sonnet input is $3/MTok, so writes cost $3.75/MTok
+        assert!((unread.current_cost - 3.75).abs() < 1e-9);
+        assert!((unread.estimated_saving - 0.75).abs() < 1e-9);
+        assert!((cache.total.write_premium_usd - unread.estimated_saving).abs() < 1e-9);
+        assert!((cache.total.unread_write_cost_usd - unread.current_cost).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,312 @@
+// Subscription plan break-even analysis: API-equivalent cost vs flat monthly plans
+use crate::config::PlanConfig;
+use crate::models::currency::{format_currency, format_currency_signed};
+use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
+use crate::output::table::{
+    TableType, apply_list_table_style_with_color, apply_table_style_with_color, format_number,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tabled::builder::Builder;
+
+/// API-equivalent cost for one billing month
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthlyApiCost {
+    pub month: String, // YYYY-MM
+    pub message_count: u64,
+    pub api_cost_usd: f64,
+    pub partial: bool, // Month still in progress
+}
+
+/// What one plan would have saved in a given month (positive = plan is cheaper)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanMonth {
+    pub plan: String,
+    pub savings_usd: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthlyPlanComparison {
+    pub month: String,
+    pub message_count: u64,
+    pub api_cost_usd: f64,
+    pub partial: bool,
+    pub plans: Vec<PlanMonth>,
+}
+
+/// Aggregate verdict for one plan over the whole period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanSummary {
+    pub plan: String,
+    pub monthly_price_usd: f64,
+    pub average_api_cost_usd: f64,
+    pub break_even_ratio: f64, // Average API cost / plan price; >= 1.0 means the plan pays off
+    pub months_ahead: usize,   // Months where the plan was cheaper than API billing
+    pub average_savings_usd: f64,
+    pub total_savings_usd: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanComparison {
+    pub months: Vec<MonthlyPlanComparison>,
+    pub plans: Vec<PlanSummary>,
+    pub recommendation: String, // Largest plan the usage pays for, or "api" when none does
+}
+
+/// Compare monthly API-equivalent costs against each configured plan
+///
+/// Averages and the recommendation use complete months only, unless every
+/// month in range is still in progress.
+pub fn compare_plans(
+    monthly_costs: &[MonthlyApiCost],
+    plans: &BTreeMap<String, PlanConfig>,
+) -> PlanComparison {
+    // Present plans from cheapest to most expensive
+    let mut plans: Vec<(&String, &PlanConfig)> = plans.iter().collect();
+    plans.sort_by(|a, b| {
+        a.1.monthly_price_usd
+            .total_cmp(&b.1.monthly_price_usd)
+            .then_with(|| a.0.cmp(b.0))
+    });
+
+    let months: Vec<MonthlyPlanComparison> = monthly_costs
+        .iter()
+        .map(|month| MonthlyPlanComparison {
+            month: month.month.clone(),
+            message_count: month.message_count,
+            api_cost_usd: month.api_cost_usd,
+            partial: month.partial,
+            plans: plans
+                .iter()
+                .map(|(name, plan)| PlanMonth {
+                    plan: name.to_string(),
+                    savings_usd: month.api_cost_usd - plan.monthly_price_usd,
+                })
+                .collect(),
+        })
+        .collect();
+
+    let complete: Vec<&MonthlyApiCost> = monthly_costs.iter().filter(|m| !m.partial).collect();
+    let basis: Vec<&MonthlyApiCost> = if complete.is_empty() {
+        monthly_costs.iter().collect()
+    } else {
+        complete
+    };
+    let average_api_cost = if basis.is_empty() {
+        0.0
+    } else {
+        basis.iter().map(|m| m.api_cost_usd).sum::<f64>() / basis.len() as f64
+    };
+
+    let summaries: Vec<PlanSummary> = plans
+        .iter()
+        .map(|(name, plan)| {
+            let price = plan.monthly_price_usd;
+            PlanSummary {
+                plan: name.to_string(),
+                monthly_price_usd: price,
+                average_api_cost_usd: average_api_cost,
+                break_even_ratio: if price > 0.0 {
+                    average_api_cost / price
+                } else {
+                    0.0
+                },
+                months_ahead: basis.iter().filter(|m| m.api_cost_usd > price).count(),
+                average_savings_usd: average_api_cost - price,
+                total_savings_usd: basis.iter().map(|m| m.api_cost_usd - price).sum(),
+            }
+        })
+        .collect();
+
+    // Plan usage limits aren't modelled, so recommend the largest plan the usage still pays for
+    let recommendation = summaries
+        .iter()
+        .filter(|s| s.break_even_ratio >= 1.0)
+        .max_by(|a, b| a.monthly_price_usd.total_cmp(&b.monthly_price_usd))
+        .map(|s| s.plan.clone())
+        .unwrap_or_else(|| "api".to_string());
+
+    PlanComparison {
+        months,
+        plans: summaries,
+        recommendation,
+    }
+}
+
+impl OutputFormat for PlanComparison {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.months.is_empty() {
+            return "No usage data found.".to_string();
+        }
+
+        // Per-month history: one savings column per plan
+        let mut history = Builder::default();
+        let mut header = vec![
+            "Month".to_string(),
+            "Messages".to_string(),
+            "API Cost".to_string(),
+        ];
+        header.extend(self.plans.iter().map(|p| format!("vs {}", p.plan)));
+        history.push_record(header);
+
+        for month in &self.months {
+            let mut row = vec![
+                if month.partial {
+                    format!("{} (to date)", month.month)
+                } else {
+                    month.month.clone()
+                },
+                format_number(month.message_count),
+                format_currency(month.api_cost_usd, currency, decimal_places),
+            ];
+            row.extend(
+                month
+                    .plans
+                    .iter()
+                    .map(|p| format_currency_signed(p.savings_usd, currency, decimal_places)),
+            );
+            history.push_record(row);
+        }
+
+        let mut totals = vec![
+            "TOTAL".to_string(),
+            format_number(self.months.iter().map(|m| m.message_count).sum()),
+            format_currency(
+                self.months
+                    .iter()
+                    .fold(0.0, |total, m| total + m.api_cost_usd),
+                currency,
+                decimal_places,
+            ),
+        ];
+        totals.extend((0..self.plans.len()).map(|i| {
+            format_currency_signed(
+                self.months.iter().map(|m| m.plans[i].savings_usd).sum(),
+                currency,
+                decimal_places,
+            )
+        }));
+        history.push_record(totals);
+
+        let history_table =
+            apply_table_style_with_color(history.build(), colored, TableType::PlanHistory);
+
+        // Per-plan verdict
+        let mut summary = Builder::default();
+        summary.push_record([
+            "Plan",
+            "Price/Month",
+            "Avg API Cost",
+            "Break-even",
+            "Months Ahead",
+            "Avg Savings",
+        ]);
+        let basis_months = self.months.iter().filter(|m| !m.partial).count();
+        let basis_months = if basis_months == 0 {
+            self.months.len()
+        } else {
+            basis_months
+        };
+        for plan in &self.plans {
+            summary.push_record([
+                plan.plan.clone(),
+                format_currency(plan.monthly_price_usd, currency, decimal_places),
+                format_currency(plan.average_api_cost_usd, currency, decimal_places),
+                format!("{}%", format_decimal(plan.break_even_ratio * 100.0, 0)),
+                format!("{}/{}", plan.months_ahead, basis_months),
+                format_currency_signed(plan.average_savings_usd, currency, decimal_places),
+            ]);
+        }
+        let summary_table =
+            apply_list_table_style_with_color(summary.build(), colored, TableType::PlanSummary);
+
+        let verdict = match self.plans.iter().find(|p| p.plan == self.recommendation) {
+            Some(best) => format!(
+                "Recommendation: {} (API-equivalent cost averages {}% of its price)",
+                best.plan,
+                format_decimal(best.break_even_ratio * 100.0, 0)
+            ),
+            None => {
+                "Recommendation: API billing (no plan pays off at this usage level)".to_string()
+            }
+        };
+
+        format!("{history_table}\n\n{summary_table}\n\n{verdict}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plans() -> BTreeMap<String, PlanConfig> {
+        BTreeMap::from([
+            (
+                "pro".to_string(),
+                PlanConfig {
+                    monthly_price_usd: 20.0,
+                },
+            ),
+            (
+                "max-5x".to_string(),
+                PlanConfig {
+                    monthly_price_usd: 100.0,
+                },
+            ),
+        ])
+    }
+
+    fn month(month: &str, cost: f64, partial: bool) -> MonthlyApiCost {
+        MonthlyApiCost {
+            month: month.to_string(),
+            message_count: 10,
+            api_cost_usd: cost,
+            partial,
+        }
+    }
+
+    #[test]
+    fn test_compare_plans_ignores_partial_month() {
+        let costs = vec![
+            month("2025-04", 150.0, false),
+            month("2025-05", 90.0, false),
+            month("2025-06", 5.0, true),
+        ];
+
+        let comparison = compare_plans(&costs, &plans());
+
+        assert_eq!(comparison.months.len(), 3);
+        // Cheapest plan first
+        assert_eq!(comparison.months[0].plans[1].plan, "max-5x");
+        assert!((comparison.months[0].plans[1].savings_usd - 50.0).abs() < 1e-9);
+
+        let max = &comparison.plans[1];
+        assert!((max.average_api_cost_usd - 120.0).abs() < 1e-9);
+        assert!((max.break_even_ratio - 1.2).abs() < 1e-9);
+        assert_eq!(max.months_ahead, 1);
+        assert!((max.total_savings_usd - 40.0).abs() < 1e-9);
+
+        let pro = &comparison.plans[0];
+        assert!((pro.average_savings_usd - 100.0).abs() < 1e-9);
+        // Both plans pay off; the larger one is recommended
+        assert_eq!(comparison.recommendation, "max-5x");
+    }
+
+    #[test]
+    fn test_compare_plans_recommends_api_for_light_usage() {
+        let costs = vec![month("2025-05", 12.0, false)];
+        let comparison = compare_plans(&costs, &plans());
+        assert_eq!(comparison.recommendation, "api");
+        assert_eq!(comparison.plans[0].months_ahead, 0);
+    }
+}
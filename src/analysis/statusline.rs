@@ -0,0 +1,416 @@
+// Statusline: session, today and 5-hour block costs from an on-disk incremental cache
+use crate::analysis::UsageTracker;
+use crate::analysis::live::BURN_RATE_WINDOW_MINUTES;
+use crate::models::PricingManager;
+use crate::models::currency::format_currency;
+use crate::output::OutputFormat;
+use crate::parser::deduplication::DeduplicationEngine;
+use crate::parser::jsonl::{JsonlParser, UsageData};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bump when the cache layout changes; older caches are discarded
+const CACHE_VERSION: u32 = 1;
+
+/// Messages older than this are dropped from the cache
+pub const RETENTION_DAYS: i64 = 7;
+
+/// Length of a usage block, as in Claude's rolling usage limits
+pub const BLOCK_HOURS: i64 = 5;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct CachedFile {
+    offset: u64,
+    lines: usize,
+}
+
+/// Just enough of a message to total its cost
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedMessage {
+    pub hash: Option<String>, // Deduplication hash, when the message has one
+    pub timestamp: DateTime<Utc>,
+    pub session_id: Option<String>,
+    pub cost_usd: f64,
+}
+
+/// Read offsets and recent priced messages, persisted between statusline runs
+///
+/// Each run only parses lines appended since the previous run and prices only
+/// those, so it stays fast however large the projects directory grows.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StatuslineCache {
+    version: u32,
+    projects_dir: PathBuf,
+    files: BTreeMap<PathBuf, CachedFile>,
+    messages: Vec<CachedMessage>,
+}
+
+impl StatuslineCache {
+    /// Get path to the statusline cache file
+    pub fn get_cache_path() -> Result<PathBuf> {
+        let home = dirs::home_dir().context("Failed to determine home directory")?;
+        Ok(home
+            .join(".config")
+            .join("ccost")
+            .join("statusline_cache.json"))
+    }
+
+    /// Load the cache for `projects_dir`, starting fresh if it is missing or stale
+    pub fn load(projects_dir: &Path) -> Self {
+        Self::get_cache_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<Self>(&contents).ok())
+            .filter(|cache| cache.version == CACHE_VERSION && cache.projects_dir == projects_dir)
+            .unwrap_or_else(|| Self::new(projects_dir))
+    }
+
+    fn new(projects_dir: &Path) -> Self {
+        Self {
+            version: CACHE_VERSION,
+            projects_dir: projects_dir.to_path_buf(),
+            files: BTreeMap::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    /// Save the cache; written to a temporary file first since several sessions may race
+    pub fn save(&self) -> Result<()> {
+        let cache_path = Self::get_cache_path()?;
+
+        // Ensure parent directory exists
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory: {}", parent.display())
+            })?;
+        }
+
+        let contents =
+            serde_json::to_string(self).context("Failed to serialize statusline cache")?;
+        let temp_path = cache_path.with_extension(format!("json.{}", std::process::id()));
+        fs::write(&temp_path, contents)
+            .with_context(|| format!("Failed to write cache file: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &cache_path)
+            .with_context(|| format!("Failed to write cache file: {}", cache_path.display()))?;
+
+        Ok(())
+    }
+
+    /// Parse lines appended to every JSONL file since the last run
+    ///
+    /// `extra_file` is read too when it lives outside the projects directory
+    /// (e.g. the transcript of the current session). A file that shrank was
+    /// rewritten, so the whole cache is rebuilt.
+    pub fn read_appended(
+        &mut self,
+        parser: &JsonlParser,
+        extra_file: Option<&Path>,
+    ) -> Result<Vec<UsageData>> {
+        let mut paths = parser.find_jsonl_files()?;
+        if let Some(extra_file) = extra_file
+            && extra_file.is_file()
+            && !paths.iter().any(|path| path == extra_file)
+        {
+            paths.push(extra_file.to_path_buf());
+        }
+
+        let sizes: BTreeMap<PathBuf, u64> = paths
+            .into_iter()
+            .filter_map(|path| Some((path.clone(), fs::metadata(&path).ok()?.len())))
+            .collect();
+        let rewritten = self
+            .files
+            .iter()
+            .any(|(path, file)| sizes.get(path).is_some_and(|&size| size < file.offset));
+        if rewritten {
+            *self = Self::new(&self.projects_dir);
+        }
+        // Messages from deleted files stay counted; only their offsets are forgotten
+        self.files.retain(|path, _| sizes.contains_key(path));
+
+        let mut appended = Vec::new();
+        for (path, size) in sizes {
+            let file = self.files.entry(path.clone()).or_default();
+            if size == file.offset {
+                continue;
+            }
+            // Unreadable files are retried on the next run
+            if let Ok(lines) = parser.parse_file_from(&path, file.offset, file.lines, false) {
+                file.offset = lines.offset;
+                file.lines += lines.lines;
+                appended.extend(lines.messages);
+            }
+        }
+        Ok(appended)
+    }
+
+    /// Price and add new messages, skipping duplicates and anything past retention
+    pub async fn add_messages(
+        &mut self,
+        messages: Vec<UsageData>,
+        usage_tracker: &UsageTracker,
+        pricing_manager: &mut PricingManager,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let cutoff = now - Duration::days(RETENTION_DAYS);
+        let mut seen: HashSet<String> = self
+            .messages
+            .iter()
+            .filter_map(|message| message.hash.clone())
+            .collect();
+
+        for message in messages {
+            let Some(usage) = &message.usage else {
+                continue;
+            };
+            let Some(timestamp) = message
+                .timestamp
+                .as_deref()
+                .and_then(|ts| usage_tracker.parse_timestamp(ts).ok())
+            else {
+                continue;
+            };
+            if timestamp < cutoff {
+                continue;
+            }
+
+            let message_id = message.message.as_ref().and_then(|m| m.id.clone());
+            let hash = DeduplicationEngine::generate_hash(
+                &message_id,
+                &message.request_id,
+                &message.session_id,
+            );
+            if let Some(hash) = &hash
+                && !seen.insert(hash.clone())
+            {
+                continue;
+            }
+
+            let cost_usd = match message.cost_usd {
+                Some(embedded_cost) => embedded_cost,
+                None => {
+                    let model_name = message
+                        .message
+                        .as_ref()
+                        .and_then(|m| m.model.as_deref())
+                        .unwrap_or("unknown");
+                    usage_tracker
+                        .calculate_enhanced_cost(usage, model_name, pricing_manager)
+                        .await?
+                        .0
+                }
+            };
+
+            self.messages.push(CachedMessage {
+                hash,
+                timestamp,
+                session_id: message.session_id.clone(),
+                cost_usd,
+            });
+        }
+
+        self.messages.retain(|message| message.timestamp >= cutoff);
+        Ok(())
+    }
+
+    /// Totals for the statusline
+    ///
+    /// `today_start` is the start of the current day in the configured
+    /// timezone. Session cost only covers the retention window.
+    pub fn summarize(
+        &self,
+        session_id: Option<&str>,
+        today_start: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> StatuslineSummary {
+        let window_start = now - Duration::minutes(BURN_RATE_WINDOW_MINUTES);
+        let session_cost = session_id.map(|session_id| {
+            self.messages
+                .iter()
+                .filter(|m| m.session_id.as_deref() == Some(session_id))
+                .map(|m| m.cost_usd)
+                .sum()
+        });
+        let today_cost = self
+            .messages
+            .iter()
+            .filter(|m| m.timestamp >= today_start && m.timestamp <= now)
+            .map(|m| m.cost_usd)
+            .sum();
+        let window_cost: f64 = self
+            .messages
+            .iter()
+            .filter(|m| m.timestamp > window_start && m.timestamp <= now)
+            .map(|m| m.cost_usd)
+            .sum();
+
+        let (block_cost, block_ends_at) = match active_block(&self.messages, now) {
+            Some((cost, ends_at)) => (Some(cost), Some(ends_at)),
+            None => (None, None),
+        };
+
+        StatuslineSummary {
+            model: None,
+            session_cost,
+            today_cost,
+            block_cost,
+            block_ends_at,
+            block_minutes_left: block_ends_at.map(|ends_at| (ends_at - now).num_minutes()),
+            burn_rate_per_hour: window_cost * 60.0 / BURN_RATE_WINDOW_MINUTES as f64,
+        }
+    }
+}
+
+/// Cost and end of the block containing `now`, if there is one
+///
+/// A block starts at the hour of the first message after a gap and lasts
+/// `BLOCK_HOURS`; the first message after it ends (or after a gap that long)
+/// opens the next one.
+fn active_block(messages: &[CachedMessage], now: DateTime<Utc>) -> Option<(f64, DateTime<Utc>)> {
+    let block_length = Duration::hours(BLOCK_HOURS);
+    let mut sorted: Vec<&CachedMessage> = messages.iter().filter(|m| m.timestamp <= now).collect();
+    sorted.sort_by_key(|m| m.timestamp);
+
+    let mut block: Option<(DateTime<Utc>, f64)> = None; // (start, cost)
+    let mut last_timestamp = None;
+    for message in sorted {
+        let starts_new = match (block, last_timestamp) {
+            (Some((start, _)), Some(last)) => {
+                message.timestamp >= start + block_length
+                    || message.timestamp - last >= block_length
+            }
+            _ => true,
+        };
+        if starts_new {
+            let start = message
+                .timestamp
+                .duration_trunc(Duration::hours(1))
+                .unwrap_or(message.timestamp);
+            block = Some((start, 0.0));
+        }
+        if let Some((_, cost)) = &mut block {
+            *cost += message.cost_usd;
+        }
+        last_timestamp = Some(message.timestamp);
+    }
+
+    let (start, cost) = block?;
+    let ends_at = start + block_length;
+    (now < ends_at).then_some((cost, ends_at))
+}
+
+/// One-line summary printed by `ccost statusline`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatuslineSummary {
+    pub model: Option<String>,     // Display name from Claude Code, if given
+    pub session_cost: Option<f64>, // Only with a session id on stdin
+    pub today_cost: f64,
+    pub block_cost: Option<f64>, // Only while a block is active
+    pub block_ends_at: Option<DateTime<Utc>>,
+    pub block_minutes_left: Option<i64>,
+    pub burn_rate_per_hour: f64,
+}
+
+impl StatuslineSummary {
+    /// Multiply every amount by `factor` (e.g. an effective currency rate)
+    pub fn scale(&mut self, factor: f64) {
+        self.today_cost *= factor;
+        self.burn_rate_per_hour *= factor;
+        if let Some(cost) = &mut self.session_cost {
+            *cost *= factor;
+        }
+        if let Some(cost) = &mut self.block_cost {
+            *cost *= factor;
+        }
+    }
+}
+
+impl OutputFormat for StatuslineSummary {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        let money = |amount: f64| format_currency(amount, currency, decimal_places);
+        let mut parts = Vec::new();
+        if let Some(model) = &self.model {
+            parts.push(if colored {
+                format!("\x1b[1m{model}\x1b[0m")
+            } else {
+                model.clone()
+            });
+        }
+        if let Some(cost) = self.session_cost {
+            parts.push(format!("session {}", money(cost)));
+        }
+        parts.push(format!("today {}", money(self.today_cost)));
+        if let (Some(cost), Some(minutes)) = (self.block_cost, self.block_minutes_left) {
+            parts.push(format!(
+                "block {} ({}h{:02}m left)",
+                money(cost),
+                minutes / 60,
+                minutes % 60
+            ));
+        }
+        parts.push(format!("{}/h", money(self.burn_rate_per_hour)));
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn cached(hour: u32, minute: u32, session: &str, cost: f64) -> CachedMessage {
+        CachedMessage {
+            hash: None,
+            timestamp: Utc.with_ymd_and_hms(2025, 6, 9, hour, minute, 0).unwrap(),
+            session_id: Some(session.to_string()),
+            cost_usd: cost,
+        }
+    }
+
+    #[test]
+    fn test_summarize_session_today_and_active_block() {
+        let mut cache = StatuslineCache::new(Path::new("/tmp/projects"));
+        cache.messages = vec![
+            // Yesterday's block, long over
+            CachedMessage {
+                timestamp: Utc.with_ymd_and_hms(2025, 6, 8, 22, 0, 0).unwrap(),
+                ..cached(0, 0, "s0", 9.0)
+            },
+            // First block of the day: 06:00-11:00
+            cached(6, 30, "s1", 2.0),
+            // A gap-free message after 11:00 opens the next block at 11:00
+            cached(11, 15, "s2", 1.0),
+            cached(12, 40, "s2", 0.5),
+        ];
+        let today_start = Utc.with_ymd_and_hms(2025, 6, 9, 0, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 6, 9, 13, 0, 0).unwrap();
+
+        let summary = cache.summarize(Some("s2"), today_start, now);
+        assert_eq!(summary.session_cost, Some(1.5));
+        assert_eq!(summary.today_cost, 3.5);
+        assert_eq!(summary.block_cost, Some(1.5));
+        assert_eq!(summary.block_minutes_left, Some(180));
+        assert_eq!(summary.burn_rate_per_hour, 0.5);
+        assert_eq!(
+            summary.to_table_with_currency_and_color("USD", 2, false),
+            "session $1.50 · today $3.50 · block $1.50 (3h00m left) · $0.50/h"
+        );
+
+        // Five quiet hours later the block has ended
+        let later = Utc.with_ymd_and_hms(2025, 6, 9, 17, 45, 0).unwrap();
+        assert_eq!(cache.summarize(None, today_start, later).block_cost, None);
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use chrono::{DateTime, Datelike, LocalResult, NaiveDate, Offset, TimeZone, Timelike, Utc};
 use chrono_tz::Tz;
 use std::str::FromStr;
 
@@ -25,6 +25,52 @@ impl TimezoneCalculator {
         })
     }
 
+    /// Get the local calendar date a timestamp belongs to, honoring the daily cutoff hour
+    pub fn local_date(&self, timestamp: DateTime<Utc>) -> NaiveDate {
+        let local = timestamp.with_timezone(&self.timezone);
+        (local - chrono::Duration::hours(self.daily_cutoff_hour.into())).date_naive()
+    }
+
+    /// Convert a timestamp to wall-clock time in the configured timezone
+    pub fn local_datetime(&self, timestamp: DateTime<Utc>) -> DateTime<Tz> {
+        timestamp.with_timezone(&self.timezone)
+    }
+
+    /// Get the local weekday (0 = Monday) and wall-clock hour of a timestamp
+    ///
+    /// Unlike `local_date`, this ignores the daily cutoff hour.
+    pub fn local_weekday_hour(&self, timestamp: DateTime<Utc>) -> (usize, usize) {
+        let local = timestamp.with_timezone(&self.timezone);
+        (
+            local.weekday().num_days_from_monday() as usize,
+            local.hour() as usize,
+        )
+    }
+
+    /// Get the start of a local calendar date, honoring the daily cutoff hour
+    pub fn day_start(&self, date: NaiveDate) -> DateTime<Utc> {
+        let start_time = date
+            .and_hms_opt(self.daily_cutoff_hour.into(), 0, 0)
+            .expect("Invalid daily cutoff hour");
+
+        match self.timezone.from_local_datetime(&start_time) {
+            LocalResult::Single(start) | LocalResult::Ambiguous(start, _) => {
+                start.with_timezone(&Utc)
+            }
+            // A DST gap skips the cutoff (clocks jump from 00:00 to 01:00 in
+            // America/Santiago): the day starts at the jump, i.e. the cutoff
+            // at the offset in force the day before
+            LocalResult::None => {
+                let offset = self
+                    .timezone
+                    .offset_from_local_datetime(&(start_time - chrono::Duration::days(1)))
+                    .earliest()
+                    .map_or(0, |offset| offset.fix().local_minus_utc());
+                (start_time - chrono::Duration::seconds(offset.into())).and_utc()
+            }
+        }
+    }
+
     /// Get the start of today in the configured timezone
     pub fn today_start(&self) -> DateTime<Utc> {
         let now = Utc::now().with_timezone(&self.timezone);
@@ -131,3 +177,43 @@ impl TimezoneCalculator {
             .with_timezone(&Utc)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_day_start_in_dst_gap() {
+        // Chile skips 00:00-01:00 local on 2025-09-07 (UTC-4 to UTC-3)
+        let santiago = TimezoneCalculator::new("America/Santiago", 0).unwrap();
+        let gap_day = NaiveDate::from_ymd_opt(2025, 9, 7).unwrap();
+        let start = santiago.day_start(gap_day);
+        assert_eq!(start, utc("2025-09-07T04:00:00Z"));
+        assert_eq!(santiago.local_date(start), gap_day);
+        assert_eq!(
+            santiago.local_date(start - chrono::Duration::seconds(1)),
+            gap_day.pred_opt().unwrap()
+        );
+
+        // Cuba skips midnight on 2025-03-09 (UTC-5 to UTC-4)
+        let havana = TimezoneCalculator::new("America/Havana", 0).unwrap();
+        assert_eq!(
+            havana.day_start(NaiveDate::from_ymd_opt(2025, 3, 9).unwrap()),
+            utc("2025-03-09T05:00:00Z")
+        );
+
+        // Ordinary days and cutoff hours are unaffected
+        assert_eq!(
+            santiago.day_start(NaiveDate::from_ymd_opt(2025, 9, 8).unwrap()),
+            utc("2025-09-08T03:00:00Z")
+        );
+        let cutoff = TimezoneCalculator::new("America/Santiago", 6).unwrap();
+        assert_eq!(cutoff.day_start(gap_day), utc("2025-09-07T09:00:00Z"));
+    }
+}
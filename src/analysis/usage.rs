@@ -1,9 +1,10 @@
+use crate::analysis::branches::message_branch;
 use crate::models::{PricingManager, PricingSource};
 use crate::parser::jsonl::{Usage, UsageData};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CostCalculationMode {
@@ -22,6 +23,10 @@ pub struct ProjectUsage {
     pub message_count: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pricing_source: Option<String>,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // USD cost per UTC day, for dated currency conversion
+    #[serde(skip)]
+    pub branch_model_usage: HashMap<Option<String>, HashMap<String, ModelUsage>>, // model_usage split by git branch, for --branch
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -33,6 +38,8 @@ pub struct ModelUsage {
     pub cache_read_tokens: u64,
     pub cost_usd: f64,
     pub message_count: u64,
+    #[serde(skip)]
+    pub daily_cost_usd: BTreeMap<NaiveDate, f64>, // USD cost per UTC day, for dated currency conversion
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -41,6 +48,29 @@ pub struct UsageFilter {
     pub model_name: Option<String>,
     pub since: Option<DateTime<Utc>>,
     pub until: Option<DateTime<Utc>>,
+    pub branch_name: Option<String>, // Exact git branch (--branch)
+}
+
+impl ModelUsage {
+    /// Add one message's tokens and cost
+    fn record(&mut self, usage: &Usage, cost: f64, date: Option<NaiveDate>) {
+        self.input_tokens += usage.input_tokens.unwrap_or(0);
+        self.output_tokens += usage.output_tokens.unwrap_or(0);
+        self.cache_creation_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
+        self.cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0);
+        self.message_count += 1;
+        self.cost_usd += cost;
+        if let Some(date) = date {
+            *self.daily_cost_usd.entry(date).or_insert(0.0) += cost;
+        }
+    }
+}
+
+impl UsageFilter {
+    /// Whether the message was written on the `--branch` branch (always true without one)
+    pub fn matches_branch(&self, message: &UsageData) -> bool {
+        self.branch_name.is_none() || message_branch(message) == self.branch_name.as_deref()
+    }
 }
 
 pub struct UsageTracker {
@@ -186,6 +216,20 @@ impl UsageTracker {
 
             project_usage.total_cost_usd += cost;
             model_usage.cost_usd += cost;
+            if let Some(date) = self.message_date(&message) {
+                *project_usage.daily_cost_usd.entry(date).or_insert(0.0) += cost;
+                *model_usage.daily_cost_usd.entry(date).or_insert(0.0) += cost;
+            }
+            project_usage
+                .branch_model_usage
+                .entry(message_branch(&message).map(str::to_string))
+                .or_default()
+                .entry(model_name.clone())
+                .or_insert_with(|| ModelUsage {
+                    model_name: model_name.clone(),
+                    ..Default::default()
+                })
+                .record(usage, cost, self.message_date(&message));
         }
 
         Ok(projects.into_values().collect())
@@ -199,7 +243,7 @@ impl UsageTracker {
         filter: &UsageFilter,
     ) -> Result<(Vec<ProjectUsage>, Option<String>)> {
         let mut projects: HashMap<String, ProjectUsage> = HashMap::new();
-        let mut pricing_sources: Vec<PricingSource> = Vec::new();
+        let mut pricing_sources: HashMap<String, Vec<PricingSource>> = HashMap::new();
 
         for (message, project_name) in enhanced_data {
             // Apply timestamp filtering
@@ -269,7 +313,7 @@ impl UsageTracker {
                 CostCalculationMode::Auto => {
                     // Use embedded cost if available, otherwise calculate with enhanced pricing
                     if let Some(embedded_cost) = message.cost_usd {
-                        (embedded_cost, PricingSource::StaticFallback) // Treat embedded as static
+                        (embedded_cost, PricingSource::Embedded)
                     } else {
                         self.calculate_enhanced_cost(usage, &model_name, pricing_manager)
                             .await?
@@ -279,40 +323,48 @@ impl UsageTracker {
 
             project_usage.total_cost_usd += cost;
             model_usage.cost_usd += cost;
-            pricing_sources.push(source);
+            if let Some(date) = self.message_date(&message) {
+                *project_usage.daily_cost_usd.entry(date).or_insert(0.0) += cost;
+                *model_usage.daily_cost_usd.entry(date).or_insert(0.0) += cost;
+            }
+            project_usage
+                .branch_model_usage
+                .entry(message_branch(&message).map(str::to_string))
+                .or_default()
+                .entry(model_name.clone())
+                .or_insert_with(|| ModelUsage {
+                    model_name: model_name.clone(),
+                    ..Default::default()
+                })
+                .record(usage, cost, self.message_date(&message));
+            pricing_sources
+                .entry(project_name.clone())
+                .or_default()
+                .push(source);
         }
 
-        // Determine overall pricing source
-        let overall_source = if pricing_sources.is_empty() {
-            None
-        } else {
-            let live_count = pricing_sources
-                .iter()
-                .filter(|&s| *s == PricingSource::LiteLLM)
-                .count();
-            let total_count = pricing_sources.len();
-
-            if live_count == total_count {
-                Some("Live (LiteLLM)".to_string())
-            } else if live_count > 0 {
-                Some(format!(
-                    "Mixed ({} live, {} static)",
-                    live_count,
-                    total_count - live_count
-                ))
-            } else {
-                Some("Static".to_string())
-            }
-        };
+        // Summarize which sources priced each project's messages, and all messages overall
+        let all_sources: Vec<PricingSource> = pricing_sources.values().flatten().cloned().collect();
+        let overall_source = summarize_pricing_sources(&all_sources);
 
-        // Set pricing source for each project
         for project in projects.values_mut() {
-            project.pricing_source = overall_source.clone();
+            project.pricing_source = pricing_sources
+                .get(&project.project_name)
+                .and_then(|sources| summarize_pricing_sources(sources));
         }
 
         Ok((projects.into_values().collect(), overall_source))
     }
 
+    /// UTC calendar date of a message, if it has a parseable timestamp
+    fn message_date(&self, message: &UsageData) -> Option<NaiveDate> {
+        message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| self.parse_timestamp(ts).ok())
+            .map(|time| time.date_naive())
+    }
+
     fn extract_model_from_message(&self, message: &UsageData) -> String {
         message
             .message
@@ -340,6 +392,71 @@ impl UsageTracker {
     }
 }
 
+/// Describe the mix of pricing sources used for a set of messages
+///
+/// Returns `None` for an empty set, a single label when one source priced every
+/// message, and a "Mixed (...)" breakdown otherwise.
+pub fn summarize_pricing_sources(sources: &[PricingSource]) -> Option<String> {
+    if sources.is_empty() {
+        return None;
+    }
+
+    let count = |kind: PricingSource| sources.iter().filter(|&s| *s == kind).count();
+    let live_count = count(PricingSource::LiteLLM);
+    let static_count = count(PricingSource::StaticFallback);
+    let embedded_count = count(PricingSource::Embedded);
+    let override_count = count(PricingSource::Override);
+    let total_count = sources.len();
+
+    if live_count == total_count {
+        Some("Live (LiteLLM)".to_string())
+    } else if static_count == total_count {
+        Some("Static".to_string())
+    } else if embedded_count == total_count {
+        Some("Embedded (costUSD)".to_string())
+    } else if override_count == total_count {
+        Some("Override (config)".to_string())
+    } else {
+        let parts: Vec<String> = [
+            (live_count, "live"),
+            (static_count, "static"),
+            (embedded_count, "embedded"),
+            (override_count, "override"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{n} {label}"))
+        .collect();
+        Some(format!("Mixed ({})", parts.join(", ")))
+    }
+}
+
+/// Combine each project's per-model usage into one row per model
+pub fn merge_model_usage(projects: &[ProjectUsage]) -> Vec<ModelUsage> {
+    let mut models: BTreeMap<String, ModelUsage> = BTreeMap::new();
+    for model_usage in projects.iter().flat_map(|p| p.model_usage.values()) {
+        let merged = models
+            .entry(model_usage.model_name.clone())
+            .or_insert_with(|| ModelUsage {
+                model_name: model_usage.model_name.clone(),
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                cost_usd: 0.0,
+                message_count: 0,
+                daily_cost_usd: BTreeMap::new(),
+            });
+        merged.input_tokens += model_usage.input_tokens;
+        merged.output_tokens += model_usage.output_tokens;
+        merged.cache_creation_tokens += model_usage.cache_creation_tokens;
+        merged.cache_read_tokens += model_usage.cache_read_tokens;
+        merged.cost_usd += model_usage.cost_usd;
+        merged.message_count += model_usage.message_count;
+    }
+    models.into_values().collect()
+}
+
 impl Default for ProjectUsage {
     fn default() -> Self {
         Self {
@@ -352,6 +469,8 @@ impl Default for ProjectUsage {
             model_usage: HashMap::new(),
             message_count: 0,
             pricing_source: None,
+            daily_cost_usd: BTreeMap::new(),
+            branch_model_usage: HashMap::new(),
         }
     }
 }
@@ -366,6 +485,7 @@ impl Default for ModelUsage {
             cache_read_tokens: 0,
             cost_usd: 0.0,
             message_count: 0,
+            daily_cost_usd: BTreeMap::new(),
         }
     }
 }
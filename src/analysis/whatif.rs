@@ -0,0 +1,351 @@
+// What-if re-pricing: cost recorded usage as if it had run on another model
+use crate::analysis::{UsageFilter, UsageTracker};
+use crate::models::PricingManager;
+use crate::output::OutputFormat;
+use crate::output::locale::format_decimal;
+use crate::output::table::{TableType, WhatIfRow, apply_table_style_with_color, format_number};
+use crate::parser::jsonl::UsageData;
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Original vs hypothetical cost for one project/model pair
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WhatIfUsage {
+    pub project_name: String,
+    pub model_name: String,
+    pub target_model: String,
+    pub message_count: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub original_cost_usd: f64,
+    pub whatif_cost_usd: f64,
+    pub difference_usd: f64,
+    #[serde(skip)]
+    pub daily_original_usd: BTreeMap<NaiveDate, f64>, // For dated currency conversion
+    #[serde(skip)]
+    pub daily_whatif_usd: BTreeMap<NaiveDate, f64>,
+}
+
+// Wrapper for what-if results to implement OutputFormat
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatIfUsageList(pub Vec<WhatIfUsage>);
+
+/// Recompute every message's cost with `target_model` pricing, keeping token counts
+///
+/// The original cost follows the normal rules (embedded `costUSD` first, then
+/// enhanced pricing for the recorded model); the hypothetical cost always uses
+/// the target model's rates.
+pub async fn reprice_usage(
+    usage_tracker: &UsageTracker,
+    enhanced_data: Vec<(UsageData, String)>,
+    pricing_manager: &mut PricingManager,
+    filter: &UsageFilter,
+    target_model: &str,
+) -> Result<WhatIfUsageList> {
+    let target_pricing = pricing_manager.get_enhanced_pricing(target_model).await;
+    let mut entries: HashMap<(String, String), WhatIfUsage> = HashMap::new();
+
+    for (message, project_name) in enhanced_data {
+        let message_time = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| usage_tracker.parse_timestamp(ts).ok());
+
+        // Apply timestamp filtering
+        if let Some(message_time) = message_time
+            && (filter.since.is_some_and(|since| message_time < since)
+                || filter.until.is_some_and(|until| message_time > until))
+        {
+            continue;
+        }
+
+        // Skip messages without usage data
+        let usage = match &message.usage {
+            Some(usage) => usage,
+            None => continue,
+        };
+
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        if let Some(ref filter_model) = filter.model_name
+            && model_name != *filter_model
+        {
+            continue;
+        }
+
+        if !filter.matches_branch(&message) {
+            continue;
+        }
+
+        let input_tokens = usage.input_tokens.unwrap_or(0);
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        let cache_creation_tokens = usage.cache_creation_input_tokens.unwrap_or(0);
+        let cache_read_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+
+        let original_cost = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => {
+                usage_tracker
+                    .calculate_enhanced_cost(usage, &model_name, pricing_manager)
+                    .await?
+                    .0
+            }
+        };
+        let whatif_cost = target_pricing.calculate_cost(
+            input_tokens,
+            output_tokens,
+            cache_creation_tokens,
+            cache_read_tokens,
+        );
+
+        let entry = entries
+            .entry((project_name.clone(), model_name.clone()))
+            .or_insert_with(|| WhatIfUsage {
+                project_name: project_name.clone(),
+                model_name: model_name.clone(),
+                target_model: target_model.to_string(),
+                message_count: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+                original_cost_usd: 0.0,
+                whatif_cost_usd: 0.0,
+                difference_usd: 0.0,
+                daily_original_usd: BTreeMap::new(),
+                daily_whatif_usd: BTreeMap::new(),
+            });
+
+        entry.message_count += 1;
+        entry.input_tokens += input_tokens;
+        entry.output_tokens += output_tokens;
+        entry.cache_creation_tokens += cache_creation_tokens;
+        entry.cache_read_tokens += cache_read_tokens;
+        entry.original_cost_usd += original_cost;
+        entry.whatif_cost_usd += whatif_cost;
+        entry.difference_usd = entry.whatif_cost_usd - entry.original_cost_usd;
+        if let Some(message_time) = message_time {
+            let date = message_time.date_naive();
+            *entry.daily_original_usd.entry(date).or_insert(0.0) += original_cost;
+            *entry.daily_whatif_usd.entry(date).or_insert(0.0) += whatif_cost;
+        }
+    }
+
+    let mut results: Vec<WhatIfUsage> = entries.into_values().collect();
+    results.sort_by(|a, b| {
+        a.project_name
+            .cmp(&b.project_name)
+            .then_with(|| a.model_name.cmp(&b.model_name))
+    });
+
+    Ok(WhatIfUsageList(results))
+}
+
+fn format_change_percent(original: f64, whatif: f64) -> String {
+    if original.abs() < f64::EPSILON {
+        return "-".to_string();
+    }
+    let change = (whatif - original) / original * 100.0;
+    let sign = if change < 0.0 { "-" } else { "+" };
+    format!("{sign}{}%", format_decimal(change.abs(), 1))
+}
+
+impl OutputFormat for WhatIfUsageList {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.0)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        currency: &str,
+        decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.0.is_empty() {
+            return "No usage data found.".to_string();
+        }
+
+        let mut rows: Vec<WhatIfRow> = self
+            .0
+            .iter()
+            .map(|usage| WhatIfRow {
+                project: usage.project_name.clone(),
+                model: usage.model_name.clone(),
+                messages: format_number(usage.message_count),
+                original_cost: crate::models::currency::format_currency(
+                    usage.original_cost_usd,
+                    currency,
+                    decimal_places,
+                ),
+                whatif_cost: crate::models::currency::format_currency(
+                    usage.whatif_cost_usd,
+                    currency,
+                    decimal_places,
+                ),
+                difference: crate::models::currency::format_currency_signed(
+                    usage.difference_usd,
+                    currency,
+                    decimal_places,
+                ),
+                change: format_change_percent(usage.original_cost_usd, usage.whatif_cost_usd),
+            })
+            .collect();
+
+        // Calculate totals for summary row
+        let total_messages: u64 = self.0.iter().map(|u| u.message_count).sum();
+        let total_original: f64 = self.0.iter().map(|u| u.original_cost_usd).sum();
+        let total_whatif: f64 = self.0.iter().map(|u| u.whatif_cost_usd).sum();
+
+        rows.push(WhatIfRow {
+            project: "TOTAL".to_string(),
+            model: String::new(),
+            messages: format_number(total_messages),
+            original_cost: crate::models::currency::format_currency(
+                total_original,
+                currency,
+                decimal_places,
+            ),
+            whatif_cost: crate::models::currency::format_currency(
+                total_whatif,
+                currency,
+                decimal_places,
+            ),
+            difference: crate::models::currency::format_currency_signed(
+                total_whatif - total_original,
+                currency,
+                decimal_places,
+            ),
+            change: format_change_percent(total_original, total_whatif),
+        });
+
+        apply_table_style_with_color(tabled::Table::new(rows), colored, TableType::WhatIf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::CostCalculationMode;
+    use crate::parser::jsonl::{Message, Usage};
+
+    fn message(model: &str, input: u64, output: u64, cost_usd: Option<f64>) -> UsageData {
+        UsageData {
+            timestamp: Some("2025-06-09T10:00:00Z".to_string()),
+            uuid: None,
+            request_id: None,
+            session_id: None,
+            message: Some(Message {
+                model: Some(model.to_string()),
+                ..Default::default()
+            }),
+            usage: Some(Usage {
+                input_tokens: Some(input),
+                output_tokens: Some(output),
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            }),
+            cost_usd,
+            cwd: None,
+            original_cwd: None,
+            git_branch: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reprice_usage_as_cheaper_model() {
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let data = vec![
+            (
+                message("claude-opus-4-20250514", 1_000_000, 100_000, None),
+                "alpha".to_string(),
+            ),
+            (
+                message("claude-opus-4-20250514", 0, 0, Some(2.0)),
+                "alpha".to_string(),
+            ),
+            (
+                message("claude-sonnet-4-20250514", 1_000_000, 0, None),
+                "beta".to_string(),
+            ),
+        ];
+
+        let results = reprice_usage(
+            &tracker,
+            data,
+            &mut pricing_manager,
+            &UsageFilter::default(),
+            "claude-haiku-3-5-20241022",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.0.len(), 2);
+
+        let alpha = &results.0[0];
+        assert_eq!(alpha.project_name, "alpha");
+        assert_eq!(alpha.message_count, 2);
+        // Opus: $15 input + $7.50 output, plus $2 embedded
+        assert!((alpha.original_cost_usd - 24.5).abs() < 1e-9);
+        // Haiku: $1 input + $0.50 output; the embedded-cost message had no tokens
+        assert!((alpha.whatif_cost_usd - 1.5).abs() < 1e-9);
+        assert!((alpha.difference_usd + 23.0).abs() < 1e-9);
+
+        let beta = &results.0[1];
+        assert_eq!(beta.model_name, "claude-sonnet-4-20250514");
+        assert!((beta.original_cost_usd - 3.0).abs() < 1e-9);
+        assert!((beta.whatif_cost_usd - 1.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_reprice_usage_respects_model_filter() {
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let mut pricing_manager = PricingManager::new();
+        let data = vec![
+            (
+                message("claude-opus-4-20250514", 1_000, 1_000, None),
+                "alpha".to_string(),
+            ),
+            (
+                message("claude-sonnet-4-20250514", 1_000, 1_000, None),
+                "alpha".to_string(),
+            ),
+        ];
+        let filter = UsageFilter {
+            model_name: Some("claude-sonnet-4-20250514".to_string()),
+            ..Default::default()
+        };
+
+        let results = reprice_usage(
+            &tracker,
+            data,
+            &mut pricing_manager,
+            &filter,
+            "claude-opus-4-20250514",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results.0.len(), 1);
+        assert_eq!(results.0[0].model_name, "claude-sonnet-4-20250514");
+        assert!(results.0[0].difference_usd > 0.0);
+    }
+
+    #[test]
+    fn test_difference_formatting() {
+        use crate::models::currency::format_currency_signed;
+
+        assert_eq!(format_currency_signed(-12.4, "USD", 2), "-$12.40");
+        assert_eq!(format_currency_signed(3.1, "USD", 2), "+$3.10");
+        assert_eq!(format_change_percent(10.0, 2.0), "-80.0%");
+        assert_eq!(format_change_percent(0.0, 2.0), "-");
+    }
+}
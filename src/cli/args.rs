@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "ccost")]
@@ -25,14 +25,44 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub json: bool,
 
+    /// Wrap JSON output with exchange rate provenance and USD originals
+    #[arg(long, global = true)]
+    pub currency_provenance: bool,
+
+    /// Output format: table, json, prometheus or openmetrics (metrics for usage views only)
+    #[arg(long, global = true)]
+    pub format: Option<String>,
+
+    /// Write prometheus/openmetrics output atomically to this file (node_exporter textfile collector)
+    #[arg(long, global = true)]
+    pub textfile: Option<String>,
+
     /// Enable colorized table output
     #[arg(long, global = true)]
     pub colored: bool,
 
+    /// Show bar charts and sparklines below tables (daily and timeframe views)
+    #[arg(long, global = true)]
+    pub chart: bool,
+
+    /// Keep running and refresh as Claude writes new messages (timeframe views)
+    #[arg(long, global = true)]
+    pub watch: bool,
+
     /// Use dummy project names for privacy in screenshots
     #[arg(short = 'd', long, global = true)]
     pub hidden: bool,
 
+    #[command(flatten)]
+    pub filters: FilterArgs,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+/// Usage filters shared by every report command
+#[derive(Args, Debug, Clone, Default)]
+pub struct FilterArgs {
     /// Filter by model
     #[arg(long, global = true)]
     pub model: Option<String>,
@@ -45,8 +75,9 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub until: Option<String>,
 
-    #[command(subcommand)]
-    pub command: Option<Commands>,
+    /// Only count messages written on this git branch (exact name)
+    #[arg(long, global = true)]
+    pub branch: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -64,6 +95,27 @@ pub enum ConfigAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum PricingAction {
+    /// List effective pricing for all known models
+    List,
+    /// Show pricing for a model from every source
+    Show {
+        /// Model name (e.g., claude-sonnet-4-20250514)
+        model: String,
+    },
+    /// Fetch the latest LiteLLM pricing and update the local cache
+    Refresh,
+    /// Compare the built-in static table against cached LiteLLM pricing
+    Diff,
+}
+
+#[derive(Subcommand)]
+pub enum BudgetAction {
+    /// Show spend against each configured budget (exit code 2 = warning, 3 = exceeded)
+    Status,
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Show today's usage
@@ -113,9 +165,173 @@ pub enum Commands {
         projects: Option<String>,
     },
 
+    /// Reprice usage as if it had run on another model
+    #[command(name = "whatif")]
+    WhatIf {
+        /// Model to reprice usage as (e.g., claude-haiku-3-5-20241022)
+        target_model: String,
+
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Compare API-equivalent cost against subscription plans
+    Plans {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Number of billing months to compare (including the current one)
+        #[arg(long, default_value = "6")]
+        months: u32,
+    },
+
+    /// Compare usage between two periods (e.g., last-week this-week, 2025-05 2025-06)
+    Compare {
+        /// Baseline period: today, yesterday, this-week, last-week, this-month, last-month,
+        /// YYYY-MM, YYYY-MM-DD or YYYY-MM-DD..YYYY-MM-DD
+        baseline: String,
+
+        /// Period to compare against the baseline (same formats)
+        current: String,
+
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Group rows by "project" or "model"
+        #[arg(long, default_value = "project")]
+        by: String,
+    },
+
+    /// Flag days, sessions and projects that cost far more than their own history
+    Anomalies {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Robust z-score (median absolute deviation) above which costs are flagged
+        #[arg(long, default_value = "3.5")]
+        threshold: f64,
+    },
+
+    /// Show prompt cache efficiency: hit rates, read savings and unread cache writes
+    Cache {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Group rows by "project", "model" or "session"
+        #[arg(long, default_value = "project")]
+        by: String,
+    },
+
+    /// Attribute cost to git commits: turns since the previous commit are charged to the next one
+    Commits {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Group rows by "commit", "author" or "branch"
+        #[arg(long, default_value = "commit")]
+        by: String,
+    },
+
+    /// Break down cost by the git branch each message was written on, within each project
+    Branches {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Show when usage happens: a weekday × hour-of-day grid in the configured timezone
+    Heatmap {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Value to plot: "cost" or "tokens"
+        #[arg(long, default_value = "cost")]
+        metric: String,
+
+        /// Print the grid as CSV (semicolon-separated in comma-decimal locales)
+        #[arg(long)]
+        csv: bool,
+    },
+
+    /// Suggest ways to cut costs, with estimated savings
+    Optimize {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Explore usage in a full-screen terminal dashboard
+    Tui {
+        /// Start filtered to this project
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Serve usage as JSON over HTTP on localhost (/usage, /projects, /daily, /models, /sessions, /metrics)
+    Serve {
+        /// Port to listen on (127.0.0.1 only)
+        #[arg(long, default_value = "8787")]
+        port: u16,
+    },
+
+    /// Push per-message usage logs and cost metrics to an OpenTelemetry collector (OTLP/HTTP JSON)
+    Otlp {
+        /// Filter by project name
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Collector base URL (overrides otlp.endpoint)
+        #[arg(long)]
+        endpoint: Option<String>,
+
+        /// Print the OTLP request bodies instead of sending them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// One-line cost summary for the Claude Code statusline or a shell prompt
+    Statusline,
+
+    /// Live cost meter for a terminal pane (refreshes as Claude writes messages)
+    Live {
+        /// Only count this project
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Check spending against configured budgets
+    Budget {
+        #[command(subcommand)]
+        action: BudgetAction,
+    },
+
+    /// Post budget and cost spike alerts to a webhook (each alert is sent once)
+    Notify {
+        /// Webhook URL (overrides notify.webhook_url)
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Print the alerts that would be sent without sending or recording them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Configuration management
     Config {
         #[command(subcommand)]
         action: ConfigAction,
     },
+
+    /// Inspect and refresh model pricing
+    Pricing {
+        #[command(subcommand)]
+        action: PricingAction,
+    },
 }
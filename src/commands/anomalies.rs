@@ -0,0 +1,236 @@
+// Spending anomaly command and verbose-mode anomaly summary
+use crate::analysis::anomalies::{Anomaly, AnomalyReport, describe_anomaly, detect_anomalies};
+use crate::analysis::{TimezoneCalculator, UsageFilter, UsageTracker};
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::models::PricingManager;
+use crate::models::currency::CurrencyConverter;
+use crate::output::OutputFormat;
+use crate::parser::jsonl::UsageData;
+use crate::utils::{print_filter_info, resolve_filters};
+
+pub async fn handle_anomalies_command(
+    threshold: f64,
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    if !threshold.is_finite() || threshold <= 0.0 {
+        let e = anyhow::anyhow!("Threshold must be a positive number");
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // --since/--until choose which anomalies to report; history always uses all data
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    // Process JSONL files
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let report = match detect_anomalies(
+        &context.usage_tracker,
+        &usage_tuples,
+        &mut context.pricing_manager,
+        &usage_filter,
+        &context.timezone_calc,
+        threshold,
+    )
+    .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    let mut report = AnomalyReport {
+        threshold: report.threshold,
+        anomalies: report.within(&usage_filter).into_iter().cloned().collect(),
+    };
+
+    // Convert currencies if needed
+    let report_usd = report.clone();
+    let original_total_usd = total_cost(&report);
+    if options.currency != "USD" {
+        convert_anomaly_currency(
+            &context.currency_converter,
+            &mut report,
+            options.currency,
+            options.verbose,
+            options.json_output,
+        )
+        .await;
+    }
+    let currency = context.currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        total_cost(&report),
+    );
+
+    // Display results
+    if options.json_output {
+        match report.to_json_with_currency(&currency, &report_usd, options.currency_provenance) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            report.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored,
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// Verbose mode: mention anomalies inside the filter's date range, in the target currency
+///
+/// Failures are only reported, never fatal, since this is supplementary output.
+/// An anomaly whose amounts can't be converted is described in USD.
+pub async fn print_anomaly_summary(
+    usage_tracker: &UsageTracker,
+    usage_tuples: &[(UsageData, String)],
+    pricing_manager: &mut PricingManager,
+    usage_filter: &UsageFilter,
+    timezone_calc: &TimezoneCalculator,
+    currency_converter: &CurrencyConverter,
+    options: &OutputOptions<'_>,
+) {
+    let report = match detect_anomalies(
+        usage_tracker,
+        usage_tuples,
+        pricing_manager,
+        usage_filter,
+        timezone_calc,
+        crate::analysis::anomalies::DEFAULT_THRESHOLD,
+    )
+    .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            let error_msg = format!("Failed to check for anomalies: {e}");
+            if options.json_output {
+                eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+            } else {
+                eprintln!("Warning: {}", error_msg);
+            }
+            return;
+        }
+    };
+
+    for anomaly in report.within(usage_filter) {
+        let mut anomaly = anomaly.clone();
+        let currency = if options.currency != "USD"
+            && convert_anomaly(currency_converter, &mut anomaly, options.currency)
+                .await
+                .is_ok()
+        {
+            options.currency
+        } else {
+            "USD"
+        };
+        let message = format!(
+            "Anomaly: {}",
+            describe_anomaly(&anomaly, currency, options.decimal_places)
+        );
+        if options.json_output {
+            eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, message);
+        } else {
+            println!("{message}");
+        }
+    }
+}
+
+fn total_cost(report: &AnomalyReport) -> f64 {
+    report.anomalies.iter().fold(0.0, |total, a| total + a.cost)
+}
+
+async fn convert_anomaly_currency(
+    currency_converter: &CurrencyConverter,
+    report: &mut AnomalyReport,
+    target_currency: &str,
+    verbose: bool,
+    json_output: bool,
+) {
+    // Keep USD amounts if conversion fails
+    for anomaly in &mut report.anomalies {
+        if let Err(e) = convert_anomaly(currency_converter, anomaly, target_currency).await
+            && verbose
+        {
+            let error_msg = format!("Failed to convert currency for {}: {}", anomaly.subject, e);
+            if json_output {
+                eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+            } else {
+                eprintln!("Warning: {}", error_msg);
+            }
+        }
+    }
+}
+
+/// Convert one anomaly's cost and typical cost from USD
+pub async fn convert_anomaly(
+    currency_converter: &CurrencyConverter,
+    anomaly: &mut Anomaly,
+    target_currency: &str,
+) -> anyhow::Result<()> {
+    let converted = currency_converter
+        .convert_daily_from_usd(anomaly.cost, &anomaly.daily_cost_usd, target_currency)
+        .await?;
+    // The typical cost has no single date; scale it by the same effective rate
+    if anomaly.cost.abs() > f64::EPSILON {
+        anomaly.typical_cost *= converted / anomaly.cost;
+    }
+    anomaly.cost = converted;
+    Ok(())
+}
@@ -0,0 +1,165 @@
+// Budget status command
+use crate::analysis::UsageFilter;
+use crate::analysis::budget::{BudgetPeriod, BudgetStatus, BudgetStatusList, evaluate_budget};
+use crate::cli::BudgetAction;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::config::BudgetConfig;
+use crate::config::settings::CurrencyConfig;
+use crate::models::currency::{CurrencyConverter, CurrencyProvenance};
+use crate::output::OutputFormat;
+use crate::output::table::CurrencyEnvelope;
+use crate::parser::jsonl::UsageData;
+use crate::utils::apply_usage_filters;
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+pub async fn handle_budget_action(
+    action: BudgetAction,
+    budgets: &BTreeMap<String, BudgetConfig>,
+    currency_config: &CurrencyConfig,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    match action {
+        BudgetAction::Status => {}
+    }
+
+    if budgets.is_empty() {
+        let e = anyhow::anyhow!(
+            "No budgets configured. Add one with: ccost config set budgets.monthly.amount 100"
+        );
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Process JSONL files once; each budget filters its own period and scope
+    let all_usage_data = match context.process_jsonl_files(
+        None,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let statuses =
+        match evaluate_budgets(&mut context, &usage_tuples, budgets, currency_config).await {
+            Ok(statuses) => BudgetStatusList(statuses),
+            Err(e) => {
+                handle_error(&e, options.json_output);
+                return Err(e);
+            }
+        };
+
+    // Display results
+    if options.json_output {
+        // Each status carries its own conversion; the envelope sums them when one currency is used
+        let json = if options.currency_provenance {
+            let conversions: Vec<CurrencyProvenance> = statuses
+                .0
+                .iter()
+                .filter_map(|status| status.conversion.clone())
+                .collect();
+            let currency = CurrencyProvenance::combine(&conversions);
+            CurrencyEnvelope::new(currency.as_ref(), &statuses, None).to_json()
+        } else {
+            statuses.to_json()
+        };
+        match json {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            statuses.to_table_with_currency_and_color(
+                "USD",
+                options.decimal_places,
+                options.colored
+            )
+        );
+    }
+
+    // Non-zero exit lets cron jobs and shell prompts react to crossed thresholds
+    let exit_code = statuses.overall_state().exit_code();
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+
+    Ok(())
+}
+
+/// Spend against every budget for its current period, in each budget's currency
+pub async fn evaluate_budgets(
+    context: &mut TimeframeContext,
+    usage_tuples: &[(UsageData, String)],
+    budgets: &BTreeMap<String, BudgetConfig>,
+    currency_config: &CurrencyConfig,
+) -> anyhow::Result<Vec<BudgetStatus>> {
+    let now = Utc::now();
+    let mut statuses = Vec::new();
+    for (name, budget) in budgets {
+        let period = BudgetPeriod::from_config_str(&budget.period)
+            .map_err(|e| anyhow::anyhow!("Budget '{name}': {e}"))?;
+        let bounds = period.current_bounds(&context.timezone_calc);
+
+        let usage_filter = UsageFilter {
+            project_name: budget.project.clone(),
+            model_name: budget.model.clone(),
+            since: Some(bounds.0),
+            until: None,
+            branch_name: None,
+        };
+
+        let (project_usage, _) = context
+            .calculate_usage_enhanced(usage_tuples.to_vec(), &usage_filter)
+            .await?;
+        let filtered_usage = apply_usage_filters(project_usage, &usage_filter);
+
+        let spent_usd = filtered_usage
+            .iter()
+            .fold(0.0, |total, p| total + p.total_cost_usd);
+        let mut daily_cost_usd = BTreeMap::new();
+        for project in &filtered_usage {
+            for (date, cost) in &project.daily_cost_usd {
+                *daily_cost_usd.entry(*date).or_insert(0.0) += cost;
+            }
+        }
+
+        // Budgets are in their own currency; a fresh converter keeps provenance per budget
+        let currency_converter = CurrencyConverter::from_config(currency_config);
+        let spent = currency_converter
+            .convert_daily_from_usd(spent_usd, &daily_cost_usd, &budget.currency)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Budget '{name}': failed to convert spend to {}: {e}",
+                    budget.currency
+                )
+            })?;
+
+        let mut status = evaluate_budget(name, budget, period, bounds, spent, now);
+        status.conversion = Some(currency_converter.provenance(&budget.currency, spent_usd, spent));
+        statuses.push(status);
+    }
+    Ok(statuses)
+}
@@ -0,0 +1,183 @@
+// Git commit cost attribution command
+use crate::analysis::UsageFilter;
+use crate::analysis::commits::{CommitCost, CommitGroup, CommitReport, commit_costs};
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::models::currency::CurrencyConverter;
+use crate::output::OutputFormat;
+use crate::parser::jsonl::UsageData;
+use crate::utils::{print_filter_info, resolve_filters};
+
+pub async fn handle_commits_command(
+    group_by: String,
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    let group = match CommitGroup::from_arg(&group_by) {
+        Ok(group) => group,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    // Process JSONL files
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let mut report = match commit_costs(
+        &context.usage_tracker,
+        &usage_tuples,
+        &mut context.pricing_manager,
+        &usage_filter,
+        &context.timezone_calc,
+        group,
+        options.hidden,
+    )
+    .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    if report.rows.is_empty() && report.unattributed.message_count == 0 {
+        if options.json_output {
+            println!(
+                r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
+            );
+        } else {
+            println!("No usage data found matching your filters.");
+        }
+        return Ok(());
+    }
+
+    // Convert currencies if needed
+    let report_usd = report.clone();
+    let original_total_usd = total_cost(&report);
+    if options.currency != "USD" {
+        convert_commit_currency(
+            &context.currency_converter,
+            &mut report,
+            options.currency,
+            options.verbose,
+            options.json_output,
+        )
+        .await;
+    }
+    let currency = context.currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        total_cost(&report),
+    );
+
+    // Display results
+    if options.json_output {
+        match report.to_json_with_currency(&currency, &report_usd, options.currency_provenance) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            report.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored
+            )
+        );
+    }
+
+    Ok(())
+}
+
+fn total_cost(report: &CommitReport) -> f64 {
+    report
+        .rows
+        .iter()
+        .fold(report.unattributed.cost, |total, row| total + row.cost)
+}
+
+async fn convert_commit_currency(
+    currency_converter: &CurrencyConverter,
+    report: &mut CommitReport,
+    target_currency: &str,
+    verbose: bool,
+    json_output: bool,
+) {
+    let rows: Vec<&mut CommitCost> = report
+        .rows
+        .iter_mut()
+        .chain(std::iter::once(&mut report.unattributed))
+        .collect();
+    for row in rows {
+        match currency_converter
+            .convert_daily_from_usd(row.cost, &row.daily_cost_usd, target_currency)
+            .await
+        {
+            Ok(converted) => row.cost = converted,
+            Err(e) => {
+                if verbose {
+                    let error_msg = format!("Failed to convert currency for {}: {}", row.name, e);
+                    if json_output {
+                        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                    } else {
+                        eprintln!("Warning: {}", error_msg);
+                    }
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+}
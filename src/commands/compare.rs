@@ -0,0 +1,223 @@
+// Period-over-period comparison command
+use crate::analysis::UsageFilter;
+use crate::analysis::compare::{
+    CompareGroup, ComparePeriod, PeriodTotals, compare_periods, group_totals, parse_period,
+};
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::models::currency::CurrencyConverter;
+use crate::output::OutputFormat;
+use crate::parser::jsonl::UsageData;
+use crate::utils::{apply_usage_filters, print_filter_info};
+use chrono::{Duration, Utc};
+use std::collections::BTreeMap;
+
+pub async fn handle_compare_command(
+    baseline: String,
+    current: String,
+    group_by: String,
+    project: Option<String>,
+    model: Option<String>,
+    branch: Option<String>,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let today = context.timezone_calc.local_date(Utc::now());
+    let parsed = CompareGroup::from_arg(&group_by).and_then(|group| {
+        Ok((
+            group,
+            parse_period(&baseline, today)?,
+            parse_period(&current, today)?,
+        ))
+    });
+    let (group, baseline_period, current_period) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Process JSONL files once; each period re-runs the aggregation on a copy
+    let all_usage_data = match context.process_jsonl_files(
+        project.clone(),
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let mut period_totals = Vec::with_capacity(2);
+    for period in [&baseline_period, &current_period] {
+        let usage_filter = UsageFilter {
+            project_name: project.clone(),
+            model_name: model.clone(),
+            since: Some(context.timezone_calc.day_start(period.first_day)),
+            until: Some(
+                context
+                    .timezone_calc
+                    .day_start(period.last_day + Duration::days(1))
+                    - Duration::seconds(1),
+            ),
+            branch_name: branch.clone(),
+        };
+
+        if options.verbose {
+            print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+        }
+
+        let (project_usage, _) = match context
+            .calculate_usage_enhanced(usage_tuples.clone(), &usage_filter)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                handle_error(&e, options.json_output);
+                return Err(e);
+            }
+        };
+        let filtered_usage = apply_usage_filters(project_usage, &usage_filter);
+        period_totals.push(group_totals(&filtered_usage, group));
+    }
+    let mut current_totals = period_totals.pop().unwrap_or_default();
+    let mut baseline_totals = period_totals.pop().unwrap_or_default();
+
+    if baseline_totals.is_empty() && current_totals.is_empty() {
+        if options.json_output {
+            println!(
+                r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
+            );
+        } else {
+            println!("No usage data found matching your filters.");
+        }
+        return Ok(());
+    }
+
+    // Convert currencies if needed
+    let original_total_usd = total_cost(&baseline_totals) + total_cost(&current_totals);
+    let comparison_usd = compare_periods(
+        group,
+        baseline_period.clone(),
+        current_period.clone(),
+        &baseline_totals,
+        &current_totals,
+    );
+    if options.currency != "USD" {
+        for totals in [&mut baseline_totals, &mut current_totals] {
+            convert_totals_currency(
+                &context.currency_converter,
+                totals,
+                options.currency,
+                options.verbose,
+                options.json_output,
+            )
+            .await;
+        }
+    }
+    let currency = context.currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        total_cost(&baseline_totals) + total_cost(&current_totals),
+    );
+
+    let comparison = compare_periods(
+        group,
+        baseline_period,
+        current_period,
+        &baseline_totals,
+        &current_totals,
+    );
+
+    // Display results
+    if options.json_output {
+        match comparison.to_json_with_currency(
+            &currency,
+            &comparison_usd,
+            options.currency_provenance,
+        ) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        let describe = |period: &ComparePeriod| {
+            let first = context
+                .date_formatter
+                .format_naive_date_for_table(&period.first_day);
+            if period.first_day == period.last_day {
+                format!("{} ({first})", period.label)
+            } else {
+                let last = context
+                    .date_formatter
+                    .format_naive_date_for_table(&period.last_day);
+                format!("{} ({first} – {last})", period.label)
+            }
+        };
+        println!(
+            "Comparing {} → {}",
+            describe(&comparison.baseline_period),
+            describe(&comparison.current_period)
+        );
+        println!();
+        println!(
+            "{}",
+            comparison.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored
+            )
+        );
+    }
+
+    Ok(())
+}
+
+fn total_cost(totals: &BTreeMap<String, PeriodTotals>) -> f64 {
+    totals.values().fold(0.0, |total, t| total + t.cost)
+}
+
+async fn convert_totals_currency(
+    currency_converter: &CurrencyConverter,
+    totals: &mut BTreeMap<String, PeriodTotals>,
+    target_currency: &str,
+    verbose: bool,
+    json_output: bool,
+) {
+    for (name, entry) in totals.iter_mut() {
+        match currency_converter
+            .convert_daily_from_usd(entry.cost, &entry.daily_cost_usd, target_currency)
+            .await
+        {
+            Ok(converted) => entry.cost = converted,
+            Err(e) => {
+                if verbose {
+                    let error_msg = format!("Failed to convert currency for {name}: {e}");
+                    if json_output {
+                        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                    } else {
+                        eprintln!("Warning: {}", error_msg);
+                    }
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+}
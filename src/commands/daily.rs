@@ -1,8 +1,11 @@
 // Daily usage breakdown command
-use crate::analysis::DailyUsageList;
-use crate::commands::timeframe_utils::{TimeframeContext, handle_error};
+use crate::analysis::{DailyUsageList, UsageFilter};
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::commands::watch::run_or_watch;
 use crate::models::currency::CurrencyConverter;
 use crate::output::OutputFormat;
+use crate::output::chart::{daily_charts, terminal_width};
 use crate::utils::{DateFormatter, EnhancedUsageData};
 use chrono::Utc;
 use std::collections::HashMap;
@@ -10,41 +13,73 @@ use std::collections::HashMap;
 pub async fn handle_daily_command(
     days: u32,
     project_filter: Option<String>,
-    _since: Option<String>,
-    _until: Option<String>,
-    model_filter: Option<String>,
-    target_currency: &str,
-    decimal_places: u8,
-    json_output: bool,
-    verbose: bool,
-    colored: bool,
-    hidden: bool,
-    timezone_name: &str,
-    daily_cutoff_hour: u8,
-    date_format: &str,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
 ) -> anyhow::Result<()> {
     // Initialize context
-    let mut context =
-        match TimeframeContext::new(timezone_name, daily_cutoff_hour, date_format).await {
-            Ok(ctx) => ctx,
-            Err(e) => {
-                handle_error(&e, json_output);
-                return Err(e);
-            }
-        };
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    run_or_watch(
+        &mut context,
+        options.watch,
+        options.json_output,
+        async |context| {
+            show_daily(
+                context,
+                days,
+                project_filter.clone(),
+                filters.clone(),
+                options,
+            )
+            .await
+        },
+    )
+    .await
+}
+
+/// Load usage and print the per-day breakdown
+async fn show_daily(
+    context: &mut TimeframeContext,
+    days: u32,
+    project_filter: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    // --days selects the range, so only the model and branch filters apply here
+    let FilterArgs {
+        model: model_filter,
+        branch,
+        ..
+    } = filters;
+    let branch_filter = UsageFilter {
+        branch_name: branch,
+        ..UsageFilter::default()
+    };
 
     // Process JSONL files
-    let all_usage_data =
-        match context.process_jsonl_files(project_filter.clone(), verbose, json_output, hidden) {
-            Ok(data) => data,
-            Err(e) => {
-                handle_error(&e, json_output);
-                return Err(e);
-            }
-        };
+    let mut all_usage_data = match context.process_jsonl_files(
+        project_filter.clone(),
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    all_usage_data.retain(|enhanced| branch_filter.matches_branch(&enhanced.usage_data));
 
     if all_usage_data.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -62,11 +97,11 @@ pub async fn handle_daily_command(
         &context.usage_tracker,
         &context.pricing_manager,
         &context.date_formatter,
-        json_output,
+        options.json_output,
     )?;
 
     if daily_usage_list.0.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No daily usage data found matching filters", "data": []}}"#
             );
@@ -78,19 +113,32 @@ pub async fn handle_daily_command(
 
     // Convert currencies if needed
     let mut converted_daily_usage = daily_usage_list;
-    if target_currency != "USD" {
+    let daily_usage_usd = converted_daily_usage.clone();
+    let original_total_usd = total_daily_cost(&converted_daily_usage);
+    if options.currency != "USD" {
         convert_daily_currency(
+            &context.currency_converter,
             &mut converted_daily_usage,
-            target_currency,
-            verbose,
-            json_output,
+            options.currency,
+            options.verbose,
+            options.json_output,
         )
         .await?;
     }
 
+    let currency = context.currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        total_daily_cost(&converted_daily_usage),
+    );
+
     // Display results
-    if json_output {
-        match converted_daily_usage.to_json() {
+    if options.json_output {
+        match converted_daily_usage.to_json_with_currency(
+            &currency,
+            &daily_usage_usd,
+            options.currency_provenance,
+        ) {
             Ok(json) => println!("{json}"),
             Err(e) => {
                 println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
@@ -101,17 +149,31 @@ pub async fn handle_daily_command(
         println!(
             "{}",
             converted_daily_usage.to_table_with_currency_and_color(
-                target_currency,
-                decimal_places,
-                colored
+                options.currency,
+                options.decimal_places,
+                options.colored,
             )
         );
+        if options.chart {
+            println!();
+            println!(
+                "{}",
+                daily_charts(
+                    &converted_daily_usage,
+                    options.currency,
+                    options.decimal_places,
+                    options.colored,
+                    terminal_width()
+                )
+            );
+        }
     }
 
     Ok(())
 }
 
-fn group_usage_by_day(
+/// Aggregate usage into one entry per UTC day over the last `days` days
+pub fn group_usage_by_day(
     all_usage_data: &[EnhancedUsageData],
     days: u32,
     model_filter: Option<String>,
@@ -147,7 +209,7 @@ fn group_usage_by_day(
         }
 
         // Parse timestamp and extract date
-        let date_key = if let Some(timestamp_str) = &message.timestamp {
+        let (date_key, message_date) = if let Some(timestamp_str) = &message.timestamp {
             if let Ok(message_time) = usage_tracker.parse_timestamp(timestamp_str) {
                 // Check if message is within the requested days range
                 let today = Utc::now().date_naive();
@@ -158,11 +220,12 @@ fn group_usage_by_day(
                     continue;
                 }
 
-                if json_output {
+                let date_key = if json_output {
                     date_formatter.format_naive_date_for_json(&message_date)
                 } else {
                     date_formatter.format_naive_date_for_table(&message_date)
-                }
+                };
+                (date_key, message_date)
             } else {
                 continue; // Skip messages with unparseable timestamps
             }
@@ -182,6 +245,9 @@ fn group_usage_by_day(
                 total_cost_usd: 0.0,
                 message_count: 0,
                 projects_count: 0,
+                usage_date: Some(message_date),
+                model_cost_usd: Default::default(),
+                project_cost_usd: Default::default(),
             });
 
         // Aggregate token counts
@@ -216,6 +282,11 @@ fn group_usage_by_day(
         };
 
         daily_usage.total_cost_usd += cost;
+        *daily_usage.model_cost_usd.entry(model_name).or_insert(0.0) += cost;
+        *daily_usage
+            .project_cost_usd
+            .entry(enhanced.project_name.clone())
+            .or_insert(0.0) += cost;
     }
 
     // Count projects per day
@@ -253,20 +324,35 @@ fn group_usage_by_day(
     Ok(DailyUsageList(daily_usage_vec))
 }
 
-async fn convert_daily_currency(
+pub fn total_daily_cost(daily_usage_list: &DailyUsageList) -> f64 {
+    daily_usage_list
+        .0
+        .iter()
+        .fold(0.0, |total, daily| total + daily.total_cost_usd)
+}
+
+pub async fn convert_daily_currency(
+    currency_converter: &CurrencyConverter,
     daily_usage_list: &mut DailyUsageList,
     target_currency: &str,
     verbose: bool,
     json_output: bool,
 ) -> anyhow::Result<()> {
-    let currency_converter = CurrencyConverter::new();
-
-    // Convert all USD amounts to target currency
+    // Convert all USD amounts to target currency, at each day's rate in historical mode
     for daily in &mut daily_usage_list.0 {
-        match currency_converter
-            .convert_from_usd(daily.total_cost_usd, target_currency)
-            .await
-        {
+        let converted = match daily.usage_date {
+            Some(date) => {
+                currency_converter
+                    .convert_from_usd_on(daily.total_cost_usd, target_currency, date)
+                    .await
+            }
+            None => {
+                currency_converter
+                    .convert_from_usd(daily.total_cost_usd, target_currency)
+                    .await
+            }
+        };
+        match converted {
             Ok(converted_cost) => {
                 daily.total_cost_usd = converted_cost;
             }
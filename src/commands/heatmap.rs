@@ -0,0 +1,157 @@
+// Hour-of-day / day-of-week heatmap command
+use crate::analysis::UsageFilter;
+use crate::analysis::heatmap::{HeatmapMetric, build_heatmap};
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::output::OutputFormat;
+use crate::parser::jsonl::UsageData;
+use crate::utils::{print_filter_info, resolve_filters};
+
+pub async fn handle_heatmap_command(
+    metric: String,
+    csv_output: bool,
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    let metric = match HeatmapMetric::from_arg(&metric) {
+        Ok(metric) => metric,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    if csv_output && options.json_output {
+        let e = anyhow::anyhow!("Use either --csv or --json, not both");
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    // Keep CSV output machine-readable
+    if options.verbose && !csv_output {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    // Process JSONL files
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let mut heatmap = match build_heatmap(
+        &context.usage_tracker,
+        &usage_tuples,
+        &mut context.pricing_manager,
+        &usage_filter,
+        &context.timezone_calc,
+        options.timezone,
+        metric,
+    )
+    .await
+    {
+        Ok(heatmap) => heatmap,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Convert currencies if needed; cells share the period's effective rate
+    let heatmap_usd = heatmap.clone();
+    let original_total_usd = match metric {
+        HeatmapMetric::Cost => heatmap.total,
+        HeatmapMetric::Tokens => 0.0,
+    };
+    if metric == HeatmapMetric::Cost && options.currency != "USD" && heatmap.total > 0.0 {
+        match context
+            .currency_converter
+            .convert_daily_from_usd(heatmap.total, &heatmap.daily_cost_usd, options.currency)
+            .await
+        {
+            Ok(converted) => heatmap.scale(converted / heatmap.total),
+            Err(e) => {
+                if options.verbose {
+                    let error_msg = format!("Failed to convert currency: {e}");
+                    if options.json_output {
+                        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                    } else {
+                        eprintln!("Warning: {}", error_msg);
+                    }
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+    let converted_total = match metric {
+        HeatmapMetric::Cost => heatmap.total,
+        HeatmapMetric::Tokens => 0.0,
+    };
+
+    // Display results
+    if csv_output {
+        println!("{}", heatmap.to_csv(options.decimal_places));
+    } else if options.json_output {
+        let currency = context.currency_converter.provenance(
+            options.currency,
+            original_total_usd,
+            converted_total,
+        );
+        match heatmap.to_json_with_currency(&currency, &heatmap_usd, options.currency_provenance) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            heatmap.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored
+            )
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+// Live cost meter command
+use crate::analysis::UsageFilter;
+use crate::analysis::live::build_live_summary;
+use crate::commands::timeframe_utils::{
+    OutputOptions, TimeframeContext, UsageTimeframe, handle_error,
+};
+use crate::commands::watch::watch_changes;
+use crate::output::OutputFormat;
+use crate::parser::jsonl::UsageData;
+use crate::utils::{print_filter_info, resolve_filters};
+use chrono::Utc;
+
+pub async fn handle_live_command(
+    project: Option<String>,
+    model: Option<String>,
+    branch: Option<String>,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let result = watch_changes(&mut context, options.json_output, async |context| {
+        show_live(
+            context,
+            project.clone(),
+            model.clone(),
+            branch.clone(),
+            options,
+        )
+        .await
+    })
+    .await;
+    if let Err(e) = &result {
+        handle_error(e, options.json_output);
+    }
+    result
+}
+
+/// Load new messages and print the meter
+async fn show_live(
+    context: &mut TimeframeContext,
+    project: Option<String>,
+    model: Option<String>,
+    branch: Option<String>,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    // Re-resolved on every refresh so the meter rolls over at the daily cutoff
+    let (final_project, final_since, final_until, final_model) = resolve_filters(
+        Some(UsageTimeframe::Today),
+        project,
+        None,
+        None,
+        model,
+        &context.timezone_calc,
+    );
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    let all_usage_data = context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    )?;
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let mut summary = build_live_summary(
+        &context.usage_tracker,
+        &usage_tuples,
+        &mut context.pricing_manager,
+        &usage_filter,
+        &context.timezone_calc,
+        Utc::now(),
+    )
+    .await?;
+
+    // Convert currencies if needed; every amount shares today's effective rate
+    let summary_usd = summary.clone();
+    let original_total_usd = summary.today_cost;
+    if options.currency != "USD" && summary.today_cost > 0.0 {
+        match context
+            .currency_converter
+            .convert_daily_from_usd(
+                summary.today_cost,
+                &summary.daily_cost_usd,
+                options.currency,
+            )
+            .await
+        {
+            Ok(converted) => summary.scale(converted / summary.today_cost),
+            Err(e) => {
+                if options.verbose {
+                    let error_msg = format!("Failed to convert currency: {e}");
+                    if options.json_output {
+                        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                    } else {
+                        eprintln!("Warning: {}", error_msg);
+                    }
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+
+    // Display results
+    if options.json_output {
+        let currency = context.currency_converter.provenance(
+            options.currency,
+            original_total_usd,
+            summary.today_cost,
+        );
+        match summary.to_json_with_currency(&currency, &summary_usd, options.currency_provenance) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            summary.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored,
+            )
+        );
+    }
+
+    Ok(())
+}
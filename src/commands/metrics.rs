@@ -0,0 +1,90 @@
+// Prometheus/OpenMetrics output for usage views (--format prometheus)
+use crate::analysis::UsageFilter;
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, UsageTimeframe};
+use crate::output::metrics::{MetricsFormat, to_metrics, write_textfile};
+use crate::parser::jsonl::UsageData;
+use crate::utils::{apply_usage_filters, resolve_filters};
+use std::path::Path;
+
+/// Report an error as plain text; JSON errors would corrupt a metrics scrape
+fn fail(e: anyhow::Error) -> ! {
+    eprintln!("Error: {e}");
+    std::process::exit(1);
+}
+
+pub async fn handle_metrics_command(
+    format: MetricsFormat,
+    textfile: Option<String>,
+    timeframe: Option<UsageTimeframe>,
+    projects: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => fail(e),
+    };
+
+    let (_, final_since, final_until, final_model) =
+        resolve_filters(timeframe, None, since, until, model, &context.timezone_calc);
+
+    // Comma-separated, as for `ccost projects`
+    let project_filter: Option<Vec<String>> = projects.map(|list| {
+        list.split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect()
+    });
+    let usage_filter = UsageFilter {
+        project_name: None, // Applied below, since several projects may be selected
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    // No JSONL files just means no samples
+    if let Err(e) = context.incremental_parser.refresh(None, false, false) {
+        fail(e);
+    }
+    let usage_tuples: Vec<(UsageData, String)> = context
+        .incremental_parser
+        .usage_data(options.hidden)
+        .into_iter()
+        .filter(|enhanced| {
+            project_filter
+                .as_ref()
+                .is_none_or(|names| names.contains(&enhanced.project_name))
+        })
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    // Counters stay in USD, whatever --currency says
+    let project_usage = match context
+        .calculate_usage_enhanced(usage_tuples, &usage_filter)
+        .await
+    {
+        Ok((usage, _)) => apply_usage_filters(usage, &usage_filter),
+        Err(e) => fail(e),
+    };
+    let windowed = usage_filter.since.is_some() || usage_filter.until.is_some();
+    let metrics = to_metrics(&project_usage, format, windowed);
+
+    match textfile {
+        Some(path) => {
+            if let Err(e) = write_textfile(Path::new(&path), &metrics) {
+                fail(e);
+            }
+        }
+        None => print!("{metrics}"),
+    }
+    Ok(())
+}
@@ -1,7 +1,26 @@
 // Command handlers module
+pub mod anomalies;
+pub mod branches;
+pub mod budget;
+pub mod cache;
+pub mod commits;
+pub mod compare;
 pub mod config;
+pub mod heatmap;
+pub mod live;
+pub mod metrics;
+pub mod notify;
+pub mod optimize;
+pub mod otlp;
+pub mod plans;
+pub mod pricing;
 pub mod projects;
+pub mod serve;
+pub mod statusline;
+pub mod tui;
 pub mod usage;
+pub mod watch;
+pub mod whatif;
 
 // New individual timeframe commands
 pub mod daily;
@@ -0,0 +1,353 @@
+// Webhook notifications for budget and anomaly alerts
+use crate::analysis::UsageFilter;
+use crate::analysis::alerts::{Alert, NotifyState, anomaly_alerts, budget_alerts};
+use crate::analysis::anomalies::{Anomaly, detect_anomalies};
+use crate::commands::anomalies::convert_anomaly;
+use crate::commands::budget::evaluate_budgets;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::config::settings::CurrencyConfig;
+use crate::config::{BudgetConfig, NotifyConfig};
+use crate::models::currency::CurrencyProvenance;
+use crate::output::table::CurrencyEnvelope;
+use crate::output::webhook::{WebhookFormat, post_with_retry};
+use crate::parser::jsonl::UsageData;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub async fn handle_notify_command(
+    notify_config: &NotifyConfig,
+    budgets: &BTreeMap<String, BudgetConfig>,
+    currency_config: &CurrencyConfig,
+    url: Option<String>,
+    dry_run: bool,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let format = match WebhookFormat::from_config_str(&notify_config.format) {
+        Ok(format) => format,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    let url = url.unwrap_or_else(|| notify_config.webhook_url.clone());
+    if url.is_empty() && !dry_run {
+        let e = anyhow::anyhow!(
+            "No webhook configured. Set one with: ccost config set notify.webhook_url <url>"
+        );
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Process JSONL files once for budgets and anomalies
+    let all_usage_data = match context.process_jsonl_files(
+        None,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let statuses =
+        match evaluate_budgets(&mut context, &usage_tuples, budgets, currency_config).await {
+            Ok(statuses) => statuses,
+            Err(e) => {
+                handle_error(&e, options.json_output);
+                return Err(e);
+            }
+        };
+
+    // Only recent spikes are news; history still covers all data
+    let recent = UsageFilter {
+        since: Some(
+            context.timezone_calc.today_start()
+                - Duration::days(notify_config.anomaly_lookback_days.into()),
+        ),
+        ..Default::default()
+    };
+    let report = match detect_anomalies(
+        &context.usage_tracker,
+        &usage_tuples,
+        &mut context.pricing_manager,
+        &recent,
+        &context.timezone_calc,
+        notify_config.anomaly_threshold,
+    )
+    .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Describe spikes in the default currency, or all in USD if any conversion fails
+    let recent_anomalies = report.within(&recent);
+    let anomaly_total_usd: f64 = recent_anomalies.iter().map(|a| a.cost).sum();
+    let mut anomaly_currency = currency_config.default_currency.as_str();
+    let mut converted: Vec<Anomaly> = recent_anomalies.iter().map(|&a| a.clone()).collect();
+    for anomaly in &mut converted {
+        if let Err(e) =
+            convert_anomaly(&context.currency_converter, anomaly, anomaly_currency).await
+        {
+            if options.verbose {
+                let error_msg =
+                    format!("Failed to convert currency for {}: {}", anomaly.subject, e);
+                if options.json_output {
+                    eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                } else {
+                    eprintln!("Warning: {}", error_msg);
+                }
+            }
+            anomaly_currency = "USD";
+            converted = recent_anomalies.iter().map(|&a| a.clone()).collect();
+            break;
+        }
+    }
+    let converted_anomalies: Vec<&Anomaly> = converted.iter().collect();
+    let mut alerts = budget_alerts(&statuses, options.decimal_places);
+    alerts.extend(anomaly_alerts(
+        &converted_anomalies,
+        anomaly_currency,
+        options.decimal_places,
+    ));
+
+    let mut state = NotifyState::load();
+    let already_sent = alerts.iter().filter(|alert| state.is_sent(alert)).count();
+    alerts.retain(|alert| !state.is_sent(alert));
+    let now = Utc::now();
+
+    // Show what would be posted without sending or recording anything
+    if dry_run {
+        let payloads: Vec<serde_json::Value> = alerts
+            .iter()
+            .map(|alert| format.payload(alert, now))
+            .collect();
+        if options.json_output {
+            let json = if options.currency_provenance {
+                // Budget amounts are in each budget's currency
+                let mut conversions: Vec<CurrencyProvenance> = statuses
+                    .iter()
+                    .filter_map(|status| status.conversion.clone())
+                    .collect();
+                conversions.push(context.currency_converter.provenance(
+                    anomaly_currency,
+                    anomaly_total_usd,
+                    converted.iter().map(|a| a.cost).sum(),
+                ));
+                let currency = CurrencyProvenance::combine(&conversions);
+                CurrencyEnvelope::new(currency.as_ref(), &payloads, None).to_json()
+            } else {
+                serde_json::to_string_pretty(&payloads)
+            };
+            match json {
+                Ok(json) => println!("{json}"),
+                Err(e) => {
+                    println!(
+                        r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#
+                    );
+                    std::process::exit(1);
+                }
+            }
+        } else {
+            for (alert, payload) in alerts.iter().zip(&payloads) {
+                println!("Would send: {} ({})", alert.title, alert.key);
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(payload).unwrap_or_default()
+                );
+            }
+            println!(
+                "{} new alert(s), {} already sent",
+                alerts.len(),
+                already_sent
+            );
+        }
+        return Ok(());
+    }
+
+    let client = match reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent(concat!("ccost/", env!("CARGO_PKG_VERSION")))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            let e = anyhow::anyhow!("Failed to create HTTP client: {e}");
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let state_path = match NotifyState::get_state_path() {
+        Ok(path) => path,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    let webhook = Webhook {
+        client: &client,
+        url: &url,
+        format,
+        max_retries: notify_config.max_retries,
+    };
+    let failures = match deliver_alerts(
+        &webhook,
+        &alerts,
+        &mut state,
+        &state_path,
+        now,
+        options.verbose && !options.json_output,
+    )
+    .await
+    {
+        Ok(failures) => failures,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    let sent = alerts.len() - failures.len();
+
+    if !failures.is_empty() {
+        let e = anyhow::anyhow!(
+            "Failed to deliver {} of {} alert(s): {}",
+            failures.len(),
+            alerts.len(),
+            failures.join("; ")
+        );
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    let message = format!("Sent {sent} alert(s), {already_sent} already sent");
+    if options.json_output {
+        println!(r#"{{"status": "success", "message": "{message}"}}"#);
+    } else {
+        println!("{message}");
+    }
+    Ok(())
+}
+
+/// Where and how alerts are posted
+struct Webhook<'a> {
+    client: &'a reqwest::Client,
+    url: &'a str,
+    format: WebhookFormat,
+    max_retries: u32,
+}
+
+/// Post each alert, returning one message per alert that could not be delivered
+///
+/// Each delivery is recorded in the state file as soon as it succeeds, so a
+/// later failure does not cause it to be resent.
+async fn deliver_alerts(
+    webhook: &Webhook<'_>,
+    alerts: &[Alert],
+    state: &mut NotifyState,
+    state_path: &Path,
+    now: DateTime<Utc>,
+    print_sent: bool,
+) -> anyhow::Result<Vec<String>> {
+    let mut failures = Vec::new();
+    for alert in alerts {
+        let payload = webhook.format.payload(alert, now);
+        match post_with_retry(webhook.client, webhook.url, &payload, webhook.max_retries).await {
+            Ok(()) => {
+                state.mark_sent(alert, now);
+                state.save_to(state_path, now)?;
+                if print_sent {
+                    println!("Sent: {}", alert.title);
+                }
+            }
+            Err(e) => failures.push(format!("{}: {:#}", alert.title, e)),
+        }
+    }
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::alerts::{AlertKind, Severity};
+    use axum::{Json, Router, http::StatusCode, routing::post};
+    use tempfile::TempDir;
+
+    fn alert(key: &str) -> Alert {
+        Alert {
+            key: key.to_string(),
+            kind: AlertKind::Anomaly,
+            severity: Severity::Warning,
+            title: format!("Alert {key}"),
+            message: "day 2025-06-09 cost $40.00 vs typical $5.00 (score 9.5)".to_string(),
+            details: serde_json::Value::Null,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deliver_alerts_records_only_delivered() {
+        // The webhook rejects one alert outright and accepts the others
+        let app = Router::new().route(
+            "/hook",
+            post(|Json(payload): Json<serde_json::Value>| async move {
+                if payload["key"] == "bad" {
+                    StatusCode::BAD_REQUEST
+                } else {
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let temp_dir = TempDir::new().unwrap();
+        let state_path = temp_dir.path().join("notify_state.json");
+        let alerts = vec![alert("first"), alert("bad"), alert("last")];
+        let mut state = NotifyState::default();
+        let client = reqwest::Client::new();
+        let webhook = Webhook {
+            client: &client,
+            url: &url,
+            format: WebhookFormat::Generic,
+            max_retries: 3,
+        };
+        let failures = deliver_alerts(
+            &webhook,
+            &alerts,
+            &mut state,
+            &state_path,
+            Utc::now(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("Alert bad: Webhook returned 400"));
+        let saved = NotifyState::load_from(&state_path);
+        assert!(saved.is_sent(&alerts[0]));
+        assert!(!saved.is_sent(&alerts[1]));
+        assert!(saved.is_sent(&alerts[2]));
+    }
+}
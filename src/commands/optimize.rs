@@ -0,0 +1,176 @@
+// Cost optimization recommendations command
+use crate::analysis::UsageFilter;
+use crate::analysis::optimization::{RecommendationList, recommend_optimizations};
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::models::currency::CurrencyConverter;
+use crate::output::OutputFormat;
+use crate::parser::jsonl::UsageData;
+use crate::utils::{print_filter_info, resolve_filters};
+
+pub async fn handle_optimize_command(
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    // Process JSONL files
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let usage_tuples: Vec<(UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let mut recommendations = match recommend_optimizations(
+        &context.usage_tracker,
+        &usage_tuples,
+        &mut context.pricing_manager,
+        &usage_filter,
+    )
+    .await
+    {
+        Ok(recommendations) => recommendations,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Convert currencies if needed
+    let recommendations_usd = recommendations.clone();
+    let original_total_usd = recommendations.total_estimated_saving;
+    if options.currency != "USD" {
+        convert_recommendation_currency(
+            &context.currency_converter,
+            &mut recommendations,
+            options.currency,
+            options.verbose,
+            options.json_output,
+        )
+        .await;
+    }
+    let currency = context.currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        recommendations.total_estimated_saving,
+    );
+
+    // Display results
+    if options.json_output {
+        match recommendations.to_json_with_currency(
+            &currency,
+            &recommendations_usd,
+            options.currency_provenance,
+        ) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            recommendations.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored,
+            )
+        );
+    }
+
+    Ok(())
+}
+
+async fn convert_recommendation_currency(
+    currency_converter: &CurrencyConverter,
+    recommendations: &mut RecommendationList,
+    target_currency: &str,
+    verbose: bool,
+    json_output: bool,
+) {
+    for recommendation in &mut recommendations.recommendations {
+        let converted = async {
+            Ok::<_, anyhow::Error>((
+                currency_converter
+                    .convert_daily_from_usd(
+                        recommendation.current_cost,
+                        &recommendation.daily_cost_usd,
+                        target_currency,
+                    )
+                    .await?,
+                currency_converter
+                    .convert_daily_from_usd(
+                        recommendation.estimated_saving,
+                        &recommendation.daily_saving_usd,
+                        target_currency,
+                    )
+                    .await?,
+            ))
+        }
+        .await;
+
+        match converted {
+            Ok((current_cost, estimated_saving)) => {
+                recommendation.current_cost = current_cost;
+                recommendation.estimated_saving = estimated_saving;
+            }
+            Err(e) => {
+                if verbose {
+                    let error_msg = format!(
+                        "Failed to convert currency for {}: {}",
+                        recommendation.subject, e
+                    );
+                    if json_output {
+                        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                    } else {
+                        eprintln!("Warning: {}", error_msg);
+                    }
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+    recommendations.refresh_total();
+}
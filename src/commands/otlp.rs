@@ -0,0 +1,197 @@
+// OpenTelemetry (OTLP) export command
+use crate::analysis::UsageFilter;
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::config::OtlpConfig;
+use crate::output::otlp::{
+    OtlpExporter, UsageEvent, logs_payloads, metric_point_count, metrics_payload,
+};
+use crate::utils::{print_filter_info, resolve_filters};
+use chrono::Utc;
+
+pub async fn handle_otlp_command(
+    otlp_config: &OtlpConfig,
+    endpoint: Option<String>,
+    dry_run: bool,
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    // Process JSONL files (deduplicated)
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // One event per message, priced the same way as the usage views. Metrics are
+    // all-time cumulative sums, so --since/--until only limit the log records.
+    let mut all_events = Vec::new();
+    for enhanced in all_usage_data {
+        let message = enhanced.usage_data;
+        let Some(usage) = &message.usage else {
+            continue;
+        };
+        let Some(timestamp) = message
+            .timestamp
+            .as_deref()
+            .and_then(|ts| context.usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+        let model_name = message
+            .message
+            .as_ref()
+            .and_then(|m| m.model.clone())
+            .unwrap_or_else(|| "unknown".to_string());
+        if usage_filter
+            .model_name
+            .as_ref()
+            .is_some_and(|filter| *filter != model_name)
+        {
+            continue;
+        }
+        if !usage_filter.matches_branch(&message) {
+            continue;
+        }
+
+        let cost_usd = match message.cost_usd {
+            Some(embedded_cost) => embedded_cost,
+            None => match context
+                .usage_tracker
+                .calculate_enhanced_cost(usage, &model_name, &mut context.pricing_manager)
+                .await
+            {
+                Ok((cost, _)) => cost,
+                Err(e) => {
+                    handle_error(&e, options.json_output);
+                    return Err(e);
+                }
+            },
+        };
+
+        all_events.push(UsageEvent {
+            timestamp,
+            project: enhanced.project_name,
+            model: model_name,
+            session_id: message.session_id.clone(),
+            message_id: message.message.as_ref().and_then(|m| m.id.clone()),
+            request_id: message.request_id.clone(),
+            input_tokens: usage.input_tokens.unwrap_or(0),
+            output_tokens: usage.output_tokens.unwrap_or(0),
+            cache_creation_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+            cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+            cost_usd,
+        });
+    }
+    all_events.sort_by_key(|event| event.timestamp);
+    let events: Vec<UsageEvent> = all_events
+        .iter()
+        .filter(|event| {
+            !(usage_filter
+                .since
+                .is_some_and(|since| event.timestamp < since)
+                || usage_filter
+                    .until
+                    .is_some_and(|until| event.timestamp > until))
+        })
+        .cloned()
+        .collect();
+
+    if events.is_empty() {
+        if options.json_output {
+            println!(
+                r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
+            );
+        } else {
+            println!("No usage data found matching filters");
+        }
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let logs = logs_payloads(&events, &otlp_config.service_name, now);
+    let metrics = metrics_payload(&all_events, &otlp_config.service_name, now);
+
+    // Print the request bodies instead of sending them
+    if dry_run {
+        let requests = serde_json::json!({"logs": logs, "metrics": metrics});
+        match serde_json::to_string_pretty(&requests) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let endpoint = endpoint.unwrap_or_else(|| otlp_config.endpoint.clone());
+    let exporter = match OtlpExporter::new(&endpoint, &otlp_config.headers) {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    for payload in &logs {
+        if let Err(e) = exporter.export_logs(payload).await {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    }
+    if let Err(e) = exporter.export_metrics(&metrics).await {
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    let message = format!(
+        "Exported {} usage events and {} metric points to {}",
+        events.len(),
+        metric_point_count(&all_events),
+        endpoint
+    );
+    if options.json_output {
+        println!(r#"{{"status": "success", "message": "{message}"}}"#);
+    } else {
+        println!("{message}");
+    }
+    Ok(())
+}
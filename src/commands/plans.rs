@@ -0,0 +1,255 @@
+// Subscription plan comparison command
+use crate::analysis::UsageFilter;
+use crate::analysis::plans::{MonthlyApiCost, compare_plans};
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::config::PlanConfig;
+use crate::models::currency::CurrencyConverter;
+use crate::output::OutputFormat;
+use crate::utils::{apply_usage_filters, print_filter_info, resolve_filters};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+pub async fn handle_plans_command(
+    months: u32,
+    project: Option<String>,
+    filters: FilterArgs,
+    plans: &BTreeMap<String, PlanConfig>,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    if plans.is_empty() {
+        let e = anyhow::anyhow!(
+            "No plans configured. Add one with: ccost config set plans.pro.monthly_price_usd 20"
+        );
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    // Billing months: explicit --since/--until win, otherwise the last N months
+    let current_month = first_of_month(context.timezone_calc.local_date(Utc::now()));
+    let last_month = final_until
+        .map(|until| first_of_month(context.timezone_calc.local_date(until)))
+        .unwrap_or(current_month);
+    let first_month = match final_since {
+        Some(since) => first_of_month(context.timezone_calc.local_date(since)),
+        None => last_month
+            .checked_sub_months(Months::new(months.max(1) - 1))
+            .unwrap_or(last_month),
+    };
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    // Process JSONL files
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Bucket messages into local calendar months
+    let mut by_month: HashMap<NaiveDate, Vec<(crate::parser::jsonl::UsageData, String)>> =
+        HashMap::new();
+    for enhanced in all_usage_data {
+        let Some(timestamp) = enhanced
+            .usage_data
+            .timestamp
+            .as_deref()
+            .and_then(|ts| context.usage_tracker.parse_timestamp(ts).ok())
+        else {
+            continue;
+        };
+        let month = first_of_month(context.timezone_calc.local_date(timestamp));
+        if month < first_month || month > last_month {
+            continue;
+        }
+        by_month
+            .entry(month)
+            .or_default()
+            .push((enhanced.usage_data, enhanced.project_name));
+    }
+
+    // API-equivalent cost per month, including months without usage
+    let mut monthly_costs = Vec::new();
+    let mut monthly_daily_costs = Vec::new();
+    let mut month = first_month;
+    while month <= last_month {
+        let usage_tuples = by_month.remove(&month).unwrap_or_default();
+        let (project_usage, _) = match context
+            .calculate_usage_enhanced(usage_tuples, &usage_filter)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                handle_error(&e, options.json_output);
+                return Err(e);
+            }
+        };
+        let filtered_usage = apply_usage_filters(project_usage, &usage_filter);
+
+        monthly_costs.push(MonthlyApiCost {
+            month: month.format("%Y-%m").to_string(),
+            message_count: filtered_usage.iter().map(|p| p.message_count).sum(),
+            api_cost_usd: filtered_usage
+                .iter()
+                .fold(0.0, |total, p| total + p.total_cost_usd),
+            partial: month == current_month,
+        });
+
+        let mut daily_cost_usd = BTreeMap::new();
+        for project in &filtered_usage {
+            for (date, cost) in &project.daily_cost_usd {
+                *daily_cost_usd.entry(*date).or_insert(0.0) += cost;
+            }
+        }
+        monthly_daily_costs.push(daily_cost_usd);
+
+        month = match month.checked_add_months(Months::new(1)) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    // Convert currencies if needed: usage at its own dates' rates, plan prices at today's
+    let original_total_usd = total_api_cost(&monthly_costs);
+    let comparison_usd = compare_plans(&monthly_costs, plans);
+    let mut plans = plans.clone();
+    if options.currency != "USD" {
+        match convert_plan_inputs(
+            &context.currency_converter,
+            &monthly_costs,
+            &monthly_daily_costs,
+            &plans,
+            options.currency,
+        )
+        .await
+        {
+            Ok((converted_costs, converted_plans)) => {
+                monthly_costs = converted_costs;
+                plans = converted_plans;
+            }
+            Err(e) => {
+                if options.verbose {
+                    let error_msg =
+                        format!("Failed to convert currency to {}: {e}", options.currency);
+                    if options.json_output {
+                        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                    } else {
+                        eprintln!("Warning: {}", error_msg);
+                    }
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+
+    let currency = context.currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        total_api_cost(&monthly_costs),
+    );
+    let comparison = compare_plans(&monthly_costs, &plans);
+
+    // Display results
+    if options.json_output {
+        match comparison.to_json_with_currency(
+            &currency,
+            &comparison_usd,
+            options.currency_provenance,
+        ) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            comparison.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// Convert monthly costs and plan prices together so a failure leaves both in USD
+async fn convert_plan_inputs(
+    currency_converter: &CurrencyConverter,
+    monthly_costs: &[MonthlyApiCost],
+    monthly_daily_costs: &[BTreeMap<NaiveDate, f64>],
+    plans: &BTreeMap<String, PlanConfig>,
+    target_currency: &str,
+) -> anyhow::Result<(Vec<MonthlyApiCost>, BTreeMap<String, PlanConfig>)> {
+    let mut converted_costs = Vec::with_capacity(monthly_costs.len());
+    for (month, daily) in monthly_costs.iter().zip(monthly_daily_costs) {
+        converted_costs.push(MonthlyApiCost {
+            api_cost_usd: currency_converter
+                .convert_daily_from_usd(month.api_cost_usd, daily, target_currency)
+                .await?,
+            ..month.clone()
+        });
+    }
+
+    let mut converted_plans = BTreeMap::new();
+    for (name, plan) in plans {
+        converted_plans.insert(
+            name.clone(),
+            PlanConfig {
+                monthly_price_usd: currency_converter
+                    .convert_from_usd(plan.monthly_price_usd, target_currency)
+                    .await?,
+            },
+        );
+    }
+
+    Ok((converted_costs, converted_plans))
+}
+
+fn total_api_cost(monthly_costs: &[MonthlyApiCost]) -> f64 {
+    monthly_costs
+        .iter()
+        .fold(0.0, |total, m| total + m.api_cost_usd)
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1).expect("Valid first day of month")
+}
@@ -0,0 +1,467 @@
+// Pricing inspection command handler
+use crate::cli::PricingAction;
+use crate::commands::timeframe_utils::OutputOptions;
+use crate::config::PricingConfig;
+use crate::models::PricingManager;
+use crate::models::currency::CurrencyConverter;
+use crate::models::litellm::{EnhancedModelPricing, LiteLLMClient, PricingSource};
+use crate::output::OutputFormat;
+use crate::output::table::{CurrencyEnvelope, TableType, apply_list_table_style_with_color};
+use serde::Serialize;
+use tabled::{Table, Tabled};
+
+/// Effective per-model rates in USD per million tokens
+#[derive(Debug, Clone, Serialize)]
+pub struct PricingEntry {
+    pub model: String,
+    pub source: String,
+    pub input_cost_per_mtok: f64,
+    pub output_cost_per_mtok: f64,
+    pub cache_creation_cost_per_mtok: f64,
+    pub cache_read_cost_per_mtok: f64,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub effective: bool,
+}
+
+impl PricingEntry {
+    fn new(model: &str, pricing: &EnhancedModelPricing) -> Self {
+        Self {
+            model: model.to_string(),
+            source: pricing.source.to_string(),
+            input_cost_per_mtok: pricing.input_cost_per_mtok,
+            output_cost_per_mtok: pricing.output_cost_per_mtok,
+            cache_creation_cost_per_mtok: pricing.cache_creation_cost_per_mtok,
+            cache_read_cost_per_mtok: pricing.cache_read_cost_per_mtok,
+            effective: false,
+        }
+    }
+}
+
+/// Wrapper for pricing entries to implement OutputFormat
+#[derive(Debug, Clone, Serialize)]
+pub struct PricingEntryList(pub Vec<PricingEntry>);
+
+impl OutputFormat for PricingEntryList {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.0)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        _currency: &str,
+        _decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.0.is_empty() {
+            return "No pricing data found.".to_string();
+        }
+
+        #[derive(Tabled)]
+        struct PricingRow {
+            #[tabled(rename = "Model")]
+            model: String,
+            #[tabled(rename = "Source")]
+            source: String,
+            #[tabled(rename = "Input/MTok")]
+            input: String,
+            #[tabled(rename = "Output/MTok")]
+            output: String,
+            #[tabled(rename = "Cache Write/MTok")]
+            cache_creation: String,
+            #[tabled(rename = "Cache Read/MTok")]
+            cache_read: String,
+        }
+
+        let rows: Vec<PricingRow> = self
+            .0
+            .iter()
+            .map(|entry| PricingRow {
+                model: entry.model.clone(),
+                source: if entry.effective {
+                    format!("{} (effective)", entry.source)
+                } else {
+                    entry.source.clone()
+                },
+                input: format_rate(entry.input_cost_per_mtok),
+                output: format_rate(entry.output_cost_per_mtok),
+                cache_creation: format_rate(entry.cache_creation_cost_per_mtok),
+                cache_read: format_rate(entry.cache_read_cost_per_mtok),
+            })
+            .collect();
+
+        apply_list_table_style_with_color(Table::new(rows), colored, TableType::Pricing)
+    }
+}
+
+/// Static vs LiteLLM comparison for one model
+#[derive(Debug, Clone, Serialize)]
+pub struct PricingDiffEntry {
+    pub model: String,
+    pub static_pricing: Option<PricingEntry>,
+    pub litellm_pricing: Option<PricingEntry>,
+    pub status: String, // "match", "differs", "missing_in_litellm", "missing_in_static"
+}
+
+/// Wrapper for pricing diff entries to implement OutputFormat
+#[derive(Debug, Clone, Serialize)]
+pub struct PricingDiffList(pub Vec<PricingDiffEntry>);
+
+impl OutputFormat for PricingDiffList {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.0)
+    }
+
+    fn to_table_with_currency_and_color(
+        &self,
+        _currency: &str,
+        _decimal_places: u8,
+        colored: bool,
+    ) -> String {
+        if self.0.is_empty() {
+            return "No pricing data to compare.".to_string();
+        }
+
+        #[derive(Tabled)]
+        struct PricingDiffRow {
+            #[tabled(rename = "Model")]
+            model: String,
+            #[tabled(rename = "Status")]
+            status: String,
+            #[tabled(rename = "Input/MTok")]
+            input: String,
+            #[tabled(rename = "Output/MTok")]
+            output: String,
+            #[tabled(rename = "Cache Write/MTok")]
+            cache_creation: String,
+            #[tabled(rename = "Cache Read/MTok")]
+            cache_read: String,
+        }
+
+        let compare = |entry: &PricingDiffEntry, field: fn(&PricingEntry) -> f64| -> String {
+            match (&entry.static_pricing, &entry.litellm_pricing) {
+                (Some(s), Some(l)) if rates_equal(field(s), field(l)) => format_rate(field(s)),
+                (Some(s), Some(l)) => {
+                    format!("{} → {}", format_rate(field(s)), format_rate(field(l)))
+                }
+                (Some(s), None) => format_rate(field(s)),
+                (None, Some(l)) => format_rate(field(l)),
+                (None, None) => "-".to_string(),
+            }
+        };
+
+        let rows: Vec<PricingDiffRow> = self
+            .0
+            .iter()
+            .map(|entry| PricingDiffRow {
+                model: entry.model.clone(),
+                status: entry.status.replace('_', " "),
+                input: compare(entry, |p| p.input_cost_per_mtok),
+                output: compare(entry, |p| p.output_cost_per_mtok),
+                cache_creation: compare(entry, |p| p.cache_creation_cost_per_mtok),
+                cache_read: compare(entry, |p| p.cache_read_cost_per_mtok),
+            })
+            .collect();
+
+        apply_list_table_style_with_color(Table::new(rows), colored, TableType::Pricing)
+    }
+}
+
+/// Format a per-MTok rate in USD, keeping sub-cent precision for cache rates
+fn format_rate(rate: f64) -> String {
+    let formatted = format!("{rate:.4}");
+    let trimmed = formatted.trim_end_matches('0');
+    let decimals = trimmed.split('.').nth(1).map_or(0, |d| d.len());
+    if decimals < 2 {
+        format!("${rate:.2}")
+    } else {
+        format!("${trimmed}")
+    }
+}
+
+fn rates_equal(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+/// Compare the static table against LiteLLM data for every model known to either side
+pub fn diff_pricing(
+    manager: &PricingManager,
+    litellm: &crate::models::litellm::LiteLLMPricingData,
+) -> Vec<PricingDiffEntry> {
+    let mut models = manager.list_models();
+    // Only consider first-party Claude model ids from LiteLLM (skip provider-prefixed aliases)
+    models.extend(
+        litellm
+            .models
+            .keys()
+            .filter(|name| name.starts_with("claude-"))
+            .cloned(),
+    );
+    models.sort();
+    models.dedup();
+
+    models
+        .into_iter()
+        .filter_map(|model| {
+            let static_pricing = manager
+                .get_static_enhanced_pricing(&model)
+                .map(|p| PricingEntry::new(&model, &p));
+            let litellm_pricing = litellm
+                .models
+                .get(&model)
+                .map(|data| PricingEntry::new(&model, &EnhancedModelPricing::from_litellm(data)));
+
+            let status = match (&static_pricing, &litellm_pricing) {
+                (Some(s), Some(l)) => {
+                    let same = rates_equal(s.input_cost_per_mtok, l.input_cost_per_mtok)
+                        && rates_equal(s.output_cost_per_mtok, l.output_cost_per_mtok)
+                        && rates_equal(
+                            s.cache_creation_cost_per_mtok,
+                            l.cache_creation_cost_per_mtok,
+                        )
+                        && rates_equal(s.cache_read_cost_per_mtok, l.cache_read_cost_per_mtok);
+                    if same { "match" } else { "differs" }
+                }
+                (Some(_), None) => "missing_in_litellm",
+                (None, Some(_)) => "missing_in_static",
+                (None, None) => return None, // Override-only model
+            };
+
+            Some(PricingDiffEntry {
+                model,
+                static_pricing,
+                litellm_pricing,
+                status: status.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub async fn handle_pricing_action(
+    action: PricingAction,
+    pricing_config: &PricingConfig,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    match action {
+        PricingAction::List => {
+            let mut manager = PricingManager::from_config(pricing_config);
+            let _ = manager.initialize_live_pricing().await;
+
+            let mut models = manager.list_models();
+            models.extend(
+                manager
+                    .live_model_names()
+                    .into_iter()
+                    .filter(|name| name.starts_with("claude-")),
+            );
+            models.sort();
+            models.dedup();
+
+            let mut entries = Vec::new();
+            for model in models {
+                let pricing = manager.get_enhanced_pricing(&model).await;
+                entries.push(PricingEntry::new(&model, &pricing));
+            }
+
+            print_output(&PricingEntryList(entries), options);
+            if !options.json_output {
+                println!();
+                println!("Pricing mode: {}", manager.get_pricing_source_info());
+            }
+        }
+        PricingAction::Show { model } => {
+            let mut manager = PricingManager::from_config(pricing_config);
+            let _ = manager.initialize_live_pricing().await;
+            let effective = manager.get_enhanced_pricing(&model).await;
+
+            let mut entries = Vec::new();
+            if let Some(pricing) = manager.get_override(&model) {
+                entries.push(PricingEntry::new(&model, &pricing));
+            }
+            if let Some(data) = LiteLLMClient::read_persistent_cache()
+                .and_then(|cache| cache.data.models.get(&model).cloned())
+            {
+                entries.push(PricingEntry::new(
+                    &model,
+                    &EnhancedModelPricing::from_litellm(&data),
+                ));
+            }
+            match manager.get_static_enhanced_pricing(&model) {
+                Some(pricing) => entries.push(PricingEntry::new(&model, &pricing)),
+                None if effective.source == PricingSource::StaticFallback => {
+                    // Unknown everywhere - show the generic fallback that will actually be used
+                    entries.push(PricingEntry::new(&model, &effective));
+                }
+                None => {}
+            }
+
+            let effective_source = effective.source.to_string();
+            for entry in &mut entries {
+                entry.effective = entry.source == effective_source;
+            }
+
+            print_output(&PricingEntryList(entries), options);
+            if !options.json_output && manager.get_static_enhanced_pricing(&model).is_none() {
+                println!();
+                println!(
+                    "Note: {model} is not in the static table; unknown models use Sonnet rates"
+                );
+            }
+        }
+        PricingAction::Refresh => {
+            let mut client = LiteLLMClient::new();
+            match client.refresh_pricing_data().await {
+                Ok(data) => {
+                    let claude_models = data
+                        .models
+                        .keys()
+                        .filter(|name| name.starts_with("claude-"))
+                        .count();
+                    let cache_path = LiteLLMClient::get_persistent_cache_path()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default();
+                    if options.json_output {
+                        println!(
+                            "{}",
+                            serde_json::json!({
+                                "status": "success",
+                                "models": data.models.len(),
+                                "claude_models": claude_models,
+                                "cache_path": cache_path,
+                            })
+                        );
+                    } else {
+                        println!(
+                            "Refreshed LiteLLM pricing: {} models ({} Claude) written to {}",
+                            data.models.len(),
+                            claude_models,
+                            cache_path
+                        );
+                    }
+                }
+                Err(e) => {
+                    if options.json_output {
+                        println!(
+                            r#"{{"status": "error", "message": "Failed to refresh pricing: {e}"}}"#
+                        );
+                    } else {
+                        eprintln!("Error: Failed to refresh pricing: {e}");
+                    }
+                    std::process::exit(1);
+                }
+            }
+        }
+        PricingAction::Diff => {
+            let Some(cache) = LiteLLMClient::read_persistent_cache() else {
+                if options.json_output {
+                    println!(
+                        r#"{{"status": "error", "message": "No LiteLLM cache found. Run 'ccost pricing refresh' first"}}"#
+                    );
+                } else {
+                    eprintln!("Error: No LiteLLM cache found. Run 'ccost pricing refresh' first");
+                }
+                std::process::exit(1);
+            };
+
+            let manager = PricingManager::from_config(pricing_config);
+            let diff = PricingDiffList(diff_pricing(&manager, &cache.data));
+
+            print_output(&diff, options);
+            if !options.json_output {
+                let stale = diff.0.iter().filter(|d| d.status == "differs").count();
+                println!();
+                println!(
+                    "LiteLLM cache fetched {}; {} static model(s) differ",
+                    cache.timestamp.format("%Y-%m-%d %H:%M UTC"),
+                    stale
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_output<T: OutputFormat + Serialize>(output: &T, options: &OutputOptions<'_>) {
+    if options.json_output {
+        // Prices are always USD; the envelope says so rather than omitting the block
+        let json = if options.currency_provenance {
+            let currency = CurrencyConverter::new().provenance("USD", 0.0, 0.0);
+            CurrencyEnvelope::new(Some(&currency), output, None).to_json()
+        } else {
+            output.to_json()
+        };
+        match json {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            output.to_table_with_currency_and_color("USD", 2, options.colored)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::litellm::{LiteLLMModelData, LiteLLMPricingData};
+    use std::collections::HashMap;
+
+    fn litellm_model(input: f64, output: f64) -> LiteLLMModelData {
+        LiteLLMModelData {
+            input_cost_per_token: Some(input / 1_000_000.0),
+            output_cost_per_token: Some(output / 1_000_000.0),
+            cache_creation_input_token_cost: Some(input * 1.25 / 1_000_000.0),
+            cache_read_input_token_cost: Some(input * 0.1 / 1_000_000.0),
+            max_tokens: None,
+            max_input_tokens: None,
+            max_output_tokens: None,
+        }
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(3.0), "$3.00");
+        assert_eq!(format_rate(0.3), "$0.30");
+        assert_eq!(format_rate(0.075), "$0.075");
+        assert_eq!(format_rate(18.75), "$18.75");
+    }
+
+    #[test]
+    fn test_diff_pricing_statuses() {
+        let mut models = HashMap::new();
+        models.insert(
+            "claude-opus-4-20250514".to_string(),
+            litellm_model(15.0, 75.0),
+        );
+        models.insert("claude-new-model".to_string(), litellm_model(1.0, 2.0));
+        models.insert(
+            "bedrock/claude-opus-4-20250514".to_string(),
+            litellm_model(15.0, 75.0),
+        );
+        let litellm = LiteLLMPricingData { models };
+
+        let diff = diff_pricing(&PricingManager::new(), &litellm);
+        let status = |model: &str| {
+            diff.iter()
+                .find(|d| d.model == model)
+                .map(|d| d.status.clone())
+        };
+
+        // Static table uses one cache rate for both writes and reads, so opus differs
+        assert_eq!(status("claude-opus-4-20250514").as_deref(), Some("differs"));
+        assert_eq!(
+            status("claude-sonnet-4-20250514").as_deref(),
+            Some("missing_in_litellm")
+        );
+        assert_eq!(
+            status("claude-new-model").as_deref(),
+            Some("missing_in_static")
+        );
+        assert_eq!(status("bedrock/claude-opus-4-20250514"), None);
+    }
+}
@@ -1,5 +1,6 @@
 // Projects command handler
 use crate::analysis::{CostCalculationMode, UsageFilter, UsageTracker};
+use crate::commands::timeframe_utils::OutputOptions;
 use crate::config::Config;
 use crate::models::PricingManager;
 use crate::models::currency::CurrencyConverter;
@@ -14,12 +15,7 @@ use std::path::PathBuf;
 
 pub async fn handle_projects_command(
     projects: Option<String>,
-    target_currency: &str,
-    decimal_places: u8,
-    json_output: bool,
-    verbose: bool,
-    colored: bool,
-    hidden: bool,
+    options: &OutputOptions<'_>,
 ) -> anyhow::Result<()> {
     // Load config for timezone and date format settings
     let config = Config::load().unwrap_or_default();
@@ -36,7 +32,7 @@ pub async fn handle_projects_command(
     let _date_formatter = match DateFormatter::new(&config.output.date_format) {
         Ok(formatter) => formatter,
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(
                     r#"{{"status": "error", "message": "Invalid date format configuration: {e}"}}"#
                 );
@@ -58,17 +54,10 @@ pub async fn handle_projects_command(
         PathBuf::from(&config.general.claude_projects_path)
     };
 
-    // Initialize pricing manager based on configuration
-    // Default to static pricing for speed, only use live when explicitly requested
-    let mut pricing_manager = match config.pricing.source.as_str() {
-        "live" => PricingManager::with_live_pricing(),
-        _ => PricingManager::new(), // "auto", "static" or unknown - all use static by default
-    };
-
-    // Only enable live pricing when explicitly set to "live"
-    pricing_manager.set_live_pricing(config.pricing.source == "live");
+    // Initialize pricing manager from configuration (source and per-model overrides)
+    let mut pricing_manager = PricingManager::from_config(&config.pricing);
 
-    // Pre-fetch pricing data if live pricing is enabled
+    // Pre-fetch pricing data for live/auto modes
     if let Err(_) = pricing_manager.initialize_live_pricing().await {
         // If live pricing fails, it will fall back to static during calculations
     }
@@ -83,9 +72,10 @@ pub async fn handle_projects_command(
         model_name: None,
         since: None,
         until: None,
+        branch_name: None,
     };
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         if let Some(ref filters) = project_filters {
             println!(
                 "Filtering projects: {}",
@@ -104,7 +94,7 @@ pub async fn handle_projects_command(
     let jsonl_files = match parser.find_jsonl_files() {
         Ok(files) => files,
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(r#"{{"status": "error", "message": "Failed to find JSONL files: {e}"}}"#);
             } else {
                 eprintln!("Error: Failed to find JSONL files: {e}");
@@ -118,7 +108,7 @@ pub async fn handle_projects_command(
     };
 
     if jsonl_files.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(r#"{{"status": "warning", "message": "No JSONL files found", "data": []}}"#);
         } else {
             println!("No Claude usage data found in {}", projects_dir.display());
@@ -127,7 +117,7 @@ pub async fn handle_projects_command(
         return Ok(());
     }
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!("Found {} JSONL files", jsonl_files.len());
     }
 
@@ -138,12 +128,12 @@ pub async fn handle_projects_command(
     let mut unique_messages = 0;
 
     for file_path in jsonl_files {
-        match parser.parse_file_with_verbose(&file_path, verbose) {
+        match parser.parse_file_with_verbose(&file_path, options.verbose) {
             Ok(parsed_conversation) => {
                 // Use unified project name extraction for consistency
                 let raw_project_name =
                     parser.get_unified_project_name(&file_path, &parsed_conversation.messages);
-                let project_name = maybe_hide_project_name(&raw_project_name, hidden);
+                let project_name = maybe_hide_project_name(&raw_project_name, options.hidden);
 
                 // Apply project filter if specified
                 if let Some(ref filter_projects) = project_filters {
@@ -169,8 +159,8 @@ pub async fn handle_projects_command(
                         }
                     }
                     Err(e) => {
-                        if verbose {
-                            if json_output {
+                        if options.verbose {
+                            if options.json_output {
                                 eprintln!(
                                     r#"{{"status": "warning", "message": "Failed to deduplicate file {}: {}"}}"#,
                                     file_path.display(),
@@ -191,8 +181,8 @@ pub async fn handle_projects_command(
                 files_processed += 1;
             }
             Err(e) => {
-                if verbose {
-                    if json_output {
+                if options.verbose {
+                    if options.json_output {
                         eprintln!(
                             r#"{{"status": "warning", "message": "Failed to parse file {}: {}"}}"#,
                             file_path.display(),
@@ -210,14 +200,14 @@ pub async fn handle_projects_command(
         }
     }
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!(
             "Processed {files_processed} files, {total_messages} total messages, {unique_messages} unique messages"
         );
     }
 
     if all_usage_data.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -244,7 +234,7 @@ pub async fn handle_projects_command(
     {
         Ok((usage, source)) => (usage, source),
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(r#"{{"status": "error", "message": "Failed to calculate usage: {e}"}}"#);
             } else {
                 eprintln!("Error: Failed to calculate usage: {e}");
@@ -254,7 +244,7 @@ pub async fn handle_projects_command(
     };
 
     // Display pricing source in verbose mode
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         if let Some(source) = &pricing_source {
             println!("Pricing source: {}", source);
         }
@@ -264,21 +254,28 @@ pub async fn handle_projects_command(
     let mut filtered_usage = apply_usage_filters(project_usage, &usage_filter);
 
     // Convert currencies if needed
-    if target_currency != "USD" {
-        let currency_converter = CurrencyConverter::new();
-
+    let currency_converter = CurrencyConverter::from_config(&config.currency);
+    let filtered_usage_usd = filtered_usage.clone();
+    let original_total_usd = filtered_usage
+        .iter()
+        .fold(0.0, |total, p| total + p.total_cost_usd);
+    if options.currency != "USD" {
         // Convert all USD amounts to target currency
         for project in &mut filtered_usage {
             match currency_converter
-                .convert_from_usd(project.total_cost_usd, target_currency)
+                .convert_daily_from_usd(
+                    project.total_cost_usd,
+                    &project.daily_cost_usd,
+                    options.currency,
+                )
                 .await
             {
                 Ok(converted_cost) => {
                     project.total_cost_usd = converted_cost; // Reusing the USD field for converted amount
                 }
                 Err(e) => {
-                    if verbose {
-                        if json_output {
+                    if options.verbose {
+                        if options.json_output {
                             eprintln!(
                                 r#"{{"status": "warning", "message": "Failed to convert currency for {}: {}"}}"#,
                                 project.project_name, e
@@ -297,7 +294,11 @@ pub async fn handle_projects_command(
             // Convert model-level costs too
             for model_usage in project.model_usage.values_mut() {
                 match currency_converter
-                    .convert_from_usd(model_usage.cost_usd, target_currency)
+                    .convert_daily_from_usd(
+                        model_usage.cost_usd,
+                        &model_usage.daily_cost_usd,
+                        options.currency,
+                    )
                     .await
                 {
                     Ok(converted_cost) => {
@@ -312,7 +313,7 @@ pub async fn handle_projects_command(
     }
 
     if filtered_usage.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -322,9 +323,21 @@ pub async fn handle_projects_command(
         return Ok(());
     }
 
+    let currency = currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        filtered_usage
+            .iter()
+            .fold(0.0, |total, p| total + p.total_cost_usd),
+    );
+
     // Display results
-    if json_output {
-        match filtered_usage.to_json() {
+    if options.json_output {
+        match filtered_usage.to_json_with_currency(
+            &currency,
+            &filtered_usage_usd,
+            options.currency_provenance,
+        ) {
             Ok(json) => println!("{json}"),
             Err(e) => {
                 println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
@@ -335,9 +348,9 @@ pub async fn handle_projects_command(
         println!(
             "{}",
             filtered_usage.to_table_with_currency_and_color(
-                target_currency,
-                decimal_places,
-                colored
+                options.currency,
+                options.decimal_places,
+                options.colored,
             )
         );
     }
@@ -0,0 +1,51 @@
+// Local HTTP JSON API command
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::server::{ServerState, run};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+pub async fn handle_serve_command(port: u16, options: &OutputOptions<'_>) -> anyhow::Result<()> {
+    // Initialize context
+    let context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Only bind to the loopback interface; the API has no authentication
+    let address = format!("127.0.0.1:{port}");
+    let listener = match TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            let e = anyhow::anyhow!("Failed to bind {}: {}", address, e);
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    if options.json_output {
+        println!(
+            r#"{{"status": "success", "message": "Serving on http://{}"}}"#,
+            address
+        );
+    } else {
+        println!(
+            "Serving ccost API on http://{address} (/usage, /projects, /daily, /models, /sessions, /metrics) · Ctrl-C to stop"
+        );
+    }
+
+    let state = Arc::new(ServerState {
+        context: Mutex::new(context),
+        default_currency: options.currency.to_string(),
+        hidden: options.hidden,
+        currency_provenance: options.currency_provenance,
+    });
+    if let Err(e) = run(listener, state).await {
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+// Statusline command for Claude Code and shell prompts
+use crate::analysis::statusline::StatuslineCache;
+use crate::analysis::{CostCalculationMode, TimezoneCalculator, UsageTracker};
+use crate::commands::timeframe_utils::{OutputOptions, UsageTimeframe, handle_error};
+use crate::config::Config;
+use crate::models::PricingManager;
+use crate::models::currency::CurrencyConverter;
+use crate::output::OutputFormat;
+use crate::parser::jsonl::JsonlParser;
+use crate::utils::resolve_filters;
+use chrono::Utc;
+use serde::Deserialize;
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+
+/// Session JSON that Claude Code pipes to a statusline command
+#[derive(Debug, Default, Deserialize)]
+struct StatuslineInput {
+    session_id: Option<String>,
+    transcript_path: Option<PathBuf>,
+    model: Option<StatuslineModel>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StatuslineModel {
+    id: Option<String>,
+    display_name: Option<String>,
+}
+
+/// Read the session JSON from stdin; a terminal or empty stdin means no session
+fn read_input() -> anyhow::Result<StatuslineInput> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Ok(StatuslineInput::default());
+    }
+
+    let mut contents = String::new();
+    stdin
+        .read_to_string(&mut contents)
+        .map_err(|e| anyhow::anyhow!("Failed to read stdin: {}", e))?;
+    if contents.trim().is_empty() {
+        return Ok(StatuslineInput::default());
+    }
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid statusline JSON on stdin: {}", e))
+}
+
+fn warn(message: &str, json_output: bool) {
+    if json_output {
+        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, message);
+    } else {
+        eprintln!("Warning: {}", message);
+    }
+}
+
+pub async fn handle_statusline_command(options: &OutputOptions<'_>) -> anyhow::Result<()> {
+    let input = match read_input() {
+        Ok(input) => input,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let config = Config::load().unwrap_or_default();
+    let timezone_calc = match TimezoneCalculator::new(options.timezone, options.daily_cutoff_hour) {
+        Ok(calc) => calc,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let projects_dir = if config.general.claude_projects_path.starts_with("~/") {
+        // Expand tilde to home directory
+        if let Some(home_dir) = dirs::home_dir() {
+            home_dir.join(&config.general.claude_projects_path[2..])
+        } else {
+            PathBuf::from(&config.general.claude_projects_path)
+        }
+    } else {
+        PathBuf::from(&config.general.claude_projects_path)
+    };
+
+    // Only lines appended since the previous run are parsed
+    let parser = JsonlParser::new(projects_dir.clone());
+    let mut cache = StatuslineCache::load(&projects_dir);
+    let appended = match cache.read_appended(&parser, input.transcript_path.as_deref()) {
+        Ok(appended) => appended,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Pricing data is only needed for new messages without an embedded cost
+    let mut pricing_manager = PricingManager::from_config(&config.pricing);
+    if appended
+        .iter()
+        .any(|m| m.usage.is_some() && m.cost_usd.is_none())
+    {
+        // If live pricing fails, it will fall back to static during calculations
+        let _ = pricing_manager.initialize_live_pricing().await;
+    }
+
+    let now = Utc::now();
+    let usage_tracker = UsageTracker::new(CostCalculationMode::Auto);
+    if let Err(e) = cache
+        .add_messages(appended, &usage_tracker, &mut pricing_manager, now)
+        .await
+    {
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+    // A stale cache only costs speed on the next run
+    if let Err(e) = cache.save()
+        && options.verbose
+    {
+        warn(
+            &format!("Failed to save statusline cache: {e}"),
+            options.json_output,
+        );
+    }
+
+    let (_, today_start, _, _) = resolve_filters(
+        Some(UsageTimeframe::Today),
+        None,
+        None,
+        None,
+        None,
+        &timezone_calc,
+    );
+    let mut summary = cache.summarize(input.session_id.as_deref(), today_start.unwrap_or(now), now);
+    summary.model = input
+        .model
+        .and_then(|model| model.display_name.or(model.id));
+
+    // Convert currencies if needed; every amount uses the latest rate
+    let summary_usd = summary.clone();
+    let original_total_usd = summary.today_cost;
+    let currency_converter = CurrencyConverter::from_config(&config.currency);
+    if options.currency != "USD" {
+        match currency_converter
+            .convert_from_usd(1.0, options.currency)
+            .await
+        {
+            Ok(rate) => summary.scale(rate),
+            Err(e) => {
+                if options.verbose {
+                    warn(
+                        &format!("Failed to convert currency: {e}"),
+                        options.json_output,
+                    );
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+
+    // Display results
+    if options.json_output {
+        let currency =
+            currency_converter.provenance(options.currency, original_total_usd, summary.today_cost);
+        match summary.to_json_with_currency(&currency, &summary_usd, options.currency_provenance) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!(
+            "{}",
+            summary.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored
+            )
+        );
+    }
+
+    Ok(())
+}
@@ -1,32 +1,74 @@
 // This month's usage command
 use crate::analysis::UsageFilter;
-use crate::commands::timeframe_utils::{TimeframeContext, UsageTimeframe, handle_error};
+use crate::analysis::forecast::{ForecastMethod, daily_cost_series, forecast_month};
+use crate::cli::args::FilterArgs;
+use crate::commands::anomalies::print_anomaly_summary;
+use crate::commands::timeframe_utils::{
+    OutputOptions, TimeframeContext, UsageTimeframe, handle_error,
+};
+use crate::commands::watch::run_or_watch;
+use crate::config::ForecastConfig;
+use crate::models::currency::RateSource;
 use crate::utils::{apply_usage_filters, print_filter_info, resolve_filters};
+use chrono::Utc;
+use std::collections::BTreeMap;
 
 pub async fn handle_this_month_command(
     project: Option<String>,
-    since: Option<String>,
-    until: Option<String>,
-    model: Option<String>,
-    target_currency: &str,
-    decimal_places: u8,
-    json_output: bool,
-    verbose: bool,
-    colored: bool,
-    hidden: bool,
-    timezone_name: &str,
-    daily_cutoff_hour: u8,
-    date_format: &str,
+    filters: FilterArgs,
+    forecast_config: &ForecastConfig,
+    options: &OutputOptions<'_>,
 ) -> anyhow::Result<()> {
     // Initialize context
-    let mut context =
-        match TimeframeContext::new(timezone_name, daily_cutoff_hour, date_format).await {
-            Ok(ctx) => ctx,
-            Err(e) => {
-                handle_error(&e, json_output);
-                return Err(e);
-            }
-        };
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    run_or_watch(
+        &mut context,
+        options.watch,
+        options.json_output,
+        async |context| {
+            show_this_month(
+                context,
+                project.clone(),
+                filters.clone(),
+                forecast_config,
+                options,
+            )
+            .await
+        },
+    )
+    .await
+}
+
+/// Load usage and print this month's totals and forecast
+async fn show_this_month(
+    context: &mut TimeframeContext,
+    project: Option<String>,
+    filters: FilterArgs,
+    forecast_config: &ForecastConfig,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    let forecast_method = match ForecastMethod::from_config_str(&forecast_config.method) {
+        Ok(method) => method,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+    // Only forecast the calendar month, not an explicit --since/--until range
+    let forecast_enabled = since.is_none() && until.is_none();
 
     // Parse timeframe into date filters
     let (final_project, final_since, final_until, final_model) = resolve_filters(
@@ -44,24 +86,29 @@ pub async fn handle_this_month_command(
         model_name: final_model.clone(),
         since: final_since,
         until: final_until,
+        branch_name: branch,
     };
 
-    if verbose {
-        print_filter_info(&usage_filter, json_output, &context.date_formatter);
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
     }
 
     // Process JSONL files
-    let all_usage_data =
-        match context.process_jsonl_files(final_project, verbose, json_output, hidden) {
-            Ok(data) => data,
-            Err(e) => {
-                handle_error(&e, json_output);
-                return Err(e);
-            }
-        };
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
 
     if all_usage_data.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -77,6 +124,41 @@ pub async fn handle_this_month_command(
         .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
         .collect();
 
+    // Daily cost series for the forecast, taken before the data is consumed
+    let today = context.timezone_calc.local_date(Utc::now());
+    let daily_costs = if forecast_enabled {
+        match daily_cost_series(
+            &context.usage_tracker,
+            &usage_tuples,
+            &mut context.pricing_manager,
+            &usage_filter,
+            &context.timezone_calc,
+        )
+        .await
+        {
+            Ok(series) => series,
+            Err(e) => {
+                handle_error(&e, options.json_output);
+                return Err(e);
+            }
+        }
+    } else {
+        BTreeMap::new()
+    };
+
+    if options.verbose {
+        print_anomaly_summary(
+            &context.usage_tracker,
+            &usage_tuples,
+            &mut context.pricing_manager,
+            &usage_filter,
+            &context.timezone_calc,
+            &context.currency_converter,
+            options,
+        )
+        .await;
+    }
+
     // Calculate usage with enhanced pricing (supports live pricing)
     let (project_usage, pricing_source) = match context
         .calculate_usage_enhanced(usage_tuples, &usage_filter)
@@ -84,13 +166,13 @@ pub async fn handle_this_month_command(
     {
         Ok((usage, source)) => (usage, source),
         Err(e) => {
-            handle_error(&e, json_output);
+            handle_error(&e, options.json_output);
             return Err(e);
         }
     };
 
     // Display pricing source in verbose mode
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         if let Some(source) = &pricing_source {
             println!("Pricing source: {}", source);
         }
@@ -99,21 +181,47 @@ pub async fn handle_this_month_command(
     // Apply remaining filters to the calculated usage
     let mut filtered_usage = apply_usage_filters(project_usage, &usage_filter);
 
+    let month_to_date_usd = filtered_usage
+        .iter()
+        .fold(0.0, |total, p| total + p.total_cost_usd);
+    let mut forecast = forecast_enabled.then(|| {
+        forecast_month(
+            &daily_costs,
+            month_to_date_usd,
+            today,
+            forecast_method,
+            forecast_config.smoothing_alpha,
+        )
+    });
+
     // Convert currencies if needed
     if let Err(e) = context
-        .convert_currency(&mut filtered_usage, target_currency, verbose, json_output)
+        .convert_currency(
+            &mut filtered_usage,
+            options.currency,
+            options.verbose,
+            options.json_output,
+        )
         .await
     {
-        handle_error(&e, json_output);
+        handle_error(&e, options.json_output);
         return Err(e);
     }
 
+    // Month-to-date as converted above; the rest of the month at today's rate
+    if options.currency != "USD"
+        && let Some(forecast) = forecast.as_mut()
+        && let Some(provenance) = &context.currency_provenance
+        && provenance.source != RateSource::Unavailable
+        && let Ok(rate) = context
+            .currency_converter
+            .convert_from_usd(1.0, options.currency)
+            .await
+    {
+        forecast.convert(provenance.converted_total, rate);
+    }
+    context.forecast = forecast;
+
     // Display results
-    context.display_results(
-        &filtered_usage,
-        target_currency,
-        decimal_places,
-        json_output,
-        colored,
-    )
+    context.display_results(&filtered_usage, options)
 }
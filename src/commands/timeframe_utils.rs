@@ -1,28 +1,51 @@
 // Shared utilities for timeframe-based commands
+use crate::analysis::forecast::MonthForecast;
 use crate::analysis::{
     CostCalculationMode, TimezoneCalculator, UsageFilter, UsageTracker, usage::ProjectUsage,
 };
 use crate::config::Config;
 use crate::models::PricingManager;
-use crate::models::currency::CurrencyConverter;
+use crate::models::currency::{CurrencyConverter, CurrencyProvenance};
 use crate::output::OutputFormat;
-use crate::parser::deduplication::DeduplicationEngine;
+use crate::output::chart::{project_charts, terminal_width};
+use crate::parser::incremental::IncrementalParser;
 use crate::parser::jsonl::JsonlParser;
-use crate::utils::{DateFormatter, EnhancedUsageData, maybe_hide_project_name};
+use crate::utils::{DateFormatter, EnhancedUsageData};
+use serde::Serialize;
 use std::path::PathBuf;
 
 // Re-export the UsageTimeframe from usage.rs to avoid duplication
 pub use crate::commands::usage::UsageTimeframe;
 
+/// Output settings shared by every report command
+#[derive(Debug, Clone, Copy)]
+pub struct OutputOptions<'a> {
+    pub currency: &'a str, // Target currency
+    pub decimal_places: u8,
+    pub json_output: bool,
+    pub verbose: bool,
+    pub colored: bool,
+    pub chart: bool, // Bar charts and sparklines below tables
+    pub watch: bool, // Keep refreshing as new messages are written
+    pub hidden: bool,
+    pub timezone: &'a str,
+    pub daily_cutoff_hour: u8,
+    pub date_format: &'a str,
+    pub currency_provenance: bool, // Wrap JSON in the currency envelope (--currency-provenance)
+}
+
 /// Common configuration and setup for timeframe commands
 pub struct TimeframeContext {
     pub pricing_manager: PricingManager,
     pub usage_tracker: UsageTracker,
-    pub parser: JsonlParser,
-    pub dedup_engine: DeduplicationEngine,
+    pub incremental_parser: IncrementalParser, // Keeps read offsets and dedup state between loads
     pub date_formatter: DateFormatter,
     pub timezone_calc: TimezoneCalculator,
     pub projects_dir: PathBuf,
+    pub currency_converter: CurrencyConverter,
+    pub currency_provenance: Option<CurrencyProvenance>, // Set by convert_currency
+    pub usage_usd: Option<Vec<ProjectUsage>>, // Usage before convert_currency, for data_usd
+    pub forecast: Option<MonthForecast>,      // Month-end projection (this-month only)
 }
 
 impl TimeframeContext {
@@ -53,37 +76,46 @@ impl TimeframeContext {
             PathBuf::from(&config.general.claude_projects_path)
         };
 
-        // Initialize pricing manager based on configuration
-        // Default to static pricing for speed, only use live when explicitly requested
-        let mut pricing_manager = match config.pricing.source.as_str() {
-            "live" => PricingManager::with_live_pricing(),
-            _ => PricingManager::new(), // "auto", "static" or unknown - all use static by default
-        };
-
-        // Only enable live pricing when explicitly set to "live"
-        pricing_manager.set_live_pricing(config.pricing.source == "live");
+        // Initialize pricing manager from configuration (source and per-model overrides)
+        let mut pricing_manager = PricingManager::from_config(&config.pricing);
 
-        // Pre-fetch pricing data if live pricing is enabled
+        // Pre-fetch pricing data for live/auto modes
         if let Err(_) = pricing_manager.initialize_live_pricing().await {
             // If live pricing fails, it will fall back to static during calculations
         }
 
         let usage_tracker = UsageTracker::new(CostCalculationMode::Auto);
-        let parser = JsonlParser::new(projects_dir.clone());
-        let dedup_engine = DeduplicationEngine::new();
+        let incremental_parser = IncrementalParser::new(JsonlParser::new(projects_dir.clone()));
+        let currency_converter = CurrencyConverter::from_config(&config.currency);
 
         Ok(Self {
             pricing_manager,
             usage_tracker,
-            parser,
-            dedup_engine,
+            incremental_parser,
             date_formatter,
             timezone_calc,
             projects_dir,
+            currency_converter,
+            currency_provenance: None,
+            usage_usd: None,
+            forecast: None,
         })
     }
 
+    /// Create a context for the timezone and date format in `options`
+    pub async fn from_options(options: &OutputOptions<'_>) -> anyhow::Result<Self> {
+        Self::new(
+            options.timezone,
+            options.daily_cutoff_hour,
+            options.date_format,
+        )
+        .await
+    }
+
     /// Process JSONL files and return enhanced usage data
+    ///
+    /// Later calls only parse lines appended since the previous call and return
+    /// the accumulated data, so watch mode can reload cheaply.
     pub fn process_jsonl_files(
         &mut self,
         project: Option<String>,
@@ -98,12 +130,12 @@ impl TimeframeContext {
             );
         }
 
-        let jsonl_files = self
-            .parser
-            .find_jsonl_files()
-            .map_err(|e| anyhow::anyhow!("Failed to find JSONL files: {}", e))?;
+        // Parse new lines with deduplication
+        let stats = self
+            .incremental_parser
+            .refresh(project.as_deref(), verbose, json_output)?;
 
-        if jsonl_files.is_empty() {
+        if stats.files_found == 0 {
             if json_output {
                 println!(
                     r#"{{"status": "warning", "message": "No JSONL files found", "data": []}}"#
@@ -119,113 +151,52 @@ impl TimeframeContext {
         }
 
         if verbose && !json_output {
-            println!("Found {} JSONL files", jsonl_files.len());
-        }
-
-        // Parse all files with deduplication
-        let mut all_usage_data = Vec::new();
-        let mut files_processed = 0;
-        let mut total_messages = 0;
-        let mut unique_messages = 0;
-
-        for file_path in jsonl_files {
-            match self.parser.parse_file_with_verbose(&file_path, verbose) {
-                Ok(parsed_conversation) => {
-                    // Use unified project name extraction for consistency
-                    let raw_project_name = self
-                        .parser
-                        .get_unified_project_name(&file_path, &parsed_conversation.messages);
-                    let project_name = maybe_hide_project_name(&raw_project_name, hidden);
-
-                    // Apply project filter if specified
-                    if let Some(ref filter_project) = project {
-                        if raw_project_name != *filter_project {
-                            continue;
-                        }
-                    }
-
-                    total_messages += parsed_conversation.messages.len();
-
-                    // Apply deduplication
-                    match self
-                        .dedup_engine
-                        .filter_duplicates(parsed_conversation.messages, &project_name)
-                    {
-                        Ok(unique_data) => {
-                            unique_messages += unique_data.len();
-
-                            // Create enhanced usage data with project name
-                            for data in unique_data {
-                                let enhanced_data = EnhancedUsageData {
-                                    usage_data: data,
-                                    project_name: project_name.clone(),
-                                };
-                                all_usage_data.push(enhanced_data);
-                            }
-                        }
-                        Err(e) => {
-                            if verbose {
-                                let error_msg = format!(
-                                    "Failed to deduplicate file {}: {}",
-                                    file_path.display(),
-                                    e
-                                );
-                                if json_output {
-                                    eprintln!(
-                                        r#"{{"status": "warning", "message": "{}"}}"#,
-                                        error_msg
-                                    );
-                                } else {
-                                    eprintln!("Warning: {}", error_msg);
-                                }
-                            }
-                            continue;
-                        }
-                    }
-
-                    files_processed += 1;
-                }
-                Err(e) => {
-                    if verbose {
-                        let error_msg =
-                            format!("Failed to parse file {}: {}", file_path.display(), e);
-                        if json_output {
-                            eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
-                        } else {
-                            eprintln!("Warning: {}", error_msg);
-                        }
-                    }
-                }
-            }
-        }
-
-        if verbose && !json_output {
+            println!("Found {} JSONL files", stats.files_found);
             println!(
-                "Processed {files_processed} files, {total_messages} total messages, {unique_messages} unique messages"
+                "Processed {} files, {} total messages, {} unique messages",
+                stats.files_processed, stats.total_messages, stats.unique_messages
             );
         }
 
-        Ok(all_usage_data)
+        Ok(self.incremental_parser.usage_data(hidden))
+    }
+
+    /// Forget the previous render's conversion so its provenance doesn't leak into the next
+    pub fn reset_currency_provenance(&mut self) {
+        self.currency_converter.reset_provenance();
+        self.currency_provenance = None;
+        self.usage_usd = None;
     }
 
     /// Apply currency conversion to usage data
     pub async fn convert_currency(
-        &self,
+        &mut self,
         usage: &mut Vec<crate::analysis::usage::ProjectUsage>,
         target_currency: &str,
         verbose: bool,
         json_output: bool,
     ) -> anyhow::Result<()> {
+        let original_total_usd = usage.iter().fold(0.0, |total, p| total + p.total_cost_usd);
         if target_currency == "USD" {
+            self.currency_provenance = Some(self.currency_converter.provenance(
+                target_currency,
+                original_total_usd,
+                original_total_usd,
+            ));
+            self.usage_usd = None;
             return Ok(());
         }
-
-        let currency_converter = CurrencyConverter::new();
+        self.usage_usd = Some(usage.clone());
 
         // Convert all USD amounts to target currency
         for project in usage.iter_mut() {
-            match currency_converter
-                .convert_from_usd(project.total_cost_usd, target_currency)
+            match self
+                .currency_converter
+                .convert_daily_from_usd(
+                    project.total_cost_usd,
+                    &project.daily_cost_usd,
+                    target_currency,
+                )
                 .await
             {
                 Ok(converted_cost) => {
@@ -249,8 +220,13 @@ impl TimeframeContext {
 
             // Convert model-level costs too
             for model_usage in project.model_usage.values_mut() {
-                match currency_converter
-                    .convert_from_usd(model_usage.cost_usd, target_currency)
+                match self
+                    .currency_converter
+                    .convert_daily_from_usd(
+                        model_usage.cost_usd,
+                        &model_usage.daily_cost_usd,
+                        target_currency,
+                    )
                     .await
                 {
                     Ok(converted_cost) => {
@@ -263,6 +239,13 @@ impl TimeframeContext {
             }
         }
 
+        let converted_total = usage.iter().fold(0.0, |total, p| total + p.total_cost_usd);
+        self.currency_provenance = Some(self.currency_converter.provenance(
+            target_currency,
+            original_total_usd,
+            converted_total,
+        ));
+
         Ok(())
     }
 
@@ -270,13 +253,10 @@ impl TimeframeContext {
     pub fn display_results(
         &self,
         usage: &Vec<crate::analysis::usage::ProjectUsage>,
-        target_currency: &str,
-        decimal_places: u8,
-        json_output: bool,
-        colored: bool,
+        options: &OutputOptions<'_>,
     ) -> anyhow::Result<()> {
         if usage.is_empty() {
-            if json_output {
+            if options.json_output {
                 println!(
                     r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
                 );
@@ -286,8 +266,26 @@ impl TimeframeContext {
             return Ok(());
         }
 
-        if json_output {
-            match usage.to_json() {
+        if options.json_output {
+            // The forecast rides in the envelope; without it this stays a plain array
+            let json = match &self.forecast {
+                Some(forecast) if options.currency_provenance => ForecastJson {
+                    currency: self.currency_provenance.as_ref(),
+                    data: usage,
+                    data_usd: self.usage_usd.as_ref(),
+                    forecast,
+                }
+                .to_json(),
+                _ => match &self.currency_provenance {
+                    Some(currency) => usage.to_json_with_currency(
+                        currency,
+                        self.usage_usd.as_ref().unwrap_or(usage),
+                        options.currency_provenance,
+                    ),
+                    None => usage.to_json(),
+                },
+            };
+            match json {
                 Ok(json) => println!("{json}"),
                 Err(e) => {
                     println!(
@@ -299,8 +297,31 @@ impl TimeframeContext {
         } else {
             println!(
                 "{}",
-                usage.to_table_with_currency_and_color(target_currency, decimal_places, colored)
+                usage.to_table_with_currency_and_color(
+                    options.currency,
+                    options.decimal_places,
+                    options.colored
+                )
             );
+            if let Some(forecast) = &self.forecast {
+                println!(
+                    "{}",
+                    forecast.to_footer(options.currency, options.decimal_places)
+                );
+            }
+            if options.chart {
+                println!();
+                println!(
+                    "{}",
+                    project_charts(
+                        usage,
+                        options.currency,
+                        options.decimal_places,
+                        options.colored,
+                        terminal_width()
+                    )
+                );
+            }
         }
 
         Ok(())
@@ -322,6 +343,22 @@ impl TimeframeContext {
     }
 }
 
+/// `this-month --json --currency-provenance`: the envelope plus the projection
+#[derive(Serialize)]
+struct ForecastJson<'a> {
+    currency: Option<&'a CurrencyProvenance>,
+    data: &'a Vec<ProjectUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data_usd: Option<&'a Vec<ProjectUsage>>,
+    forecast: &'a MonthForecast,
+}
+
+impl ForecastJson<'_> {
+    fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
 /// Handle error display consistently across all commands
 pub fn handle_error(error: &anyhow::Error, json_output: bool) {
     if json_output {
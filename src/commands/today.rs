@@ -1,33 +1,49 @@
 // Today's usage command
 use crate::analysis::UsageFilter;
-use crate::commands::timeframe_utils::{TimeframeContext, UsageTimeframe, handle_error};
+use crate::cli::args::FilterArgs;
+use crate::commands::anomalies::print_anomaly_summary;
+use crate::commands::timeframe_utils::{
+    OutputOptions, TimeframeContext, UsageTimeframe, handle_error,
+};
+use crate::commands::watch::run_or_watch;
 use crate::utils::{apply_usage_filters, print_filter_info, resolve_filters};
 
 pub async fn handle_today_command(
     project: Option<String>,
-    since: Option<String>,
-    until: Option<String>,
-    model: Option<String>,
-    target_currency: &str,
-    decimal_places: u8,
-    json_output: bool,
-    verbose: bool,
-    colored: bool,
-    hidden: bool,
-    timezone_name: &str,
-    daily_cutoff_hour: u8,
-    date_format: &str,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
 ) -> anyhow::Result<()> {
     // Initialize context
-    let mut context =
-        match TimeframeContext::new(timezone_name, daily_cutoff_hour, date_format).await {
-            Ok(ctx) => ctx,
-            Err(e) => {
-                handle_error(&e, json_output);
-                return Err(e);
-            }
-        };
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    run_or_watch(
+        &mut context,
+        options.watch,
+        options.json_output,
+        async |context| show_today(context, project.clone(), filters.clone(), options).await,
+    )
+    .await
+}
 
+/// Load usage and print today's totals
+async fn show_today(
+    context: &mut TimeframeContext,
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
     // Parse timeframe into date filters
     let (final_project, final_since, final_until, final_model) = resolve_filters(
         Some(UsageTimeframe::Today),
@@ -44,24 +60,29 @@ pub async fn handle_today_command(
         model_name: final_model.clone(),
         since: final_since,
         until: final_until,
+        branch_name: branch,
     };
 
-    if verbose {
-        print_filter_info(&usage_filter, json_output, &context.date_formatter);
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
     }
 
     // Process JSONL files
-    let all_usage_data =
-        match context.process_jsonl_files(final_project, verbose, json_output, hidden) {
-            Ok(data) => data,
-            Err(e) => {
-                handle_error(&e, json_output);
-                return Err(e);
-            }
-        };
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
 
     if all_usage_data.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -77,6 +98,19 @@ pub async fn handle_today_command(
         .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
         .collect();
 
+    if options.verbose {
+        print_anomaly_summary(
+            &context.usage_tracker,
+            &usage_tuples,
+            &mut context.pricing_manager,
+            &usage_filter,
+            &context.timezone_calc,
+            &context.currency_converter,
+            options,
+        )
+        .await;
+    }
+
     // Calculate usage with enhanced pricing (supports live pricing)
     let (project_usage, pricing_source) = match context
         .calculate_usage_enhanced(usage_tuples, &usage_filter)
@@ -84,13 +118,13 @@ pub async fn handle_today_command(
     {
         Ok((usage, source)) => (usage, source),
         Err(e) => {
-            handle_error(&e, json_output);
+            handle_error(&e, options.json_output);
             return Err(e);
         }
     };
 
     // Display pricing source in verbose mode
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         if let Some(source) = &pricing_source {
             println!("Pricing source: {}", source);
         }
@@ -101,19 +135,18 @@ pub async fn handle_today_command(
 
     // Convert currencies if needed
     if let Err(e) = context
-        .convert_currency(&mut filtered_usage, target_currency, verbose, json_output)
+        .convert_currency(
+            &mut filtered_usage,
+            options.currency,
+            options.verbose,
+            options.json_output,
+        )
         .await
     {
-        handle_error(&e, json_output);
+        handle_error(&e, options.json_output);
         return Err(e);
     }
 
     // Display results
-    context.display_results(
-        &filtered_usage,
-        target_currency,
-        decimal_places,
-        json_output,
-        colored,
-    )
+    context.display_results(&filtered_usage, options)
 }
@@ -0,0 +1,94 @@
+// Interactive dashboard command
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::tui::app::App;
+use crate::tui::data::{DashboardData, DashboardQuery, DateRange};
+use crate::utils::resolve_filters;
+use std::io::IsTerminal;
+
+pub async fn handle_tui_command(
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    if options.json_output {
+        let e = anyhow::anyhow!("The dashboard is interactive and has no JSON output");
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+    if !std::io::stdout().is_terminal() {
+        let e = anyhow::anyhow!("The dashboard needs an interactive terminal");
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    // Load every project; the project filter is applied (and can be changed) in the dashboard
+    let all_usage_data =
+        match context.process_jsonl_files(None, false, options.json_output, options.hidden) {
+            Ok(data) => data,
+            Err(e) => {
+                handle_error(&e, options.json_output);
+                return Err(e);
+            }
+        };
+    if all_usage_data.is_empty() {
+        println!("No usage data found matching your filters.");
+        return Ok(());
+    }
+
+    let date_formatter = context.date_formatter.clone();
+    let mut data = DashboardData::new(
+        context,
+        all_usage_data,
+        final_since,
+        final_until,
+        options.currency,
+    )
+    .await;
+
+    // With --hidden, project names are anonymized, so --project is matched against those
+    let query = DashboardQuery {
+        project: final_project,
+        model: final_model,
+        branch,
+        range: DateRange::All,
+    };
+    let snapshot = match data.snapshot(&query).await {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let mut app = App::new(
+        snapshot,
+        query,
+        data.project_names.clone(),
+        data.model_names.clone(),
+        options.currency,
+        options.decimal_places,
+        date_formatter,
+    );
+    app.status = data.conversion_warning.clone();
+
+    crate::tui::run(&mut app, &mut data).await
+}
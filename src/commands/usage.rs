@@ -1,6 +1,10 @@
+use crate::analysis::branches::message_branch;
 use crate::analysis::{
     CostCalculationMode, DailyUsage, DailyUsageList, TimezoneCalculator, UsageFilter, UsageTracker,
 };
+use crate::cli::args::FilterArgs;
+use crate::commands::anomalies::print_anomaly_summary;
+use crate::commands::timeframe_utils::OutputOptions;
 use crate::config::Config;
 use crate::models::PricingManager;
 use crate::models::currency::CurrencyConverter;
@@ -26,24 +30,20 @@ pub enum UsageTimeframe {
 pub async fn handle_usage_command(
     timeframe: Option<UsageTimeframe>,
     project: Option<String>,
-    since: Option<String>,
-    until: Option<String>,
-    model: Option<String>,
-    target_currency: &str,
-    decimal_places: u8,
-    json_output: bool,
-    verbose: bool,
-    colored: bool,
-    hidden: bool,
-    timezone_name: &str,
-    daily_cutoff_hour: u8,
-    date_format: &str,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
 ) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
     // Initialize timezone calculator
-    let timezone_calc = match TimezoneCalculator::new(timezone_name, daily_cutoff_hour) {
+    let timezone_calc = match TimezoneCalculator::new(options.timezone, options.daily_cutoff_hour) {
         Ok(calc) => calc,
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(
                     r#"{{"status": "error", "message": "Invalid timezone configuration: {e}"}}"#
                 );
@@ -55,10 +55,10 @@ pub async fn handle_usage_command(
     };
 
     // Initialize date formatter
-    let date_formatter = match DateFormatter::new(date_format) {
+    let date_formatter = match DateFormatter::new(options.date_format) {
         Ok(formatter) => formatter,
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(
                     r#"{{"status": "error", "message": "Invalid date format configuration: {e}"}}"#
                 );
@@ -73,7 +73,7 @@ pub async fn handle_usage_command(
     let config_for_projects = match Config::load() {
         Ok(config) => config,
         Err(_) => {
-            if json_output {
+            if options.json_output {
                 println!(
                     r#"{{"status": "error", "message": "Failed to load config for projects path"}}"#
                 );
@@ -99,17 +99,10 @@ pub async fn handle_usage_command(
         PathBuf::from(&config_for_projects.general.claude_projects_path)
     };
 
-    // Initialize pricing manager based on configuration
-    // Default to static pricing for speed, only use live when explicitly requested
-    let mut pricing_manager = match config_for_projects.pricing.source.as_str() {
-        "live" => PricingManager::with_live_pricing(),
-        _ => PricingManager::new(), // "auto", "static" or unknown - all use static by default
-    };
-
-    // Only enable live pricing when explicitly set to "live"
-    pricing_manager.set_live_pricing(config_for_projects.pricing.source == "live");
+    // Initialize pricing manager from configuration (source and per-model overrides)
+    let mut pricing_manager = PricingManager::from_config(&config_for_projects.pricing);
 
-    // Pre-fetch pricing data if live pricing is enabled
+    // Pre-fetch pricing data for live/auto modes
     if let Err(_) = pricing_manager.initialize_live_pricing().await {
         // If live pricing fails, it will fall back to static during calculations
     }
@@ -120,21 +113,7 @@ pub async fn handle_usage_command(
 
     // Check if this is a daily command - requires special handling
     if let Some(UsageTimeframe::Daily { days }) = &timeframe {
-        handle_daily_usage_command(
-            *days,
-            project,
-            model,
-            target_currency,
-            decimal_places,
-            json_output,
-            verbose,
-            colored,
-            hidden,
-            timezone_name,
-            daily_cutoff_hour,
-            date_format,
-        )
-        .await?;
+        handle_daily_usage_command(*days, project, model, branch, options).await?;
         return Ok(());
     }
 
@@ -148,20 +127,21 @@ pub async fn handle_usage_command(
         model_name: final_model.clone(),
         since: final_since,
         until: final_until,
+        branch_name: branch,
     };
 
-    if verbose {
-        print_filter_info(&usage_filter, json_output, &date_formatter);
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &date_formatter);
     }
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!("Searching for JSONL files in: {}", projects_dir.display());
     }
 
     let jsonl_files = match parser.find_jsonl_files() {
         Ok(files) => files,
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(r#"{{"status": "error", "message": "Failed to find JSONL files: {e}"}}"#);
             } else {
                 eprintln!("Error: Failed to find JSONL files: {e}");
@@ -175,7 +155,7 @@ pub async fn handle_usage_command(
     };
 
     if jsonl_files.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(r#"{{"status": "warning", "message": "No JSONL files found", "data": []}}"#);
         } else {
             println!("No Claude usage data found in {}", projects_dir.display());
@@ -184,7 +164,7 @@ pub async fn handle_usage_command(
         return Ok(());
     }
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!("Found {} JSONL files", jsonl_files.len());
     }
 
@@ -195,12 +175,12 @@ pub async fn handle_usage_command(
     let mut unique_messages = 0;
 
     for file_path in jsonl_files {
-        match parser.parse_file_with_verbose(&file_path, verbose) {
+        match parser.parse_file_with_verbose(&file_path, options.verbose) {
             Ok(parsed_conversation) => {
                 // Use unified project name extraction for consistency
                 let raw_project_name =
                     parser.get_unified_project_name(&file_path, &parsed_conversation.messages);
-                let project_name = maybe_hide_project_name(&raw_project_name, hidden);
+                let project_name = maybe_hide_project_name(&raw_project_name, options.hidden);
 
                 // Apply project filter if specified
                 if let Some(ref filter_project) = final_project
@@ -226,8 +206,8 @@ pub async fn handle_usage_command(
                         }
                     }
                     Err(e) => {
-                        if verbose {
-                            if json_output {
+                        if options.verbose {
+                            if options.json_output {
                                 eprintln!(
                                     r#"{{"status": "warning", "message": "Failed to deduplicate file {}: {}"}}"#,
                                     file_path.display(),
@@ -248,8 +228,8 @@ pub async fn handle_usage_command(
                 files_processed += 1;
             }
             Err(e) => {
-                if verbose {
-                    if json_output {
+                if options.verbose {
+                    if options.json_output {
                         eprintln!(
                             r#"{{"status": "warning", "message": "Failed to parse file {}: {}"}}"#,
                             file_path.display(),
@@ -267,14 +247,14 @@ pub async fn handle_usage_command(
         }
     }
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!(
             "Processed {files_processed} files, {total_messages} total messages, {unique_messages} unique messages"
         );
     }
 
     if all_usage_data.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -290,6 +270,20 @@ pub async fn handle_usage_command(
         .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
         .collect();
 
+    let currency_converter = CurrencyConverter::from_config(&config_for_projects.currency);
+    if options.verbose {
+        print_anomaly_summary(
+            &usage_tracker,
+            &usage_tuples,
+            &mut pricing_manager,
+            &usage_filter,
+            &timezone_calc,
+            &currency_converter,
+            options,
+        )
+        .await;
+    }
+
     // Calculate usage with enhanced pricing (supports live pricing)
     let (project_usage, pricing_source) = match usage_tracker
         .calculate_usage_with_projects_filtered_enhanced(
@@ -301,7 +295,7 @@ pub async fn handle_usage_command(
     {
         Ok((usage, source)) => (usage, source),
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(r#"{{"status": "error", "message": "Failed to calculate usage: {e}"}}"#);
             } else {
                 eprintln!("Error: Failed to calculate usage: {e}");
@@ -311,7 +305,7 @@ pub async fn handle_usage_command(
     };
 
     // Display pricing source in verbose mode
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         if let Some(source) = &pricing_source {
             println!("Pricing source: {}", source);
         }
@@ -321,21 +315,27 @@ pub async fn handle_usage_command(
     let mut filtered_usage = apply_usage_filters(project_usage, &usage_filter);
 
     // Convert currencies if needed
-    if target_currency != "USD" {
-        let currency_converter = CurrencyConverter::new();
-
+    let filtered_usage_usd = filtered_usage.clone();
+    let original_total_usd = filtered_usage
+        .iter()
+        .fold(0.0, |total, p| total + p.total_cost_usd);
+    if options.currency != "USD" {
         // Convert all USD amounts to target currency
         for project in &mut filtered_usage {
             match currency_converter
-                .convert_from_usd(project.total_cost_usd, target_currency)
+                .convert_daily_from_usd(
+                    project.total_cost_usd,
+                    &project.daily_cost_usd,
+                    options.currency,
+                )
                 .await
             {
                 Ok(converted_cost) => {
                     project.total_cost_usd = converted_cost; // Reusing the USD field for converted amount
                 }
                 Err(e) => {
-                    if verbose {
-                        if json_output {
+                    if options.verbose {
+                        if options.json_output {
                             eprintln!(
                                 r#"{{"status": "warning", "message": "Failed to convert currency for {}: {}"}}"#,
                                 project.project_name, e
@@ -354,7 +354,11 @@ pub async fn handle_usage_command(
             // Convert model-level costs too
             for model_usage in project.model_usage.values_mut() {
                 match currency_converter
-                    .convert_from_usd(model_usage.cost_usd, target_currency)
+                    .convert_daily_from_usd(
+                        model_usage.cost_usd,
+                        &model_usage.daily_cost_usd,
+                        options.currency,
+                    )
                     .await
                 {
                     Ok(converted_cost) => {
@@ -369,7 +373,7 @@ pub async fn handle_usage_command(
     }
 
     if filtered_usage.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -379,9 +383,21 @@ pub async fn handle_usage_command(
         return Ok(());
     }
 
+    let currency = currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        filtered_usage
+            .iter()
+            .fold(0.0, |total, p| total + p.total_cost_usd),
+    );
+
     // Display results
-    if json_output {
-        match filtered_usage.to_json() {
+    if options.json_output {
+        match filtered_usage.to_json_with_currency(
+            &currency,
+            &filtered_usage_usd,
+            options.currency_provenance,
+        ) {
             Ok(json) => println!("{json}"),
             Err(e) => {
                 println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
@@ -392,9 +408,9 @@ pub async fn handle_usage_command(
         println!(
             "{}",
             filtered_usage.to_table_with_currency_and_color(
-                target_currency,
-                decimal_places,
-                colored
+                options.currency,
+                options.decimal_places,
+                options.colored,
             )
         );
     }
@@ -405,21 +421,14 @@ pub async fn handle_daily_usage_command(
     days: u32,
     project_filter: Option<String>,
     model_filter: Option<String>,
-    target_currency: &str,
-    decimal_places: u8,
-    json_output: bool,
-    verbose: bool,
-    colored: bool,
-    hidden: bool,
-    _timezone_name: &str,
-    _daily_cutoff_hour: u8,
-    date_format: &str,
+    branch_filter: Option<String>,
+    options: &OutputOptions<'_>,
 ) -> anyhow::Result<()> {
     // Initialize date formatter
-    let date_formatter = match DateFormatter::new(date_format) {
+    let date_formatter = match DateFormatter::new(options.date_format) {
         Ok(formatter) => formatter,
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(
                     r#"{{"status": "error", "message": "Invalid date format configuration: {e}"}}"#
                 );
@@ -434,7 +443,7 @@ pub async fn handle_daily_usage_command(
     let config_for_projects = match Config::load() {
         Ok(config) => config,
         Err(_) => {
-            if json_output {
+            if options.json_output {
                 println!(
                     r#"{{"status": "error", "message": "Failed to load config for projects path"}}"#
                 );
@@ -460,17 +469,10 @@ pub async fn handle_daily_usage_command(
         PathBuf::from(&config_for_projects.general.claude_projects_path)
     };
 
-    // Initialize pricing manager based on configuration
-    // Default to static pricing for speed, only use live when explicitly requested
-    let mut pricing_manager = match config_for_projects.pricing.source.as_str() {
-        "live" => PricingManager::with_live_pricing(),
-        _ => PricingManager::new(), // "auto", "static" or unknown - all use static by default
-    };
-
-    // Only enable live pricing when explicitly set to "live"
-    pricing_manager.set_live_pricing(config_for_projects.pricing.source == "live");
+    // Initialize pricing manager from configuration (source and per-model overrides)
+    let mut pricing_manager = PricingManager::from_config(&config_for_projects.pricing);
 
-    // Pre-fetch pricing data if live pricing is enabled
+    // Pre-fetch pricing data for live/auto modes
     if let Err(_) = pricing_manager.initialize_live_pricing().await {
         // If live pricing fails, it will fall back to static during calculations
     }
@@ -479,14 +481,14 @@ pub async fn handle_daily_usage_command(
     let parser = JsonlParser::new(projects_dir.clone());
     let mut dedup_engine = DeduplicationEngine::new();
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!("Searching for JSONL files in: {}", projects_dir.display());
     }
 
     let jsonl_files = match parser.find_jsonl_files() {
         Ok(files) => files,
         Err(e) => {
-            if json_output {
+            if options.json_output {
                 println!(r#"{{"status": "error", "message": "Failed to find JSONL files: {e}"}}"#);
             } else {
                 eprintln!("Error: Failed to find JSONL files: {e}");
@@ -500,7 +502,7 @@ pub async fn handle_daily_usage_command(
     };
 
     if jsonl_files.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(r#"{{"status": "warning", "message": "No JSONL files found", "data": []}}"#);
         } else {
             println!("No Claude usage data found in {}", projects_dir.display());
@@ -509,7 +511,7 @@ pub async fn handle_daily_usage_command(
         return Ok(());
     }
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!("Found {} JSONL files", jsonl_files.len());
     }
 
@@ -520,12 +522,12 @@ pub async fn handle_daily_usage_command(
     let mut unique_messages = 0;
 
     for file_path in jsonl_files {
-        match parser.parse_file_with_verbose(&file_path, verbose) {
+        match parser.parse_file_with_verbose(&file_path, options.verbose) {
             Ok(parsed_conversation) => {
                 // Use unified project name extraction for consistency
                 let raw_project_name =
                     parser.get_unified_project_name(&file_path, &parsed_conversation.messages);
-                let project_name = maybe_hide_project_name(&raw_project_name, hidden);
+                let project_name = maybe_hide_project_name(&raw_project_name, options.hidden);
 
                 // Apply project filter if specified
                 if let Some(ref filter_project) = project_filter
@@ -550,8 +552,8 @@ pub async fn handle_daily_usage_command(
                         }
                     }
                     Err(e) => {
-                        if verbose {
-                            if json_output {
+                        if options.verbose {
+                            if options.json_output {
                                 eprintln!(
                                     r#"{{"status": "warning", "message": "Failed to deduplicate file {}: {}"}}"#,
                                     file_path.display(),
@@ -572,8 +574,8 @@ pub async fn handle_daily_usage_command(
                 files_processed += 1;
             }
             Err(e) => {
-                if verbose {
-                    if json_output {
+                if options.verbose {
+                    if options.json_output {
                         eprintln!(
                             r#"{{"status": "warning", "message": "Failed to parse file {}: {}"}}"#,
                             file_path.display(),
@@ -591,14 +593,14 @@ pub async fn handle_daily_usage_command(
         }
     }
 
-    if verbose && !json_output {
+    if options.verbose && !options.json_output {
         println!(
             "Processed {files_processed} files, {total_messages} total messages, {unique_messages} unique messages"
         );
     }
 
     if all_usage_data.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
             );
@@ -632,9 +634,12 @@ pub async fn handle_daily_usage_command(
         {
             continue;
         }
+        if branch_filter.is_some() && message_branch(message) != branch_filter.as_deref() {
+            continue;
+        }
 
         // Parse timestamp and extract date
-        let date_key = if let Some(timestamp_str) = &message.timestamp {
+        let (date_key, message_date) = if let Some(timestamp_str) = &message.timestamp {
             if let Ok(message_time) = usage_tracker.parse_timestamp(timestamp_str) {
                 // Check if message is within the requested days range
                 let today = Utc::now().date_naive();
@@ -645,11 +650,12 @@ pub async fn handle_daily_usage_command(
                     continue;
                 }
 
-                if json_output {
+                let date_key = if options.json_output {
                     date_formatter.format_naive_date_for_json(&message_date)
                 } else {
                     date_formatter.format_naive_date_for_table(&message_date)
-                }
+                };
+                (date_key, message_date)
             } else {
                 continue; // Skip messages with unparseable timestamps
             }
@@ -669,6 +675,9 @@ pub async fn handle_daily_usage_command(
                 total_cost_usd: 0.0,
                 message_count: 0,
                 projects_count: 0,
+                usage_date: Some(message_date),
+                model_cost_usd: Default::default(),
+                project_cost_usd: Default::default(),
             });
 
         // Aggregate token counts
@@ -712,7 +721,7 @@ pub async fn handle_daily_usage_command(
         if let Some(timestamp_str) = &enhanced.usage_data.timestamp
             && let Ok(message_time) = usage_tracker.parse_timestamp(timestamp_str)
         {
-            let date_key = if json_output {
+            let date_key = if options.json_output {
                 date_formatter.format_naive_date_for_json(&message_time.date_naive())
             } else {
                 date_formatter.format_naive_date_for_table(&message_time.date_naive())
@@ -738,7 +747,7 @@ pub async fn handle_daily_usage_command(
     daily_usage_vec.sort_by(|a, b| a.date.cmp(&b.date));
 
     if daily_usage_vec.is_empty() {
-        if json_output {
+        if options.json_output {
             println!(
                 r#"{{"status": "success", "message": "No daily usage data found matching filters", "data": []}}"#
             );
@@ -749,21 +758,33 @@ pub async fn handle_daily_usage_command(
     }
 
     // Convert currencies if needed
-    if target_currency != "USD" {
-        let currency_converter = CurrencyConverter::new();
-
+    let currency_converter = CurrencyConverter::from_config(&config_for_projects.currency);
+    let daily_usage_usd = DailyUsageList(daily_usage_vec.clone());
+    let original_total_usd = daily_usage_vec
+        .iter()
+        .fold(0.0, |total, d| total + d.total_cost_usd);
+    if options.currency != "USD" {
         // Convert all USD amounts to target currency
         for daily in &mut daily_usage_vec {
-            match currency_converter
-                .convert_from_usd(daily.total_cost_usd, target_currency)
-                .await
-            {
+            let converted = match daily.usage_date {
+                Some(date) => {
+                    currency_converter
+                        .convert_from_usd_on(daily.total_cost_usd, options.currency, date)
+                        .await
+                }
+                None => {
+                    currency_converter
+                        .convert_from_usd(daily.total_cost_usd, options.currency)
+                        .await
+                }
+            };
+            match converted {
                 Ok(converted_cost) => {
                     daily.total_cost_usd = converted_cost;
                 }
                 Err(e) => {
-                    if verbose {
-                        if json_output {
+                    if options.verbose {
+                        if options.json_output {
                             eprintln!(
                                 r#"{{"status": "warning", "message": "Failed to convert currency for {}: {}"}}"#,
                                 daily.date, e
@@ -781,12 +802,24 @@ pub async fn handle_daily_usage_command(
         }
     }
 
+    let currency = currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        daily_usage_vec
+            .iter()
+            .fold(0.0, |total, d| total + d.total_cost_usd),
+    );
+
     // Wrap in our display wrapper after currency conversion
     let daily_usage_list = DailyUsageList(daily_usage_vec);
 
     // Display results
-    if json_output {
-        match daily_usage_list.to_json() {
+    if options.json_output {
+        match daily_usage_list.to_json_with_currency(
+            &currency,
+            &daily_usage_usd,
+            options.currency_provenance,
+        ) {
             Ok(json) => println!("{json}"),
             Err(e) => {
                 println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
@@ -797,9 +830,9 @@ pub async fn handle_daily_usage_command(
         println!(
             "{}",
             daily_usage_list.to_table_with_currency_and_color(
-                target_currency,
-                decimal_places,
-                colored
+                options.currency,
+                options.decimal_places,
+                options.colored,
             )
         );
     }
@@ -0,0 +1,112 @@
+// Watch mode: re-render a view whenever Claude appends to a JSONL file
+use crate::commands::timeframe_utils::TimeframeContext;
+use chrono::Utc;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::io::Write;
+use std::time::Duration;
+
+/// Wait this long after a change so a burst of writes renders once
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Re-render at least this often so "today" and other periods roll over
+const IDLE_REFRESH: Duration = Duration::from_secs(60);
+
+/// Render once, or keep re-rendering on file changes when `watch` is set
+pub async fn run_or_watch<F>(
+    context: &mut TimeframeContext,
+    watch: bool,
+    json_output: bool,
+    mut render: F,
+) -> anyhow::Result<()>
+where
+    F: AsyncFnMut(&mut TimeframeContext) -> anyhow::Result<()>,
+{
+    if watch {
+        watch_changes(context, json_output, render).await
+    } else {
+        render(context).await
+    }
+}
+
+/// Re-render whenever a JSONL file under the projects directory changes
+///
+/// Each render goes through `process_jsonl_files`, which only parses new lines.
+/// Table output clears the screen first; JSON output prints one document per
+/// refresh. A failed refresh is reported and retried on the next change, so
+/// watching runs until interrupted.
+pub async fn watch_changes<F>(
+    context: &mut TimeframeContext,
+    json_output: bool,
+    mut render: F,
+) -> anyhow::Result<()>
+where
+    F: AsyncFnMut(&mut TimeframeContext) -> anyhow::Result<()>,
+{
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        // Ignore access events, which our own reads would trigger
+        if let Ok(event) = event
+            && matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            )
+            && event
+                .paths
+                .iter()
+                .any(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        {
+            let _ = sender.send(());
+        }
+    })
+    .map_err(|e| anyhow::anyhow!("Failed to start file watcher: {}", e))?;
+    watcher
+        .watch(&context.projects_dir, RecursiveMode::Recursive)
+        .map_err(|e| {
+            anyhow::anyhow!("Failed to watch {}: {}", context.projects_dir.display(), e)
+        })?;
+
+    loop {
+        if !json_output {
+            // Clear the screen and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        context.reset_currency_provenance();
+        if let Err(e) = render(context).await {
+            warn_refresh_failed(&e, json_output);
+        }
+        if !json_output {
+            println!(
+                "\nWatching {} · updated {} · Ctrl-C to stop",
+                context.projects_dir.display(),
+                context
+                    .timezone_calc
+                    .local_datetime(Utc::now())
+                    .format("%H:%M:%S")
+            );
+        }
+        std::io::stdout().flush()?;
+
+        tokio::select! {
+            changed = receiver.recv() => {
+                if changed.is_none() {
+                    return Ok(());
+                }
+            }
+            _ = tokio::time::sleep(IDLE_REFRESH) => {}
+        }
+        tokio::time::sleep(SETTLE_DELAY).await;
+        while receiver.try_recv().is_ok() {}
+    }
+}
+
+fn warn_refresh_failed(error: &anyhow::Error, json_output: bool) {
+    let message = format!("Refresh failed, still watching: {error}");
+    if json_output {
+        eprintln!(
+            "{}",
+            serde_json::json!({"status": "warning", "message": message})
+        );
+    } else {
+        eprintln!("Warning: {message}");
+    }
+}
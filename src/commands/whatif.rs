@@ -0,0 +1,207 @@
+// What-if re-pricing command
+use crate::analysis::UsageFilter;
+use crate::analysis::whatif::{WhatIfUsageList, reprice_usage};
+use crate::cli::args::FilterArgs;
+use crate::commands::timeframe_utils::{OutputOptions, TimeframeContext, handle_error};
+use crate::models::currency::CurrencyConverter;
+use crate::output::OutputFormat;
+use crate::utils::{print_filter_info, resolve_filters};
+
+pub async fn handle_whatif_command(
+    target_model: String,
+    project: Option<String>,
+    filters: FilterArgs,
+    options: &OutputOptions<'_>,
+) -> anyhow::Result<()> {
+    let FilterArgs {
+        since,
+        until,
+        model,
+        branch,
+    } = filters;
+    // Initialize context
+    let mut context = match TimeframeContext::from_options(options).await {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    // Refuse to silently reprice with fallback rates for a model we know nothing about
+    let known_models = context.pricing_manager.list_models();
+    if !known_models.contains(&target_model)
+        && !context
+            .pricing_manager
+            .live_model_names()
+            .contains(&target_model)
+    {
+        let e = anyhow::anyhow!(
+            "Unknown model '{}'. Known models: {}. Run 'ccost pricing list' for details",
+            target_model,
+            known_models.join(", ")
+        );
+        handle_error(&e, options.json_output);
+        return Err(e);
+    }
+
+    // Parse explicit filters (no timeframe: use --since/--until)
+    let (final_project, final_since, final_until, final_model) =
+        resolve_filters(None, project, since, until, model, &context.timezone_calc);
+
+    let usage_filter = UsageFilter {
+        project_name: final_project.clone(),
+        model_name: final_model,
+        since: final_since,
+        until: final_until,
+        branch_name: branch,
+    };
+
+    if options.verbose {
+        print_filter_info(&usage_filter, options.json_output, &context.date_formatter);
+    }
+
+    // Process JSONL files
+    let all_usage_data = match context.process_jsonl_files(
+        final_project,
+        options.verbose,
+        options.json_output,
+        options.hidden,
+    ) {
+        Ok(data) => data,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    let usage_tuples: Vec<(crate::parser::jsonl::UsageData, String)> = all_usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect();
+
+    let mut results = match reprice_usage(
+        &context.usage_tracker,
+        usage_tuples,
+        &mut context.pricing_manager,
+        &usage_filter,
+        &target_model,
+    )
+    .await
+    {
+        Ok(results) => results,
+        Err(e) => {
+            handle_error(&e, options.json_output);
+            return Err(e);
+        }
+    };
+
+    if results.0.is_empty() {
+        if options.json_output {
+            println!(
+                r#"{{"status": "success", "message": "No usage data found matching filters", "data": []}}"#
+            );
+        } else {
+            println!("No usage data found matching your filters.");
+        }
+        return Ok(());
+    }
+
+    // Convert currencies if needed
+    let results_usd = results.clone();
+    let original_total_usd = total_original_cost(&results);
+    if options.currency != "USD" {
+        convert_whatif_currency(
+            &context.currency_converter,
+            &mut results,
+            options.currency,
+            options.verbose,
+            options.json_output,
+        )
+        .await;
+    }
+
+    let currency = context.currency_converter.provenance(
+        options.currency,
+        original_total_usd,
+        total_original_cost(&results),
+    );
+
+    // Display results
+    if options.json_output {
+        match results.to_json_with_currency(&currency, &results_usd, options.currency_provenance) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                println!(r#"{{"status": "error", "message": "Failed to serialize results: {e}"}}"#);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        println!("Repriced as {target_model} (token counts unchanged)");
+        println!();
+        println!(
+            "{}",
+            results.to_table_with_currency_and_color(
+                options.currency,
+                options.decimal_places,
+                options.colored,
+            )
+        );
+    }
+
+    Ok(())
+}
+
+fn total_original_cost(results: &WhatIfUsageList) -> f64 {
+    results
+        .0
+        .iter()
+        .fold(0.0, |total, u| total + u.original_cost_usd)
+}
+
+async fn convert_whatif_currency(
+    currency_converter: &CurrencyConverter,
+    results: &mut WhatIfUsageList,
+    target_currency: &str,
+    verbose: bool,
+    json_output: bool,
+) {
+    for usage in &mut results.0 {
+        let original = currency_converter
+            .convert_daily_from_usd(
+                usage.original_cost_usd,
+                &usage.daily_original_usd,
+                target_currency,
+            )
+            .await;
+        let whatif = currency_converter
+            .convert_daily_from_usd(
+                usage.whatif_cost_usd,
+                &usage.daily_whatif_usd,
+                target_currency,
+            )
+            .await;
+
+        match (original, whatif) {
+            (Ok(original), Ok(whatif)) => {
+                usage.original_cost_usd = original;
+                usage.whatif_cost_usd = whatif;
+                usage.difference_usd = whatif - original;
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                if verbose {
+                    let error_msg = format!(
+                        "Failed to convert currency for {}: {}",
+                        usage.project_name, e
+                    );
+                    if json_output {
+                        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, error_msg);
+                    } else {
+                        eprintln!("Warning: {}", error_msg);
+                    }
+                }
+                // Keep USD amounts if conversion fails
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 // Configuration module
 pub mod settings;
 
-pub use settings::Config;
+pub use settings::{
+    BudgetConfig, Config, ForecastConfig, NotifyConfig, OtlpConfig, PlanConfig, PricingConfig,
+};
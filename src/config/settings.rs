@@ -1,5 +1,7 @@
+use crate::output::locale::NumberLocale;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -10,6 +12,16 @@ pub struct Config {
     pub output: OutputConfig,
     pub timezone: TimezoneConfig,
     pub pricing: PricingConfig,
+    #[serde(default = "default_plans")]
+    pub plans: BTreeMap<String, PlanConfig>, // Subscription plans for break-even comparison
+    #[serde(default)]
+    pub budgets: BTreeMap<String, BudgetConfig>, // Spending limits checked by `ccost budget status`
+    #[serde(default)]
+    pub forecast: ForecastConfig,
+    #[serde(default)]
+    pub otlp: OtlpConfig,
+    #[serde(default)]
+    pub notify: NotifyConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +33,26 @@ pub struct GeneralConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrencyConfig {
     pub default_currency: String,
+    #[serde(default = "default_rate_mode")]
+    pub rate_mode: String, // "latest" or "historical"
+    #[serde(default = "default_provider_url")]
+    pub provider_url: String, // Daily ECB-format XML feed
+    #[serde(default = "default_history_url")]
+    pub history_url: String, // Full ECB-format rate history, for historical mode
+    #[serde(default)]
+    pub rates: BTreeMap<String, f64>, // Fixed units of currency per USD, used instead of fetching
+}
+
+fn default_rate_mode() -> String {
+    "latest".to_string()
+}
+
+pub fn default_provider_url() -> String {
+    "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml".to_string()
+}
+
+pub fn default_history_url() -> String {
+    "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-hist.xml".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +61,12 @@ pub struct OutputConfig {
     pub colored: bool,
     pub decimal_places: u8,
     pub date_format: String, // Date display format: "yyyy-mm-dd", "dd-mm-yyyy", "mm-dd-yyyy"
+    #[serde(default = "default_locale")]
+    pub locale: String, // Number separators, e.g. "en-US", "pt-BR", "de-DE"
+}
+
+fn default_locale() -> String {
+    "en-US".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +80,174 @@ pub struct PricingConfig {
     pub source: String,         // "static", "live", "auto"
     pub cache_ttl_minutes: u32, // Cache time-to-live in minutes
     pub offline_fallback: bool, // Whether to fallback to static pricing offline
+    #[serde(default)]
+    pub overrides: BTreeMap<String, PricingOverride>, // Per-model rates, highest priority
+}
+
+/// Manually configured rates for a model, in USD per million tokens
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PricingOverride {
+    pub input_cost_per_mtok: f64,
+    pub output_cost_per_mtok: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_cost_per_mtok: Option<f64>, // Defaults to 25% of input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_cost_per_mtok: Option<f64>, // Defaults to 10% of input
+}
+
+/// A flat-rate subscription plan to compare against API billing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanConfig {
+    pub monthly_price_usd: f64,
+}
+
+/// A spending limit over a calendar period, optionally scoped to a project or model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetConfig {
+    pub amount: f64,
+    #[serde(default = "default_budget_period")]
+    pub period: String, // "daily", "weekly" or "monthly"
+    #[serde(default = "default_budget_currency")]
+    pub currency: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default = "default_warn_percent")]
+    pub warn_percent: f64, // Warn once spend reaches this share of the budget
+}
+
+fn default_budget_period() -> String {
+    "monthly".to_string()
+}
+
+fn default_budget_currency() -> String {
+    "USD".to_string()
+}
+
+fn default_warn_percent() -> f64 {
+    80.0
+}
+
+/// How `this-month` projects the month-end total
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastConfig {
+    #[serde(default = "default_forecast_method")]
+    pub method: String, // "linear", "weekday" or "exponential"
+    #[serde(default = "default_smoothing_alpha")]
+    pub smoothing_alpha: f64, // Weight of the latest day for "exponential", 0-1
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        Self {
+            method: default_forecast_method(),
+            smoothing_alpha: default_smoothing_alpha(),
+        }
+    }
+}
+
+fn default_forecast_method() -> String {
+    "linear".to_string()
+}
+
+fn default_smoothing_alpha() -> f64 {
+    0.3
+}
+
+/// Where `ccost otlp` sends logs and metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtlpConfig {
+    #[serde(default = "default_otlp_endpoint")]
+    pub endpoint: String, // Collector base URL; /v1/logs and /v1/metrics are appended
+    #[serde(default = "default_otlp_service_name")]
+    pub service_name: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>, // Extra HTTP headers, e.g. for authentication
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: default_otlp_endpoint(),
+            service_name: default_otlp_service_name(),
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_otlp_endpoint() -> String {
+    "http://localhost:4318".to_string()
+}
+
+fn default_otlp_service_name() -> String {
+    "ccost".to_string()
+}
+
+/// Webhook alerts sent by `ccost notify`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    #[serde(default)]
+    pub webhook_url: String, // Empty disables sending
+    #[serde(default = "default_webhook_format")]
+    pub format: String, // "generic" or "slack"
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_anomaly_threshold")]
+    pub anomaly_threshold: f64, // Robust z-score, as for `ccost anomalies`
+    #[serde(default = "default_anomaly_lookback_days")]
+    pub anomaly_lookback_days: u32, // Spikes starting this many days before today are reported too
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: String::new(),
+            format: default_webhook_format(),
+            max_retries: default_max_retries(),
+            anomaly_threshold: default_anomaly_threshold(),
+            anomaly_lookback_days: default_anomaly_lookback_days(),
+        }
+    }
+}
+
+fn default_webhook_format() -> String {
+    "generic".to_string()
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_anomaly_threshold() -> f64 {
+    crate::analysis::anomalies::DEFAULT_THRESHOLD
+}
+
+fn default_anomaly_lookback_days() -> u32 {
+    1
+}
+
+fn default_plans() -> BTreeMap<String, PlanConfig> {
+    BTreeMap::from([
+        (
+            "pro".to_string(),
+            PlanConfig {
+                monthly_price_usd: 20.0,
+            },
+        ),
+        (
+            "max-5x".to_string(),
+            PlanConfig {
+                monthly_price_usd: 100.0,
+            },
+        ),
+        (
+            "max-20x".to_string(),
+            PlanConfig {
+                monthly_price_usd: 200.0,
+            },
+        ),
+    ])
 }
 
 impl Default for Config {
@@ -53,12 +259,17 @@ impl Default for Config {
             },
             currency: CurrencyConfig {
                 default_currency: "USD".to_string(),
+                rate_mode: default_rate_mode(),
+                provider_url: default_provider_url(),
+                history_url: default_history_url(),
+                rates: BTreeMap::new(),
             },
             output: OutputConfig {
                 format: "table".to_string(),
                 colored: false,
                 decimal_places: 2,
                 date_format: "yyyy-mm-dd".to_string(),
+                locale: default_locale(),
             },
             timezone: TimezoneConfig {
                 timezone: "UTC".to_string(),
@@ -68,7 +279,13 @@ impl Default for Config {
                 source: "auto".to_string(),
                 cache_ttl_minutes: 60,
                 offline_fallback: true,
+                overrides: BTreeMap::new(),
             },
+            plans: default_plans(),
+            budgets: BTreeMap::new(),
+            forecast: ForecastConfig::default(),
+            otlp: OtlpConfig::default(),
+            notify: NotifyConfig::default(),
         }
     }
 }
@@ -174,6 +391,43 @@ impl Config {
             self.currency.default_currency
         ));
         output.push('\n');
+        output.push_str("# Which exchange rate applies to historical costs:\n");
+        output.push_str("#   \"latest\"     - Today's ECB rate for all usage (default)\n");
+        output.push_str(
+            "#   \"historical\" - Each day's costs use that day's ECB rate (weekends use Friday's)\n",
+        );
+        output.push_str("# Historical rates are cached in ~/.config/ccost/currency_history.json\n");
+        output.push_str(&format!("rate_mode = \"{}\"\n", self.currency.rate_mode));
+        output.push('\n');
+        output.push_str("# URL of the daily exchange rate feed (ECB eurofxref XML format)\n");
+        output.push_str("# Point this at a local mirror to convert without reaching the ECB\n");
+        output.push_str(&format!(
+            "provider_url = \"{}\"\n",
+            self.currency.provider_url
+        ));
+        output.push_str("# URL of the full rate history used by rate_mode = \"historical\"\n");
+        output.push_str(
+            "# A mirror that also serves eurofxref-hist-90d.xml next to it is topped up from that\n",
+        );
+        output.push_str(&format!(
+            "history_url = \"{}\"\n",
+            self.currency.history_url
+        ));
+        output.push('\n');
+        output.push_str("# Fixed exchange rates (units of currency per 1 USD)\n");
+        output.push_str(
+            "# Manual rates are used as-is and never fetched, which also works offline\n",
+        );
+        output.push_str("# Example:\n");
+        output.push_str("#   [currency.rates]\n");
+        output.push_str("#   EUR = 0.92\n");
+        if !self.currency.rates.is_empty() {
+            output.push_str("[currency.rates]\n");
+            for (currency, rate) in &self.currency.rates {
+                output.push_str(&format!("{currency} = {rate:?}\n"));
+            }
+        }
+        output.push('\n');
 
         // Output settings
         output.push_str(
@@ -213,6 +467,12 @@ impl Config {
         output.push_str("# Note: JSON output always uses ISO format regardless of this setting\n");
         output.push_str(&format!("date_format = \"{}\"\n", self.output.date_format));
         output.push('\n');
+        output.push_str("# Locale for decimal and thousands separators in tables\n");
+        output.push_str("# Examples: \"en-US\" (1,234.56), \"pt-BR\" / \"de-DE\" (1.234,56), \"fr-FR\" (1 234,56)\n");
+        output
+            .push_str("# Note: JSON output always uses plain numbers regardless of this setting\n");
+        output.push_str(&format!("locale = \"{}\"\n", self.output.locale));
+        output.push('\n');
 
         // Timezone settings
         output.push_str(
@@ -280,6 +540,186 @@ impl Config {
             self.pricing.offline_fallback
         ));
         output.push('\n');
+        output.push_str("# Per-model price overrides in USD per million tokens\n");
+        output.push_str("# Overrides take precedence over both live and static pricing\n");
+        output.push_str("# Cache rates are optional (default: 25% / 10% of the input rate)\n");
+        output.push_str("# Example:\n");
+        output.push_str("#   [pricing.overrides.\"claude-sonnet-4-20250514\"]\n");
+        output.push_str("#   input_cost_per_mtok = 3.0\n");
+        output.push_str("#   output_cost_per_mtok = 15.0\n");
+        output.push_str("#   cache_creation_cost_per_mtok = 3.75\n");
+        output.push_str("#   cache_read_cost_per_mtok = 0.3\n");
+        for (model, rates) in &self.pricing.overrides {
+            output.push_str(&format!("[pricing.overrides.\"{model}\"]\n"));
+            output.push_str(&format!(
+                "input_cost_per_mtok = {:?}\n",
+                rates.input_cost_per_mtok
+            ));
+            output.push_str(&format!(
+                "output_cost_per_mtok = {:?}\n",
+                rates.output_cost_per_mtok
+            ));
+            if let Some(rate) = rates.cache_creation_cost_per_mtok {
+                output.push_str(&format!("cache_creation_cost_per_mtok = {rate:?}\n"));
+            }
+            if let Some(rate) = rates.cache_read_cost_per_mtok {
+                output.push_str(&format!("cache_read_cost_per_mtok = {rate:?}\n"));
+            }
+        }
+        output.push('\n');
+
+        // Forecast settings
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push_str("# FORECAST\n");
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push('\n');
+        output.push_str("[forecast]\n");
+        output.push_str("# How `ccost this-month` projects the month-end total\n");
+        output.push_str(
+            "# Options: \"linear\" (daily average), \"weekday\" (average per weekday),\n",
+        );
+        output.push_str("#          \"exponential\" (smoothed, favours recent days)\n");
+        output.push_str(&format!("method = \"{}\"\n", self.forecast.method));
+        output.push('\n');
+        output.push_str("# Smoothing factor for \"exponential\" (higher reacts faster)\n");
+        output.push_str(&format!(
+            "smoothing_alpha = {:?}\n",
+            self.forecast.smoothing_alpha
+        ));
+        output.push('\n');
+
+        // OpenTelemetry settings
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push_str("# OPENTELEMETRY EXPORT\n");
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push('\n');
+        output.push_str("[otlp]\n");
+        output
+            .push_str("# OTLP/HTTP collector that `ccost otlp` pushes usage logs and metrics to\n");
+        output.push_str("# Requests go to <endpoint>/v1/logs and <endpoint>/v1/metrics as JSON\n");
+        output.push_str(&format!("endpoint = \"{}\"\n", self.otlp.endpoint));
+        output.push('\n');
+        output.push_str("# Reported as the service.name resource attribute\n");
+        output.push_str(&format!("service_name = \"{}\"\n", self.otlp.service_name));
+        output.push('\n');
+        output.push_str("# Extra HTTP headers sent with every request\n");
+        output.push_str("# Example:\n");
+        output.push_str("#   [otlp.headers]\n");
+        output.push_str("#   Authorization = \"Bearer <token>\"\n");
+        if !self.otlp.headers.is_empty() {
+            output.push_str("[otlp.headers]\n");
+            for (name, value) in &self.otlp.headers {
+                output.push_str(&format!("\"{name}\" = {value:?}\n"));
+            }
+        }
+        output.push('\n');
+
+        // Notification settings
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push_str("# NOTIFICATIONS\n");
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push('\n');
+        output.push_str("[notify]\n");
+        output.push_str("# Webhook that `ccost notify` posts budget and cost spike alerts to\n");
+        output.push_str("# Leave empty to disable; `ccost notify --dry-run` works without it\n");
+        output.push_str(&format!("webhook_url = \"{}\"\n", self.notify.webhook_url));
+        output.push('\n');
+        output.push_str("# Payload format:\n");
+        output.push_str("#   \"generic\" - The alert as a JSON object (key, kind, severity, title, message, details)\n");
+        output.push_str("#   \"slack\"   - Slack incoming-webhook message ({\"text\": ...})\n");
+        output.push_str(&format!("format = \"{}\"\n", self.notify.format));
+        output.push('\n');
+        output.push_str(
+            "# Retries after a failed delivery (connection errors, 429 and 5xx), with backoff\n",
+        );
+        output.push_str(&format!("max_retries = {}\n", self.notify.max_retries));
+        output.push('\n');
+        output.push_str(
+            "# Robust z-score above which a day, session or project-day counts as a spike\n",
+        );
+        output.push_str(&format!(
+            "anomaly_threshold = {:?}\n",
+            self.notify.anomaly_threshold
+        ));
+        output.push('\n');
+        output.push_str("# Also report spikes that started up to this many days before today\n");
+        output.push_str(&format!(
+            "anomaly_lookback_days = {}\n",
+            self.notify.anomaly_lookback_days
+        ));
+        output.push('\n');
+        output.push_str("# Each alert is sent once; sent alerts are kept in ~/.config/ccost/notify_state.json\n");
+        output.push('\n');
+
+        // Plan settings
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push_str("# SUBSCRIPTION PLANS\n");
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push('\n');
+        output.push_str(
+            "# Monthly plan prices used by `ccost plans` to compare against API billing\n",
+        );
+        output.push_str("# Add, remove or re-price plans to match what your team pays\n");
+        for (name, plan) in &self.plans {
+            output.push_str(&format!("[plans.\"{name}\"]\n"));
+            output.push_str(&format!(
+                "monthly_price_usd = {:?}\n",
+                plan.monthly_price_usd
+            ));
+        }
+        output.push('\n');
+
+        // Budget settings
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push_str("# BUDGETS\n");
+        output.push_str(
+            "# =============================================================================\n",
+        );
+        output.push('\n');
+        output.push_str("# Spending limits checked by `ccost budget status`\n");
+        output.push_str(
+            "# period: \"daily\", \"weekly\" or \"monthly\" (calendar periods in your timezone)\n",
+        );
+        output.push_str("# Omit project and model for a global budget\n");
+        output.push_str("# Example:\n");
+        output.push_str("#   [budgets.\"team-monthly\"]\n");
+        output.push_str("#   amount = 500.0\n");
+        output.push_str("#   period = \"monthly\"\n");
+        output.push_str("#   currency = \"USD\"\n");
+        output.push_str("#   project = \"my-project\"\n");
+        output.push_str("#   warn_percent = 80.0\n");
+        for (name, budget) in &self.budgets {
+            output.push_str(&format!("[budgets.\"{name}\"]\n"));
+            output.push_str(&format!("amount = {:?}\n", budget.amount));
+            output.push_str(&format!("period = \"{}\"\n", budget.period));
+            output.push_str(&format!("currency = \"{}\"\n", budget.currency));
+            if let Some(project) = &budget.project {
+                output.push_str(&format!("project = \"{project}\"\n"));
+            }
+            if let Some(model) = &budget.model {
+                output.push_str(&format!("model = \"{model}\"\n"));
+            }
+            output.push_str(&format!("warn_percent = {:?}\n", budget.warn_percent));
+        }
+        output.push('\n');
 
         // Final notes
         output.push_str(
@@ -323,6 +763,28 @@ impl Config {
                 self.general.cost_mode = value.to_string();
             }
             "currency.default_currency" => self.currency.default_currency = value.to_string(),
+            "currency.rate_mode" => {
+                if !["latest", "historical"].contains(&value) {
+                    anyhow::bail!("Invalid rate mode: {value}. Must be 'latest' or 'historical'");
+                }
+                self.currency.rate_mode = value.to_string();
+            }
+            "currency.provider_url" => {
+                if !value.starts_with("http://") && !value.starts_with("https://") {
+                    anyhow::bail!(
+                        "Invalid provider URL: {value}. Must start with http:// or https://"
+                    );
+                }
+                self.currency.provider_url = value.to_string();
+            }
+            "currency.history_url" => {
+                if !value.starts_with("http://") && !value.starts_with("https://") {
+                    anyhow::bail!(
+                        "Invalid history URL: {value}. Must start with http:// or https://"
+                    );
+                }
+                self.currency.history_url = value.to_string();
+            }
             "output.format" => {
                 if !["table", "json"].contains(&value) {
                     anyhow::bail!("Invalid output format: {value}. Must be 'table' or 'json'");
@@ -343,6 +805,14 @@ impl Config {
                 }
                 self.output.decimal_places = places;
             }
+            "output.locale" => {
+                if NumberLocale::from_tag(value).is_none() {
+                    anyhow::bail!(
+                        "Unsupported locale: {value}. Use a tag like 'en-US', 'pt-BR' or 'de-DE'"
+                    );
+                }
+                self.output.locale = value.to_string();
+            }
             "timezone.timezone" => self.timezone.timezone = value.to_string(),
             "timezone.daily_cutoff_hour" => {
                 let hour: u8 = value
@@ -375,6 +845,181 @@ impl Config {
                     .parse()
                     .with_context(|| format!("Invalid boolean value: {value}"))?;
             }
+            "forecast.method" => {
+                if !["linear", "weekday", "exponential"].contains(&value) {
+                    anyhow::bail!(
+                        "Invalid forecast method: {value}. Must be 'linear', 'weekday', or 'exponential'"
+                    );
+                }
+                self.forecast.method = value.to_string();
+            }
+            "forecast.smoothing_alpha" => {
+                let alpha: f64 = value
+                    .parse()
+                    .with_context(|| format!("Invalid smoothing factor: {value}"))?;
+                if !(alpha > 0.0 && alpha <= 1.0) {
+                    anyhow::bail!("Smoothing factor must be greater than 0 and at most 1");
+                }
+                self.forecast.smoothing_alpha = alpha;
+            }
+            "otlp.endpoint" => {
+                if !value.starts_with("http://") && !value.starts_with("https://") {
+                    anyhow::bail!(
+                        "Invalid OTLP endpoint: {value}. Must start with http:// or https://"
+                    );
+                }
+                self.otlp.endpoint = value.to_string();
+            }
+            "otlp.service_name" => self.otlp.service_name = value.to_string(),
+            "notify.webhook_url" => {
+                if !value.is_empty()
+                    && !value.starts_with("http://")
+                    && !value.starts_with("https://")
+                {
+                    anyhow::bail!(
+                        "Invalid webhook URL: {value}. Must start with http:// or https://"
+                    );
+                }
+                self.notify.webhook_url = value.to_string();
+            }
+            "notify.format" => {
+                if !["generic", "slack"].contains(&value) {
+                    anyhow::bail!("Invalid webhook format: {value}. Must be 'generic' or 'slack'");
+                }
+                self.notify.format = value.to_string();
+            }
+            "notify.max_retries" => {
+                let retries: u32 = value
+                    .parse()
+                    .with_context(|| format!("Invalid retry count: {value}"))?;
+                if retries > 10 {
+                    anyhow::bail!("Retry count must be between 0 and 10");
+                }
+                self.notify.max_retries = retries;
+            }
+            "notify.anomaly_threshold" => {
+                let threshold: f64 = value
+                    .parse()
+                    .with_context(|| format!("Invalid threshold: {value}"))?;
+                if !threshold.is_finite() || threshold <= 0.0 {
+                    anyhow::bail!("Threshold must be a positive number");
+                }
+                self.notify.anomaly_threshold = threshold;
+            }
+            "notify.anomaly_lookback_days" => {
+                self.notify.anomaly_lookback_days = value
+                    .parse()
+                    .with_context(|| format!("Invalid number of days: {value}"))?;
+            }
+            _ if key.starts_with("otlp.headers.") => {
+                // An empty value removes the header
+                let name = &key["otlp.headers.".len()..];
+                if value.is_empty() {
+                    self.otlp.headers.remove(name);
+                } else {
+                    self.otlp
+                        .headers
+                        .insert(name.to_string(), value.to_string());
+                }
+            }
+            _ if key.starts_with("currency.rates.") => {
+                // Manual exchange rates: currency.rates.<CODE>
+                let currency = key["currency.rates.".len()..].to_uppercase();
+                if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                    anyhow::bail!("Invalid currency code: {currency}");
+                }
+                let rate: f64 = value
+                    .parse()
+                    .with_context(|| format!("Invalid exchange rate value: {value}"))?;
+                if !rate.is_finite() || rate <= 0.0 {
+                    anyhow::bail!("Exchange rate must be a positive number");
+                }
+                self.currency.rates.insert(currency, rate);
+            }
+            _ if key.starts_with("budgets.") => self.set_budget_value(key, value)?,
+            _ => {
+                // Plan prices: plans.<name>.monthly_price_usd (creates the plan if missing)
+                let Some(plan_name) = key
+                    .strip_prefix("plans.")
+                    .and_then(|rest| rest.strip_suffix(".monthly_price_usd"))
+                else {
+                    anyhow::bail!("Unknown configuration key: {key}");
+                };
+                let price: f64 = value
+                    .parse()
+                    .with_context(|| format!("Invalid price value: {value}"))?;
+                if !price.is_finite() || price <= 0.0 {
+                    anyhow::bail!("Plan price must be a positive number");
+                }
+                self.plans.insert(
+                    plan_name.to_string(),
+                    PlanConfig {
+                        monthly_price_usd: price,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Set `budgets.<name>.<field>`; setting `amount` creates the budget if missing
+    fn set_budget_value(&mut self, key: &str, value: &str) -> Result<()> {
+        let Some((name, field)) = key
+            .strip_prefix("budgets.")
+            .and_then(|rest| rest.rsplit_once('.'))
+        else {
+            anyhow::bail!("Unknown configuration key: {key}");
+        };
+
+        if field == "amount" {
+            let amount: f64 = value
+                .parse()
+                .with_context(|| format!("Invalid budget amount: {value}"))?;
+            if !amount.is_finite() || amount <= 0.0 {
+                anyhow::bail!("Budget amount must be a positive number");
+            }
+            self.budgets
+                .entry(name.to_string())
+                .or_insert_with(|| BudgetConfig {
+                    amount,
+                    period: default_budget_period(),
+                    currency: default_budget_currency(),
+                    project: None,
+                    model: None,
+                    warn_percent: default_warn_percent(),
+                })
+                .amount = amount;
+            return Ok(());
+        }
+
+        let Some(budget) = self.budgets.get_mut(name) else {
+            anyhow::bail!(
+                "Budget '{name}' not found. Create it with: ccost config set budgets.{name}.amount <amount>"
+            );
+        };
+
+        match field {
+            "period" => {
+                if !["daily", "weekly", "monthly"].contains(&value) {
+                    anyhow::bail!(
+                        "Invalid budget period: {value}. Must be 'daily', 'weekly', or 'monthly'"
+                    );
+                }
+                budget.period = value.to_string();
+            }
+            "currency" => budget.currency = value.to_uppercase(),
+            // An empty value removes the scope
+            "project" => budget.project = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "model" => budget.model = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "warn_percent" => {
+                let percent: f64 = value
+                    .parse()
+                    .with_context(|| format!("Invalid warning percentage: {value}"))?;
+                if !(0.0..=100.0).contains(&percent) {
+                    anyhow::bail!("Warning percentage must be between 0 and 100");
+                }
+                budget.warn_percent = percent;
+            }
             _ => anyhow::bail!("Unknown configuration key: {key}"),
         }
         Ok(())
@@ -9,4 +9,6 @@ pub mod core;
 pub mod models;
 pub mod output;
 pub mod parser;
+pub mod server;
+pub mod tui;
 pub mod utils;
@@ -13,22 +13,44 @@ mod core;
 mod models;
 mod output;
 mod parser;
+mod server;
+mod tui;
 mod utils;
 
 // Import CLI types and commands
 use cli::args::{Cli, Commands};
+use commands::anomalies::handle_anomalies_command;
+use commands::branches::handle_branches_command;
+use commands::budget::handle_budget_action;
+use commands::cache::handle_cache_command;
+use commands::commits::handle_commits_command;
+use commands::compare::handle_compare_command;
 use commands::config::handle_config_action;
 use commands::daily::handle_daily_command;
+use commands::heatmap::handle_heatmap_command;
+use commands::live::handle_live_command;
+use commands::metrics::handle_metrics_command;
+use commands::notify::handle_notify_command;
+use commands::optimize::handle_optimize_command;
+use commands::otlp::handle_otlp_command;
+use commands::plans::handle_plans_command;
+use commands::pricing::handle_pricing_action;
 use commands::projects::handle_projects_command;
+use commands::serve::handle_serve_command;
+use commands::statusline::handle_statusline_command;
 use commands::this_month::handle_this_month_command;
 use commands::this_week::handle_this_week_command;
+use commands::timeframe_utils::OutputOptions;
 use commands::today::handle_today_command;
-use commands::usage::handle_usage_command;
+use commands::tui::handle_tui_command;
+use commands::usage::{UsageTimeframe, handle_usage_command};
+use commands::whatif::handle_whatif_command;
 use commands::yesterday::handle_yesterday_command;
+use output::metrics::MetricsFormat;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
 
     // Load configuration
     let config = match Config::load() {
@@ -39,6 +61,28 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    // Number separators for table output
+    output::locale::set_locale(&config.output.locale);
+
+    // --format json is the same as --json; metrics formats replace a usage view's output
+    let metrics_format = match cli.format.as_deref() {
+        None | Some("table") => None,
+        Some("json") => {
+            cli.json = true;
+            None
+        }
+        Some(format) => match MetricsFormat::from_arg(format) {
+            Ok(format) => Some(format),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        },
+    };
+    if cli.textfile.is_some() && metrics_format.is_none() {
+        eprintln!("Error: --textfile needs --format prometheus or --format openmetrics");
+        std::process::exit(1);
+    }
     // Determine final currency (CLI override takes precedence)
     let target_currency = cli
         .currency
@@ -47,131 +91,192 @@ async fn main() -> anyhow::Result<()> {
 
     // Determine final colored setting (CLI override takes precedence)
     let colored = cli.colored || config.output.colored;
+    let options = OutputOptions {
+        currency: target_currency,
+        decimal_places: config.output.decimal_places,
+        json_output: cli.json,
+        verbose: cli.verbose,
+        colored,
+        chart: cli.chart,
+        watch: cli.watch,
+        hidden: cli.hidden,
+        timezone: &config.timezone.timezone,
+        daily_cutoff_hour: config.timezone.daily_cutoff_hour,
+        date_format: &config.output.date_format,
+        currency_provenance: cli.currency_provenance,
+    };
+
+    if let Some(metrics_format) = metrics_format {
+        let (timeframe, projects) = match &cli.command {
+            None => (None, None),
+            Some(Commands::Today { project }) => (Some(UsageTimeframe::Today), project.clone()),
+            Some(Commands::Yesterday { project }) => {
+                (Some(UsageTimeframe::Yesterday), project.clone())
+            }
+            Some(Commands::ThisWeek { project }) => {
+                (Some(UsageTimeframe::ThisWeek), project.clone())
+            }
+            Some(Commands::ThisMonth { project }) => {
+                (Some(UsageTimeframe::ThisMonth), project.clone())
+            }
+            Some(Commands::Daily { project, days }) => {
+                (Some(UsageTimeframe::Daily { days: *days }), project.clone())
+            }
+            Some(Commands::Projects { projects }) => (None, projects.clone()),
+            Some(_) => {
+                eprintln!(
+                    "Error: --format {} is only supported by ccost, today, yesterday, this-week, this-month, daily and projects",
+                    cli.format.as_deref().unwrap_or_default()
+                );
+                std::process::exit(1);
+            }
+        };
+        return handle_metrics_command(
+            metrics_format,
+            cli.textfile.clone(),
+            timeframe,
+            projects,
+            cli.filters.clone(),
+            &options,
+        )
+        .await;
+    }
 
     match cli.command {
         Some(Commands::Today { project }) => {
-            handle_today_command(
-                project,
-                cli.since.clone(),
-                cli.until.clone(),
-                cli.model.clone(),
-                target_currency,
-                config.output.decimal_places,
-                cli.json,
-                cli.verbose,
-                colored,
-                cli.hidden,
-                &config.timezone.timezone,
-                config.timezone.daily_cutoff_hour,
-                &config.output.date_format,
-            )
-            .await?;
+            handle_today_command(project, cli.filters.clone(), &options).await?;
         }
         Some(Commands::Yesterday { project }) => {
-            handle_yesterday_command(
+            handle_yesterday_command(project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::ThisWeek { project }) => {
+            handle_this_week_command(project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::ThisMonth { project }) => {
+            handle_this_month_command(project, cli.filters.clone(), &config.forecast, &options)
+                .await?;
+        }
+        Some(Commands::Daily { project, days }) => {
+            handle_daily_command(days, project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Projects { projects }) => {
+            handle_projects_command(projects, &options).await?;
+        }
+        Some(Commands::WhatIf {
+            target_model,
+            project,
+        }) => {
+            handle_whatif_command(target_model, project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Plans { project, months }) => {
+            handle_plans_command(
+                months,
                 project,
-                cli.since.clone(),
-                cli.until.clone(),
-                cli.model.clone(),
-                target_currency,
-                config.output.decimal_places,
-                cli.json,
-                cli.verbose,
-                colored,
-                cli.hidden,
-                &config.timezone.timezone,
-                config.timezone.daily_cutoff_hour,
-                &config.output.date_format,
+                cli.filters.clone(),
+                &config.plans,
+                &options,
             )
             .await?;
         }
-        Some(Commands::ThisWeek { project }) => {
-            handle_this_week_command(
+        Some(Commands::Compare {
+            baseline,
+            current,
+            project,
+            by,
+        }) => {
+            handle_compare_command(
+                baseline,
+                current,
+                by,
                 project,
-                cli.since.clone(),
-                cli.until.clone(),
-                cli.model.clone(),
-                target_currency,
-                config.output.decimal_places,
-                cli.json,
-                cli.verbose,
-                colored,
-                cli.hidden,
-                &config.timezone.timezone,
-                config.timezone.daily_cutoff_hour,
-                &config.output.date_format,
+                cli.filters.model.clone(),
+                cli.filters.branch.clone(),
+                &options,
             )
             .await?;
         }
-        Some(Commands::ThisMonth { project }) => {
-            handle_this_month_command(
+        Some(Commands::Anomalies { project, threshold }) => {
+            handle_anomalies_command(threshold, project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Cache { project, by }) => {
+            handle_cache_command(by, project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Commits { project, by }) => {
+            handle_commits_command(by, project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Branches { project }) => {
+            handle_branches_command(project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Heatmap {
+            project,
+            metric,
+            csv,
+        }) => {
+            handle_heatmap_command(metric, csv, project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Optimize { project }) => {
+            handle_optimize_command(project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Tui { project }) => {
+            handle_tui_command(project, cli.filters.clone(), &options).await?;
+        }
+        Some(Commands::Serve { port }) => {
+            handle_serve_command(port, &options).await?;
+        }
+        Some(Commands::Otlp {
+            project,
+            endpoint,
+            dry_run,
+        }) => {
+            handle_otlp_command(
+                &config.otlp,
+                endpoint,
+                dry_run,
                 project,
-                cli.since.clone(),
-                cli.until.clone(),
-                cli.model.clone(),
-                target_currency,
-                config.output.decimal_places,
-                cli.json,
-                cli.verbose,
-                colored,
-                cli.hidden,
-                &config.timezone.timezone,
-                config.timezone.daily_cutoff_hour,
-                &config.output.date_format,
+                cli.filters.clone(),
+                &options,
             )
             .await?;
         }
-        Some(Commands::Daily { project, days }) => {
-            handle_daily_command(
-                days,
+        Some(Commands::Statusline) => {
+            handle_statusline_command(&options).await?;
+        }
+        Some(Commands::Live { project }) => {
+            handle_live_command(
                 project,
-                cli.since.clone(),
-                cli.until.clone(),
-                cli.model.clone(),
-                target_currency,
-                config.output.decimal_places,
-                cli.json,
-                cli.verbose,
-                colored,
-                cli.hidden,
-                &config.timezone.timezone,
-                config.timezone.daily_cutoff_hour,
-                &config.output.date_format,
+                cli.filters.model.clone(),
+                cli.filters.branch.clone(),
+                &options,
             )
             .await?;
         }
-        Some(Commands::Projects { projects }) => {
-            handle_projects_command(
-                projects,
-                target_currency,
-                config.output.decimal_places,
-                cli.json,
-                cli.verbose,
-                colored,
-                cli.hidden,
+        Some(Commands::Budget { action }) => {
+            handle_budget_action(action, &config.budgets, &config.currency, &options).await?;
+        }
+        Some(Commands::Notify { url, dry_run }) => {
+            handle_notify_command(
+                &config.notify,
+                &config.budgets,
+                &config.currency,
+                url,
+                dry_run,
+                &options,
             )
             .await?;
         }
         Some(Commands::Config { action }) => {
             handle_config_action(action, cli.json);
         }
+        Some(Commands::Pricing { action }) => {
+            handle_pricing_action(action, &config.pricing, &options).await?;
+        }
         None => {
             // Default behavior: show overall usage summary
             handle_usage_command(
                 None,
-                cli.model.clone(),
-                cli.since.clone(),
-                cli.until.clone(),
-                cli.model.clone(),
-                target_currency,
-                config.output.decimal_places,
-                cli.json,
-                cli.verbose,
-                colored,
-                cli.hidden,
-                &config.timezone.timezone,
-                config.timezone.daily_cutoff_hour,
-                &config.output.date_format,
+                cli.filters.model.clone(),
+                cli.filters.clone(),
+                &options,
             )
             .await?;
         }
@@ -1,11 +1,16 @@
+use crate::config::settings::{CurrencyConfig, default_history_url, default_provider_url};
+use crate::output::locale::{NumberLocale, current_locale};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use reqwest;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use tokio::sync::OnceCell;
 
 /// Cache entry for a single currency conversion rate
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +19,9 @@ pub struct CurrencyCacheEntry {
     pub rate_from_usd: f64,
     /// Timestamp when this rate was fetched
     pub timestamp: DateTime<Utc>,
+    /// ECB publication date of the rate
+    #[serde(default)]
+    pub rate_date: Option<NaiveDate>,
 }
 
 /// Currency conversion cache stored as JSON file
@@ -23,9 +31,211 @@ pub struct CurrencyCache {
     pub rates: HashMap<String, CurrencyCacheEntry>,
 }
 
+/// Which exchange rate applies to a USD amount
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateMode {
+    /// Today's ECB rate for every amount
+    Latest,
+    /// The ECB rate published for the day the cost was incurred
+    Historical,
+}
+
+impl RateMode {
+    /// Parse from config string ("latest", "historical"); unknown values mean latest
+    pub fn from_config_str(mode: &str) -> Self {
+        match mode {
+            "historical" => RateMode::Historical,
+            _ => RateMode::Latest,
+        }
+    }
+}
+
+/// Where the exchange rate behind converted amounts came from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateSource {
+    /// No conversion needed (USD output)
+    None,
+    /// Fetched from the rate provider during this run
+    Ecb,
+    /// Read from the 24-hour rate cache
+    Cache,
+    /// Expired cache entry used because the provider was unreachable
+    StaleCache,
+    /// Fixed rate from `[currency.rates]`
+    Manual,
+    /// Conversion failed; amounts are still in USD
+    Unavailable,
+}
+
+impl RateSource {
+    /// Ordering used when several sources contributed: the least fresh one is reported
+    fn staleness(self) -> u8 {
+        match self {
+            RateSource::None => 0,
+            RateSource::Manual => 1,
+            RateSource::Ecb => 2,
+            RateSource::Cache => 3,
+            RateSource::StaleCache => 4,
+            RateSource::Unavailable => 5,
+        }
+    }
+
+    fn least_fresh(self, other: RateSource) -> RateSource {
+        if other.staleness() > self.staleness() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// A rate the converter applied, kept for provenance reporting
+#[derive(Debug, Clone, Copy)]
+struct RateRecord {
+    rate: f64,
+    rate_date: Option<NaiveDate>,
+    source: RateSource,
+}
+
+/// How converted amounts in a report were obtained, included in JSON output
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CurrencyProvenance {
+    pub target: String,
+    pub rate: f64, // USD to target; the effective average in historical mode
+    pub rate_date: Option<NaiveDate>, // Publication date (the latest used in historical mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub earliest_rate_date: Option<NaiveDate>, // Historical mode only
+    pub source: RateSource,
+    pub rate_mode: RateMode,
+    pub original_total_usd: f64,
+    pub converted_total: f64,
+}
+
+impl CurrencyProvenance {
+    /// Merge the provenance of several conversions into the same currency
+    ///
+    /// Returns `None` when the parts target different currencies. Totals are
+    /// summed and the least fresh source is reported.
+    pub fn combine(parts: &[CurrencyProvenance]) -> Option<CurrencyProvenance> {
+        let (first, rest) = parts.split_first()?;
+        if rest.iter().any(|part| part.target != first.target) {
+            return None;
+        }
+
+        let mut combined = first.clone();
+        for part in rest {
+            combined.rate_date = combined.rate_date.max(part.rate_date);
+            combined.earliest_rate_date =
+                match (combined.earliest_rate_date, part.earliest_rate_date) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            combined.source = combined.source.least_fresh(part.source);
+            combined.original_total_usd += part.original_total_usd;
+            combined.converted_total += part.converted_total;
+        }
+        if combined.original_total_usd.abs() > f64::EPSILON {
+            combined.rate = combined.converted_total / combined.original_total_usd;
+        }
+        Some(combined)
+    }
+}
+
+/// ECB reference rate series (EUR base) stored as JSON file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoricalRates {
+    /// Map of publication date to currency code to EUR rate
+    pub rates: BTreeMap<NaiveDate, HashMap<String, f64>>,
+    /// Timestamp when the series was last refreshed
+    pub timestamp: DateTime<Utc>,
+}
+
+impl HistoricalRates {
+    /// Parse an ECB eurofxref history XML document
+    pub fn parse_ecb_xml(xml_text: &str) -> Result<BTreeMap<NaiveDate, HashMap<String, f64>>> {
+        // Day cubes look like <Cube time='2025-06-09'> followed by
+        // <Cube currency='USD' rate='1.1419'/> entries (ECB uses single quotes)
+        let re = regex::Regex::new(
+            r#"time='(\d{4}-\d{2}-\d{2})'|currency='([A-Z]{3})' rate='([0-9.]+)'"#,
+        )
+        .context("Failed to create regex")?;
+
+        let mut rates: BTreeMap<NaiveDate, HashMap<String, f64>> = BTreeMap::new();
+        let mut current_date: Option<NaiveDate> = None;
+
+        for captures in re.captures_iter(xml_text) {
+            if let Some(date) = captures.get(1) {
+                current_date = Some(
+                    NaiveDate::parse_from_str(date.as_str(), "%Y-%m-%d")
+                        .context("Failed to parse ECB date")?,
+                );
+            } else if let (Some(date), Some(currency), Some(rate)) =
+                (current_date, captures.get(2), captures.get(3))
+            {
+                let rate: f64 = rate
+                    .as_str()
+                    .parse()
+                    .context("Failed to parse exchange rate")?;
+                rates
+                    .entry(date)
+                    .or_default()
+                    .insert(currency.as_str().to_string(), rate);
+            }
+        }
+
+        if rates.is_empty() {
+            anyhow::bail!("No exchange rates found in ECB history data");
+        }
+
+        Ok(rates)
+    }
+
+    /// USD to `currency` rate for a date, carrying the last published rate
+    /// forward over weekends and TARGET holidays
+    ///
+    /// Returns the publication date the rate was taken from along with the rate.
+    pub fn usd_rate_on(&self, date: NaiveDate, currency: &str) -> Option<(NaiveDate, f64)> {
+        self.rates
+            .range(..=date)
+            .rev()
+            .find_map(|(published, day)| {
+                let eur_to_usd = *day.get("USD")?;
+                let eur_to_target = if currency == "EUR" {
+                    1.0
+                } else {
+                    *day.get(currency)?
+                };
+                Some((*published, eur_to_target / eur_to_usd))
+            })
+    }
+
+    /// Whether the series was refreshed within the last 24 hours
+    fn is_fresh(&self) -> bool {
+        Utc::now().signed_duration_since(self.timestamp).num_hours() < 24
+    }
+}
+
+/// The 90-day feed published next to an ECB-layout `eurofxref-hist.xml`, if any
+fn ninety_day_history_url(history_url: &str) -> Option<String> {
+    history_url
+        .strip_suffix("eurofxref-hist.xml")
+        .map(|base| format!("{base}eurofxref-hist-90d.xml"))
+}
+
 /// Currency conversion manager with ECB API and 24-hour file-based caching
 pub struct CurrencyConverter {
     client: reqwest::Client,
+    rate_mode: RateMode,
+    history: OnceCell<Option<HistoricalRates>>, // None once the series proved unavailable
+    history_source: Mutex<Option<RateSource>>,  // How the loaded series was obtained
+    provider_url: String,
+    history_url: String,
+    manual_rates: HashMap<String, f64>, // Units of currency per USD, from config
+    stale_warned: AtomicBool,
+    latest_rate: Mutex<Option<RateRecord>>,
+    historical_dates: Mutex<Option<(NaiveDate, NaiveDate)>>, // Publication dates used
 }
 
 impl CurrencyConverter {
@@ -37,7 +247,32 @@ impl CurrencyConverter {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client }
+        Self {
+            client,
+            rate_mode: RateMode::Latest,
+            history: OnceCell::new(),
+            history_source: Mutex::new(None),
+            provider_url: default_provider_url(),
+            history_url: default_history_url(),
+            manual_rates: HashMap::new(),
+            stale_warned: AtomicBool::new(false),
+            latest_rate: Mutex::new(None),
+            historical_dates: Mutex::new(None),
+        }
+    }
+
+    /// Create a converter using the configured rate mode, provider and manual rates
+    pub fn from_config(config: &CurrencyConfig) -> Self {
+        let mut converter = Self::new();
+        converter.rate_mode = RateMode::from_config_str(&config.rate_mode);
+        converter.provider_url = config.provider_url.clone();
+        converter.history_url = config.history_url.clone();
+        converter.manual_rates = config
+            .rates
+            .iter()
+            .map(|(currency, rate)| (currency.to_uppercase(), *rate))
+            .collect();
+        converter
     }
 
     /// Get path to currency cache file
@@ -91,6 +326,113 @@ impl CurrencyConverter {
         Ok(())
     }
 
+    /// Get path to historical rate cache file (next to currency_cache.json)
+    fn get_history_cache_path() -> Result<PathBuf> {
+        Ok(Self::get_cache_path()?.with_file_name("currency_history.json"))
+    }
+
+    /// Load historical rate cache from file regardless of its age
+    fn load_history_cache() -> Option<HistoricalRates> {
+        let cache_path = Self::get_history_cache_path().ok()?;
+        let contents = fs::read_to_string(&cache_path).ok()?;
+        serde_json::from_str::<HistoricalRates>(&contents).ok()
+    }
+
+    /// Save historical rate cache to file
+    fn save_history_cache(history: &HistoricalRates) -> Result<()> {
+        let cache_path = Self::get_history_cache_path()?;
+
+        // Ensure parent directory exists
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory: {}", parent.display())
+            })?;
+        }
+
+        let contents =
+            serde_json::to_string(history).context("Failed to serialize rate history")?;
+
+        fs::write(&cache_path, contents)
+            .with_context(|| format!("Failed to write cache file: {}", cache_path.display()))?;
+
+        Ok(())
+    }
+
+    /// Get the ECB rate history, refreshing the on-disk series at most once a day
+    ///
+    /// Returns `None` if the series can neither be fetched nor read from cache;
+    /// the outcome is remembered so a failing fetch is only attempted once.
+    async fn historical_rates(&self) -> Option<&HistoricalRates> {
+        self.history
+            .get_or_init(|| async {
+                let cached = Self::load_history_cache();
+                if let Some(history) = &cached
+                    && history.is_fresh()
+                {
+                    self.record_history_source(RateSource::Cache);
+                    return cached;
+                }
+
+                // The 90-day feed is enough to top up a recent series; otherwise fetch it all
+                let recent_enough = cached
+                    .as_ref()
+                    .and_then(|h| h.rates.keys().next_back())
+                    .is_some_and(|last| {
+                        Utc::now()
+                            .date_naive()
+                            .signed_duration_since(*last)
+                            .num_days()
+                            < 80
+                    });
+                let url = recent_enough
+                    .then(|| ninety_day_history_url(&self.history_url))
+                    .flatten()
+                    .unwrap_or_else(|| self.history_url.clone());
+
+                match self.fetch_ecb_history(&url).await {
+                    Ok(fetched) => {
+                        let mut rates = cached.map(|h| h.rates).unwrap_or_default();
+                        rates.extend(fetched);
+                        let history = HistoricalRates {
+                            rates,
+                            timestamp: Utc::now(),
+                        };
+                        // Save cache (ignore errors to not fail the conversion)
+                        let _ = Self::save_history_cache(&history);
+                        self.record_history_source(RateSource::Ecb);
+                        Some(history)
+                    }
+                    // A stale series still beats failing outright
+                    Err(_) => {
+                        self.record_history_source(RateSource::StaleCache);
+                        cached
+                    }
+                }
+            })
+            .await
+            .as_ref()
+    }
+
+    /// Fetch and parse an ECB history feed
+    async fn fetch_ecb_history(
+        &self,
+        url: &str,
+    ) -> Result<BTreeMap<NaiveDate, HashMap<String, f64>>> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .context("Failed to fetch ECB exchange rate history")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("ECB API returned error: {}", response.status());
+        }
+
+        let xml_text = response.text().await?;
+        HistoricalRates::parse_ecb_xml(&xml_text)
+    }
+
     /// Check if cache entry is still valid (less than 24 hours old)
     fn is_cache_valid(entry: &CurrencyCacheEntry) -> bool {
         let now = Utc::now();
@@ -104,15 +446,76 @@ impl CurrencyConverter {
             return Ok(amount);
         }
 
+        if let Some(rate) = self.manual_rates.get(target_currency) {
+            self.record_rate(*rate, None, RateSource::Manual);
+            return Ok(amount * rate);
+        }
+
         let rate = self.get_exchange_rate("USD", target_currency).await?;
         Ok(amount * rate)
     }
 
+    /// Convert amount from USD using the rate for `date` when in historical mode
+    ///
+    /// Falls back to the latest rate if the date predates the ECB series or the
+    /// series is unavailable.
+    pub async fn convert_from_usd_on(
+        &self,
+        amount: f64,
+        target_currency: &str,
+        date: NaiveDate,
+    ) -> Result<f64> {
+        if target_currency == "USD" {
+            return Ok(amount);
+        }
+
+        if self.rate_mode == RateMode::Historical
+            && !self.manual_rates.contains_key(target_currency)
+            && let Some(history) = self.historical_rates().await
+            && let Some((published, rate)) = history.usd_rate_on(date, target_currency)
+        {
+            self.record_historical_date(published);
+            return Ok(amount * rate);
+        }
+
+        self.convert_from_usd(amount, target_currency).await
+    }
+
+    /// Convert a USD total accrued over several days
+    ///
+    /// In historical mode each day's amount uses that day's rate; any part of
+    /// the total without a date uses the latest rate. In latest mode this is
+    /// equivalent to `convert_from_usd(total_usd, ...)`.
+    pub async fn convert_daily_from_usd(
+        &self,
+        total_usd: f64,
+        daily_usd: &BTreeMap<NaiveDate, f64>,
+        target_currency: &str,
+    ) -> Result<f64> {
+        if self.rate_mode == RateMode::Latest || daily_usd.is_empty() {
+            return self.convert_from_usd(total_usd, target_currency).await;
+        }
+
+        let mut converted = 0.0;
+        for (date, amount) in daily_usd {
+            converted += self
+                .convert_from_usd_on(*amount, target_currency, *date)
+                .await?;
+        }
+
+        let undated = total_usd - daily_usd.values().sum::<f64>();
+        if undated.abs() > 1e-9 {
+            converted += self.convert_from_usd(undated, target_currency).await?;
+        }
+
+        Ok(converted)
+    }
+
     /// Get exchange rate between two currencies with caching
     async fn get_exchange_rate(&self, from_currency: &str, to_currency: &str) -> Result<f64> {
         // Only cache USD to other currency conversions for simplicity
         if from_currency != "USD" {
-            return self.fetch_ecb_rate(from_currency, to_currency).await;
+            return Ok(self.fetch_ecb_rate(from_currency, to_currency).await?.0);
         }
 
         // Load cache
@@ -121,12 +524,23 @@ impl CurrencyConverter {
         // Check if we have a valid cached rate
         if let Some(entry) = cache.rates.get(to_currency) {
             if Self::is_cache_valid(entry) {
+                self.record_rate(entry.rate_from_usd, entry.rate_date, RateSource::Cache);
                 return Ok(entry.rate_from_usd);
             }
         }
 
-        // Cache miss or expired - fetch fresh rate
-        let rate = self.fetch_ecb_rate(from_currency, to_currency).await?;
+        // Cache miss or expired - fetch fresh rate, falling back to an expired entry
+        let (rate, rate_date) = match self.fetch_ecb_rate(from_currency, to_currency).await {
+            Ok(fetched) => fetched,
+            Err(e) => match cache.rates.get(to_currency) {
+                Some(entry) => {
+                    self.warn_stale(to_currency, entry, &e);
+                    self.record_rate(entry.rate_from_usd, entry.rate_date, RateSource::StaleCache);
+                    return Ok(entry.rate_from_usd);
+                }
+                None => return Err(e),
+            },
+        };
 
         // Update cache
         cache.rates.insert(
@@ -134,8 +548,10 @@ impl CurrencyConverter {
             CurrencyCacheEntry {
                 rate_from_usd: rate,
                 timestamp: Utc::now(),
+                rate_date,
             },
         );
+        self.record_rate(rate, rate_date, RateSource::Ecb);
 
         // Save cache (ignore errors to not fail the conversion)
         let _ = Self::save_cache(&cache);
@@ -143,18 +559,142 @@ impl CurrencyConverter {
         Ok(rate)
     }
 
+    fn record_rate(&self, rate: f64, rate_date: Option<NaiveDate>, source: RateSource) {
+        if let Ok(mut latest) = self.latest_rate.lock() {
+            *latest = Some(match *latest {
+                // Reading back the entry this run just fetched is still a fetched rate
+                Some(previous)
+                    if previous.source == RateSource::Ecb
+                        && source == RateSource::Cache
+                        && previous.rate == rate =>
+                {
+                    previous
+                }
+                _ => RateRecord {
+                    rate,
+                    rate_date,
+                    source,
+                },
+            });
+        }
+    }
+
+    fn record_history_source(&self, source: RateSource) {
+        if let Ok(mut history_source) = self.history_source.lock() {
+            *history_source = Some(source);
+        }
+    }
+
+    /// Forget the rates recorded for provenance so the next report starts clean
+    ///
+    /// Call before each render when one converter serves several reports
+    /// (watch mode, the HTTP server). A series fetched earlier is reported as
+    /// cached from then on.
+    pub fn reset_provenance(&self) {
+        if let Ok(mut latest) = self.latest_rate.lock() {
+            *latest = None;
+        }
+        if let Ok(mut dates) = self.historical_dates.lock() {
+            *dates = None;
+        }
+        if let Ok(mut history_source) = self.history_source.lock()
+            && *history_source == Some(RateSource::Ecb)
+        {
+            *history_source = Some(RateSource::Cache);
+        }
+    }
+
+    fn record_historical_date(&self, published: NaiveDate) {
+        if let Ok(mut dates) = self.historical_dates.lock() {
+            *dates = Some(match *dates {
+                Some((first, last)) => (first.min(published), last.max(published)),
+                None => (published, published),
+            });
+        }
+    }
+
+    /// Describe how `original_total_usd` became `converted_total` for JSON output
+    pub fn provenance(
+        &self,
+        target_currency: &str,
+        original_total_usd: f64,
+        converted_total: f64,
+    ) -> CurrencyProvenance {
+        let latest = self.latest_rate.lock().ok().and_then(|latest| *latest);
+        let historical = self.historical_dates.lock().ok().and_then(|dates| *dates);
+
+        let (rate_date, earliest_rate_date, source) = if target_currency == "USD" {
+            (None, None, RateSource::None)
+        } else if let Some((first, last)) = historical {
+            // Days before the ECB series (if any) used the latest rate
+            let last = latest
+                .and_then(|l| l.rate_date)
+                .map_or(last, |date| date.max(last));
+            let history_source = self
+                .history_source
+                .lock()
+                .ok()
+                .and_then(|source| *source)
+                .unwrap_or(RateSource::Cache);
+            let source = latest.map_or(history_source, |latest| {
+                history_source.least_fresh(latest.source)
+            });
+            (Some(last), Some(first), source)
+        } else {
+            match latest {
+                Some(latest) => (latest.rate_date, None, latest.source),
+                None => (None, None, RateSource::Unavailable),
+            }
+        };
+
+        let rate = match source {
+            RateSource::None | RateSource::Unavailable => 1.0,
+            _ if original_total_usd.abs() > f64::EPSILON => converted_total / original_total_usd,
+            _ => latest.map_or(1.0, |latest| latest.rate),
+        };
+
+        CurrencyProvenance {
+            target: target_currency.to_string(),
+            rate,
+            rate_date,
+            earliest_rate_date,
+            source,
+            rate_mode: self.rate_mode,
+            original_total_usd,
+            converted_total,
+        }
+    }
+
+    /// Warn (once per converter) that an expired cached rate is being used
+    fn warn_stale(&self, currency: &str, entry: &CurrencyCacheEntry, error: &anyhow::Error) {
+        if self.stale_warned.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let age_days = Utc::now().signed_duration_since(entry.timestamp).num_days();
+        eprintln!(
+            "Warning: Could not refresh exchange rates ({error}); using cached USD→{currency} rate from {} ({age_days} days old)",
+            entry.timestamp.format("%Y-%m-%d")
+        );
+    }
+
     /// Fetch exchange rate from ECB API
-    async fn fetch_ecb_rate(&self, from_currency: &str, to_currency: &str) -> Result<f64> {
+    ///
+    /// Returns the rate and the ECB publication date, when the feed has one.
+    async fn fetch_ecb_rate(
+        &self,
+        from_currency: &str,
+        to_currency: &str,
+    ) -> Result<(f64, Option<NaiveDate>)> {
         // ECB provides rates with EUR as base currency
         // For non-EUR conversions, we need to calculate through EUR
-        let eur_to_target = if to_currency == "EUR" {
-            1.0
+        let (eur_to_target, target_date) = if to_currency == "EUR" {
+            (1.0, None)
         } else {
             self.fetch_eur_rate(to_currency).await?
         };
 
-        let eur_to_base = if from_currency == "EUR" {
-            1.0
+        let (eur_to_base, base_date) = if from_currency == "EUR" {
+            (1.0, None)
         } else {
             self.fetch_eur_rate(from_currency).await?
         };
@@ -162,19 +702,18 @@ impl CurrencyConverter {
         // Convert from base to target via EUR
         // Rate = (1 / EUR_to_base) * EUR_to_target
         let rate = eur_to_target / eur_to_base;
-        Ok(rate)
+        Ok((rate, target_date.or(base_date)))
     }
 
-    /// Fetch EUR to currency rate from ECB API
-    async fn fetch_eur_rate(&self, currency: &str) -> Result<f64> {
+    /// Fetch EUR to currency rate and its publication date from ECB API
+    async fn fetch_eur_rate(&self, currency: &str) -> Result<(f64, Option<NaiveDate>)> {
         if currency == "EUR" {
-            return Ok(1.0);
+            return Ok((1.0, None));
         }
 
-        let url = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
         let response = self
             .client
-            .get(url)
+            .get(&self.provider_url)
             .send()
             .await
             .context("Failed to fetch ECB exchange rates")?;
@@ -190,37 +729,321 @@ impl CurrencyConverter {
         let pattern = format!(r#"currency='{currency}' rate='([0-9.]+)'"#);
         let re = regex::Regex::new(&pattern).context("Failed to create regex")?;
 
+        // The publication date is on the enclosing <Cube time='YYYY-MM-DD'>
+        let date_re =
+            regex::Regex::new(r#"time='(\d{4}-\d{2}-\d{2})'"#).context("Failed to create regex")?;
+        let rate_date = date_re
+            .captures(&xml_text)
+            .and_then(|captures| NaiveDate::parse_from_str(&captures[1], "%Y-%m-%d").ok());
+
         if let Some(captures) = re.captures(&xml_text) {
             let rate_str = captures.get(1).unwrap().as_str();
             let rate: f64 = rate_str.parse().context("Failed to parse exchange rate")?;
-            Ok(rate)
+            Ok((rate, rate_date))
         } else {
             anyhow::bail!("Currency {currency} not found in ECB data")
         }
     }
 }
 
+/// How a currency is written: symbol and minor-unit digits
+///
+/// Where the symbol goes is up to the locale (`NumberLocale::symbol_prefix`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurrencyStyle {
+    pub symbol: &'static str,
+    pub max_decimals: u8, // ISO 4217 minor units, e.g. 0 for JPY
+}
+
+/// Formatting conventions for USD and every currency in the ECB reference rates
+pub fn currency_style(currency: &str) -> Option<CurrencyStyle> {
+    let (symbol, max_decimals) = match currency {
+        "USD" => ("$", 2),
+        "EUR" => ("€", 2),
+        "GBP" => ("£", 2),
+        "JPY" => ("¥", 0),
+        "CNY" => ("CN¥", 2),
+        "KRW" => ("₩", 0),
+        "INR" => ("₹", 2),
+        "CHF" => ("CHF", 2),
+        "CAD" => ("CA$", 2),
+        "AUD" => ("A$", 2),
+        "NZD" => ("NZ$", 2),
+        "HKD" => ("HK$", 2),
+        "SGD" => ("S$", 2),
+        "MXN" => ("MX$", 2),
+        "BRL" => ("R$", 2),
+        "ZAR" => ("R", 2),
+        "TRY" => ("₺", 2),
+        "THB" => ("฿", 2),
+        "PHP" => ("₱", 2),
+        "MYR" => ("RM", 2),
+        "IDR" => ("Rp", 2),
+        "ILS" => ("₪", 2),
+        "PLN" => ("zł", 2),
+        "CZK" => ("Kč", 2),
+        "HUF" => ("Ft", 2),
+        "RON" => ("lei", 2),
+        "BGN" => ("лв", 2),
+        "DKK" => ("kr.", 2),
+        "SEK" => ("kr", 2),
+        "NOK" => ("kr", 2),
+        "ISK" => ("kr", 0),
+        _ => return None,
+    };
+    Some(CurrencyStyle {
+        symbol,
+        max_decimals,
+    })
+}
+
 /// Format currency amount with appropriate symbol and decimals
+///
+/// Separators follow the configured `output.locale`; zero-decimal currencies
+/// such as JPY never show minor units.
 pub fn format_currency(amount: f64, currency: &str, decimal_places: u8) -> String {
-    let symbol = match currency {
-        "USD" => "$",
-        "EUR" => "€",
-        "GBP" => "£",
-        "JPY" => "¥",
-        "CNY" => "¥",
-        _ => currency,
+    format_currency_with_locale(amount, currency, decimal_places, &current_locale())
+}
+
+/// Format currency amount for an explicit locale
+pub fn format_currency_with_locale(
+    amount: f64,
+    currency: &str,
+    decimal_places: u8,
+    locale: &NumberLocale,
+) -> String {
+    let Some(style) = currency_style(currency) else {
+        let formatted_amount = locale.format_decimal(amount, decimal_places as usize);
+        return format!("{formatted_amount} {currency}");
     };
 
-    // Format with thousands separators
-    let formatted_amount = if decimal_places == 0 {
-        format!("{amount:.0}")
+    let decimal_places = decimal_places.min(style.max_decimals) as usize;
+    let formatted_amount = locale.format_decimal(amount, decimal_places);
+
+    if !locale.symbol_prefix {
+        let separator = if locale.symbol_space { " " } else { "" };
+        return format!("{formatted_amount}{separator}{}", style.symbol);
+    }
+
+    // Keep the sign ahead of the symbol ("-$1.00"); letter symbols get a space ("CHF 12.34")
+    let (sign, digits) = match formatted_amount.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", formatted_amount.as_str()),
+    };
+    let separator = if locale.symbol_space || style.symbol.ends_with(|c: char| c.is_alphabetic()) {
+        " "
     } else {
-        format!("{:.width$}", amount, width = decimal_places as usize)
+        ""
     };
+    format!("{sign}{}{separator}{digits}", style.symbol)
+}
+
+/// Format a signed amount with an explicit sign, e.g. "-$12.40" or "+$3.10"
+pub fn format_currency_signed(amount: f64, currency: &str, decimal_places: u8) -> String {
+    let sign = if amount < 0.0 { "-" } else { "+" };
+    format!(
+        "{}{}",
+        sign,
+        format_currency(amount.abs(), currency, decimal_places)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ECB_SAMPLE: &str = "<Cube>\
+        <Cube time='2025-06-09'><Cube currency='USD' rate='1.1400'/><Cube currency='GBP' rate='0.8400'/></Cube>\
+        <Cube time='2025-06-06'><Cube currency='USD' rate='1.2000'/><Cube currency='GBP' rate='0.9000'/></Cube>\
+        </Cube>";
+
+    #[test]
+    fn test_historical_rates_carry_forward_over_weekend() {
+        let history = HistoricalRates {
+            rates: HistoricalRates::parse_ecb_xml(ECB_SAMPLE).unwrap(),
+            timestamp: Utc::now(),
+        };
+        let date = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+
+        assert_eq!(history.rates.len(), 2);
+        // Saturday uses Friday's publication
+        let (published, saturday) = history.usd_rate_on(date("2025-06-07"), "GBP").unwrap();
+        assert_eq!(published, date("2025-06-06"));
+        assert!((saturday - 0.75).abs() < 1e-9);
+        let (_, monday) = history.usd_rate_on(date("2025-06-09"), "EUR").unwrap();
+        assert!((monday - 1.0 / 1.14).abs() < 1e-9);
+        // Nothing published before the series starts
+        assert!(history.usd_rate_on(date("2025-06-01"), "GBP").is_none());
+    }
+
+    #[test]
+    fn test_format_currency_styles() {
+        let us = NumberLocale::default();
+        let de = NumberLocale::from_tag("de-DE").unwrap();
+
+        assert_eq!(
+            format_currency_with_locale(1234.5, "USD", 2, &us),
+            "$1,234.50"
+        );
+        assert_eq!(format_currency_with_locale(-3.1, "USD", 2, &us), "-$3.10");
+        assert_eq!(
+            format_currency_with_locale(1234.5, "EUR", 2, &de),
+            "1.234,50 €"
+        );
+        // The locale, not the currency, decides where the symbol goes
+        assert_eq!(format_currency_with_locale(10.45, "EUR", 2, &us), "€10.45");
+        assert_eq!(
+            format_currency_with_locale(-10.45, "EUR", 2, &us),
+            "-€10.45"
+        );
+        assert_eq!(format_currency_with_locale(12.34, "USD", 2, &de), "12,34 $");
+        let br = NumberLocale::from_tag("pt-BR").unwrap();
+        assert_eq!(
+            format_currency_with_locale(12.34, "BRL", 2, &br),
+            "R$ 12,34"
+        );
+        assert_eq!(
+            format_currency_with_locale(12.34, "CHF", 2, &us),
+            "CHF 12.34"
+        );
+        // Zero-decimal currencies ignore decimal_places
+        assert_eq!(
+            format_currency_with_locale(1234.56, "JPY", 2, &us),
+            "¥1,235"
+        );
+        assert_eq!(
+            format_currency_with_locale(98765.4, "KRW", 4, &us),
+            "₩98,765"
+        );
+        assert_eq!(format_currency_with_locale(5.0, "XYZ", 2, &us), "5.00 XYZ");
+    }
+
+    #[test]
+    fn test_ninety_day_history_url() {
+        assert_eq!(
+            ninety_day_history_url(&default_history_url()).as_deref(),
+            Some("https://www.ecb.europa.eu/stats/eurofxref/eurofxref-hist-90d.xml")
+        );
+        assert_eq!(
+            ninety_day_history_url("http://mirror.local/rates/eurofxref-hist.xml").as_deref(),
+            Some("http://mirror.local/rates/eurofxref-hist-90d.xml")
+        );
+        assert_eq!(ninety_day_history_url("http://mirror.local/history"), None);
+    }
+
+    #[tokio::test]
+    async fn test_manual_rates_skip_fetching() {
+        let config = CurrencyConfig {
+            default_currency: "EUR".to_string(),
+            rate_mode: "historical".to_string(),
+            // Unreachable provider: any fetch would fail
+            provider_url: "http://127.0.0.1:9/eurofxref-daily.xml".to_string(),
+            history_url: "http://127.0.0.1:9/eurofxref-hist.xml".to_string(),
+            rates: BTreeMap::from([("eur".to_string(), 0.5)]),
+        };
+        let converter = CurrencyConverter::from_config(&config);
+        let date = NaiveDate::from_ymd_opt(2025, 6, 7).unwrap();
+
+        assert_eq!(converter.convert_from_usd(10.0, "EUR").await.unwrap(), 5.0);
+        assert_eq!(
+            converter
+                .convert_from_usd_on(10.0, "EUR", date)
+                .await
+                .unwrap(),
+            5.0
+        );
+        assert_eq!(
+            converter
+                .convert_daily_from_usd(10.0, &BTreeMap::from([(date, 4.0)]), "EUR")
+                .await
+                .unwrap(),
+            5.0
+        );
+
+        let provenance = converter.provenance("EUR", 10.0, 5.0);
+        assert_eq!(provenance.source, RateSource::Manual);
+        assert_eq!(provenance.rate, 0.5);
+        assert_eq!(provenance.rate_date, None);
+        assert_eq!(
+            converter.provenance("USD", 10.0, 10.0).source,
+            RateSource::None
+        );
+    }
+
+    #[test]
+    fn test_provenance_reports_how_rates_were_obtained() {
+        let config = CurrencyConfig {
+            default_currency: "EUR".to_string(),
+            rate_mode: "historical".to_string(),
+            provider_url: default_provider_url(),
+            history_url: default_history_url(),
+            rates: BTreeMap::new(),
+        };
+        let converter = CurrencyConverter::from_config(&config);
+        let date = NaiveDate::from_ymd_opt(2025, 6, 6).unwrap();
 
-    match currency {
-        "USD" | "GBP" => format!("{symbol}{formatted_amount}"),
-        "EUR" | "JPY" | "CNY" => format!("{formatted_amount} {symbol}"),
-        _ => format!("{formatted_amount} {currency}"),
+        // A series kept after a failed refresh is not a fresh ECB fetch
+        converter.record_history_source(RateSource::StaleCache);
+        converter.record_historical_date(date);
+        let provenance = converter.provenance("EUR", 10.0, 9.0);
+        assert_eq!(provenance.source, RateSource::StaleCache);
+        assert_eq!(provenance.earliest_rate_date, Some(date));
+
+        // The next render starts without the previous report's rates
+        converter.reset_provenance();
+        assert_eq!(
+            converter.provenance("EUR", 10.0, 10.0).source,
+            RateSource::Unavailable
+        );
+
+        // Reading back the rate this run fetched still counts as fetched
+        converter.record_rate(0.9, Some(date), RateSource::Ecb);
+        converter.record_rate(0.9, Some(date), RateSource::Cache);
+        assert_eq!(
+            converter.provenance("EUR", 10.0, 9.0).source,
+            RateSource::Ecb
+        );
+
+        // After a reset an already loaded series is served from memory
+        converter.record_history_source(RateSource::Ecb);
+        converter.reset_provenance();
+        converter.record_historical_date(date);
+        assert_eq!(
+            converter.provenance("EUR", 10.0, 9.0).source,
+            RateSource::Cache
+        );
+    }
+
+    #[test]
+    fn test_combine_provenance() {
+        let part = |target: &str, source, usd, converted| CurrencyProvenance {
+            target: target.to_string(),
+            rate: converted / usd,
+            rate_date: NaiveDate::from_ymd_opt(2025, 6, 6),
+            earliest_rate_date: None,
+            source,
+            rate_mode: RateMode::Latest,
+            original_total_usd: usd,
+            converted_total: converted,
+        };
+
+        let combined = CurrencyProvenance::combine(&[
+            part("EUR", RateSource::Ecb, 10.0, 9.0),
+            part("EUR", RateSource::Cache, 30.0, 27.0),
+        ])
+        .unwrap();
+        assert_eq!(combined.source, RateSource::Cache);
+        assert_eq!(combined.original_total_usd, 40.0);
+        assert_eq!(combined.converted_total, 36.0);
+        assert!((combined.rate - 0.9).abs() < 1e-9);
+
+        assert!(
+            CurrencyProvenance::combine(&[
+                part("EUR", RateSource::Ecb, 10.0, 9.0),
+                part("GBP", RateSource::Ecb, 10.0, 8.0),
+            ])
+            .is_none()
+        );
+        assert!(CurrencyProvenance::combine(&[]).is_none());
     }
 }
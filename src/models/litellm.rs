@@ -11,6 +11,7 @@ const LITELLM_PRICING_URL: &str =
     "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json";
 const CACHE_TTL_SECONDS: u64 = 3600; // 1 hour
 const PERSISTENT_CACHE_TTL_HOURS: i64 = 24; // 24 hours for file cache
+const CACHE_WRITE_INPUT_MULTIPLIER: f64 = 1.25; // Anthropic's 5-minute cache write rate
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LiteLLMModelData {
@@ -48,9 +49,56 @@ pub struct EnhancedModelPricing {
 pub enum PricingSource {
     LiteLLM,
     StaticFallback,
+    /// Cost was taken from the costUSD field embedded in the JSONL entry
+    Embedded,
+    /// Rates configured under `[pricing.overrides]`
+    Override,
+}
+
+impl std::fmt::Display for PricingSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            PricingSource::LiteLLM => "LiteLLM",
+            PricingSource::StaticFallback => "Static",
+            PricingSource::Embedded => "Embedded",
+            PricingSource::Override => "Override",
+        };
+        write!(f, "{label}")
+    }
 }
 
 impl EnhancedModelPricing {
+    /// Convert LiteLLM per-token costs into per-million-token pricing
+    pub fn from_litellm(model_data: &LiteLLMModelData) -> Self {
+        let input_cost = model_data.input_cost_per_token.unwrap_or(0.0) * 1_000_000.0;
+        let output_cost = model_data.output_cost_per_token.unwrap_or(0.0) * 1_000_000.0;
+
+        // Handle cache pricing with fallback logic
+        let cache_creation_cost = model_data
+            .cache_creation_input_token_cost
+            .map(|cost| cost * 1_000_000.0)
+            .unwrap_or_else(|| {
+                // Fallback: 25% of input cost for cache creation
+                input_cost * 0.25
+            });
+
+        let cache_read_cost = model_data
+            .cache_read_input_token_cost
+            .map(|cost| cost * 1_000_000.0)
+            .unwrap_or_else(|| {
+                // Fallback: 10% of input cost for cache read
+                input_cost * 0.10
+            });
+
+        Self::new(
+            input_cost,
+            output_cost,
+            cache_creation_cost,
+            cache_read_cost,
+            PricingSource::LiteLLM,
+        )
+    }
+
     pub fn new(
         input_cost: f64,
         output_cost: f64,
@@ -67,6 +115,19 @@ impl EnhancedModelPricing {
         }
     }
 
+    /// Cache write rate per million tokens for judging cache efficiency
+    ///
+    /// Static pricing has a single cache rate for reads and writes, and the
+    /// fallbacks guess 25% of input, so a creation rate below the input rate
+    /// is replaced by Anthropic's actual write rate of 1.25x input.
+    pub fn cache_write_cost_per_mtok(&self) -> f64 {
+        if self.cache_creation_cost_per_mtok < self.input_cost_per_mtok {
+            self.input_cost_per_mtok * CACHE_WRITE_INPUT_MULTIPLIER
+        } else {
+            self.cache_creation_cost_per_mtok
+        }
+    }
+
     /// Calculate cost for token usage in USD with granular cache pricing
     pub fn calculate_cost(
         &self,
@@ -133,6 +194,7 @@ impl PersistentCacheEntry {
 pub struct LiteLLMClient {
     client: Client,
     cache: Option<CacheEntry>,
+    last_refresh_attempt: Option<Instant>, // Refreshes are tried at most once per CACHE_TTL_SECONDS
 }
 
 impl LiteLLMClient {
@@ -140,11 +202,20 @@ impl LiteLLMClient {
         Self {
             client: Client::new(),
             cache: None,
+            last_refresh_attempt: None,
+        }
+    }
+
+    /// Create a client pre-populated with pricing data (no network or disk access)
+    pub fn with_data(data: LiteLLMPricingData) -> Self {
+        Self {
+            cache: Some(CacheEntry::new(data)),
+            ..Self::new()
         }
     }
 
     /// Get path to persistent LiteLLM cache file
-    fn get_persistent_cache_path() -> Result<PathBuf> {
+    pub fn get_persistent_cache_path() -> Result<PathBuf> {
         let home = dirs::home_dir().context("Failed to determine home directory")?;
         Ok(home
             .join(".config")
@@ -152,32 +223,16 @@ impl LiteLLMClient {
             .join("litellm_cache.json"))
     }
 
-    /// Load persistent cache from file
+    /// Read persistent cache from file regardless of its age
+    pub fn read_persistent_cache() -> Option<PersistentCacheEntry> {
+        let cache_path = Self::get_persistent_cache_path().ok()?;
+        let contents = fs::read_to_string(&cache_path).ok()?;
+        serde_json::from_str::<PersistentCacheEntry>(&contents).ok()
+    }
+
+    /// Load persistent cache from file, ignoring expired entries
     fn load_persistent_cache() -> Option<PersistentCacheEntry> {
-        match Self::get_persistent_cache_path() {
-            Ok(cache_path) => {
-                if cache_path.exists() {
-                    match fs::read_to_string(&cache_path) {
-                        Ok(contents) => {
-                            match serde_json::from_str::<PersistentCacheEntry>(&contents) {
-                                Ok(cache) => {
-                                    if !cache.is_expired() {
-                                        Some(cache)
-                                    } else {
-                                        None // Expired cache
-                                    }
-                                }
-                                Err(_) => None, // Invalid cache
-                            }
-                        }
-                        Err(_) => None, // Can't read file
-                    }
-                } else {
-                    None // No cache file
-                }
-            }
-            Err(_) => None, // Can't determine path
-        }
+        Self::read_persistent_cache().filter(|cache| !cache.is_expired())
     }
 
     /// Save persistent cache to file
@@ -203,21 +258,74 @@ impl LiteLLMClient {
 
     /// Fetch pricing data from LiteLLM repository with persistent caching
     pub async fn fetch_pricing_data(&mut self) -> Result<LiteLLMPricingData> {
+        self.ensure_pricing_data().await?;
+        self.cache
+            .as_ref()
+            .map(|cache| cache.data.clone())
+            .context("LiteLLM pricing data unavailable")
+    }
+
+    /// Make sure the in-memory cache holds pricing data, refreshing at most once per TTL
+    ///
+    /// Once the in-memory cache expires, the persistent cache and then the network are
+    /// tried. If both fail, expired data (in memory or on disk) keeps being used, and
+    /// the next refresh waits a full TTL so long-running modes don't stall on every
+    /// lookup while offline. Fails only when no LiteLLM data is available at all.
+    async fn ensure_pricing_data(&mut self) -> Result<()> {
         // Check in-memory cache first (fastest)
-        if let Some(ref cache) = self.cache {
-            if !cache.is_expired() {
-                return Ok(cache.data.clone());
-            }
+        if self.has_fresh_cache() {
+            return Ok(());
         }
 
+        if self
+            .last_refresh_attempt
+            .is_some_and(|attempt| attempt.elapsed() < Duration::from_secs(CACHE_TTL_SECONDS))
+        {
+            return match self.cache {
+                Some(_) => Ok(()),
+                None => Err(anyhow::anyhow!(
+                    "LiteLLM pricing unavailable (last fetch failed)"
+                )),
+            };
+        }
+        self.last_refresh_attempt = Some(Instant::now());
+
         // Check persistent cache (fast, avoids network)
         if let Some(persistent_cache) = Self::load_persistent_cache() {
             // Load into in-memory cache for subsequent calls
-            self.cache = Some(CacheEntry::new(persistent_cache.data.clone()));
-            return Ok(persistent_cache.data);
+            self.cache = Some(CacheEntry::new(persistent_cache.data));
+            return Ok(());
         }
 
-        // Fetch fresh data
+        let fetch_error = match self.fetch_remote_pricing_data().await {
+            Ok(pricing_data) => {
+                // Save to persistent cache (ignore errors to not fail the fetch)
+                let _ = Self::save_persistent_cache(&pricing_data);
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        // Stale pricing is closer to the truth than the static table
+        if self.cache.is_some() {
+            return Ok(());
+        }
+        if let Some(stale_cache) = Self::read_persistent_cache() {
+            self.cache = Some(CacheEntry::new(stale_cache.data));
+            return Ok(());
+        }
+        Err(fetch_error)
+    }
+
+    /// Fetch pricing data from LiteLLM, bypassing both caches, and rewrite the persistent cache
+    pub async fn refresh_pricing_data(&mut self) -> Result<LiteLLMPricingData> {
+        let pricing_data = self.fetch_remote_pricing_data().await?;
+        Self::save_persistent_cache(&pricing_data)?;
+        Ok(pricing_data)
+    }
+
+    /// Download and parse the LiteLLM price table, updating only the in-memory cache
+    async fn fetch_remote_pricing_data(&mut self) -> Result<LiteLLMPricingData> {
         let response = self
             .client
             .get(LITELLM_PRICING_URL)
@@ -272,51 +380,40 @@ impl LiteLLMClient {
         // Cache the data in memory
         self.cache = Some(CacheEntry::new(pricing_data.clone()));
 
-        // Save to persistent cache (ignore errors to not fail the fetch)
-        let _ = Self::save_persistent_cache(&pricing_data);
-
         Ok(pricing_data)
     }
 
+    /// Prepare pricing data for auto mode without blocking on the network for long
+    ///
+    /// Uses a fresh persistent cache when present, otherwise tries a fetch bounded by
+    /// `timeout`. If that fails, a stale persistent cache is still preferred over
+    /// static pricing. Returns an error only when no LiteLLM data is available at all.
+    pub async fn prefetch_with_fallback(&mut self, timeout: Duration) -> Result<()> {
+        match tokio::time::timeout(timeout, self.ensure_pricing_data()).await {
+            Ok(result) => result,
+            Err(_) => {
+                if let Some(stale_cache) = Self::read_persistent_cache() {
+                    self.cache = Some(CacheEntry::new(stale_cache.data));
+                    return Ok(());
+                }
+                Err(anyhow::anyhow!("LiteLLM pricing fetch timed out"))
+            }
+        }
+    }
+
     /// Get pricing for a specific model from LiteLLM data
     pub async fn get_model_pricing(
         &mut self,
         model_name: &str,
     ) -> Result<Option<EnhancedModelPricing>> {
-        let pricing_data = self.fetch_pricing_data().await?;
-
-        if let Some(model_data) = pricing_data.models.get(model_name) {
-            // Convert per-token costs to per-million-token costs
-            let input_cost = model_data.input_cost_per_token.unwrap_or(0.0) * 1_000_000.0;
-            let output_cost = model_data.output_cost_per_token.unwrap_or(0.0) * 1_000_000.0;
-
-            // Handle cache pricing with fallback logic
-            let cache_creation_cost = model_data
-                .cache_creation_input_token_cost
-                .map(|cost| cost * 1_000_000.0)
-                .unwrap_or_else(|| {
-                    // Fallback: 25% of input cost for cache creation
-                    input_cost * 0.25
-                });
-
-            let cache_read_cost = model_data
-                .cache_read_input_token_cost
-                .map(|cost| cost * 1_000_000.0)
-                .unwrap_or_else(|| {
-                    // Fallback: 10% of input cost for cache read
-                    input_cost * 0.10
-                });
-
-            Ok(Some(EnhancedModelPricing::new(
-                input_cost,
-                output_cost,
-                cache_creation_cost,
-                cache_read_cost,
-                PricingSource::LiteLLM,
-            )))
-        } else {
-            Ok(None)
-        }
+        // Populate the in-memory cache if needed, then look up without cloning the table
+        self.ensure_pricing_data().await?;
+
+        Ok(self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.data.models.get(model_name))
+            .map(EnhancedModelPricing::from_litellm))
     }
 
     /// Get enhanced pricing with static fallback
@@ -336,6 +433,17 @@ impl LiteLLMClient {
         }
     }
 
+    /// Names of models present in the in-memory cache, sorted
+    pub fn cached_model_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .cache
+            .as_ref()
+            .map(|cache| cache.data.models.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
     /// Check if cache is available and fresh
     pub fn has_fresh_cache(&self) -> bool {
         self.cache
@@ -406,6 +514,43 @@ mod tests {
         assert!(client.cache_age_seconds().is_none());
     }
 
+    #[tokio::test]
+    async fn test_failed_refresh_is_not_retried_within_ttl() {
+        let mut models = HashMap::new();
+        models.insert(
+            "test-model".to_string(),
+            LiteLLMModelData {
+                input_cost_per_token: Some(0.000002),
+                output_cost_per_token: Some(0.00001),
+                cache_creation_input_token_cost: None,
+                cache_read_input_token_cost: None,
+                max_tokens: None,
+                max_input_tokens: None,
+                max_output_tokens: None,
+            },
+        );
+        let mut client = LiteLLMClient::with_data(LiteLLMPricingData { models });
+        let expired = Duration::from_secs(CACHE_TTL_SECONDS + 1);
+        client.cache.as_mut().unwrap().timestamp = Instant::now() - expired;
+        let attempt = Instant::now();
+        client.last_refresh_attempt = Some(attempt);
+
+        // Expired data keeps serving until the next refresh is due
+        let pricing = client
+            .get_model_pricing("test-model")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!((pricing.input_cost_per_mtok - 2.0).abs() < 1e-9);
+        assert_eq!(client.last_refresh_attempt, Some(attempt));
+
+        // Without any data the lookup fails at once instead of fetching again
+        let mut client = LiteLLMClient::new();
+        client.last_refresh_attempt = Some(attempt);
+        assert!(client.get_model_pricing("test-model").await.is_err());
+        assert_eq!(client.last_refresh_attempt, Some(attempt));
+    }
+
     #[test]
     fn test_pricing_source_equality() {
         assert_eq!(PricingSource::LiteLLM, PricingSource::LiteLLM);
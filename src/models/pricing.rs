@@ -1,3 +1,4 @@
+use crate::config::PricingConfig;
 use crate::models::litellm::{EnhancedModelPricing, LiteLLMClient, PricingSource};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -36,11 +37,38 @@ impl ModelPricing {
     }
 }
 
+/// How the pricing manager sources its rates, mirroring `pricing.source` in config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PricingMode {
+    /// Embedded static pricing only
+    Static,
+    /// Always fetch LiteLLM pricing, warn and fall back to static on failure
+    Live,
+    /// Prefer cached LiteLLM pricing, try a short fetch, silently fall back to static
+    Auto,
+}
+
+impl PricingMode {
+    /// Parse a `pricing.source` config value; unknown values map to static
+    pub fn from_config_str(source: &str) -> Self {
+        match source {
+            "live" => PricingMode::Live,
+            "auto" => PricingMode::Auto,
+            _ => PricingMode::Static,
+        }
+    }
+}
+
+/// Upper bound on the network fetch in auto mode so commands stay responsive offline
+const AUTO_FETCH_TIMEOUT_SECS: u64 = 3;
+
 #[derive(Debug)]
 pub struct PricingManager {
     pricing_data: HashMap<String, ModelPricing>,
     litellm_client: Option<LiteLLMClient>,
     enable_live_pricing: bool,
+    mode: PricingMode,
+    overrides: HashMap<String, EnhancedModelPricing>,
 }
 
 impl PricingManager {
@@ -66,6 +94,8 @@ impl PricingManager {
             pricing_data,
             litellm_client: None,
             enable_live_pricing: false,
+            mode: PricingMode::Static,
+            overrides: HashMap::new(),
         }
     }
 
@@ -74,12 +104,115 @@ impl PricingManager {
         let mut manager = Self::new();
         manager.litellm_client = Some(LiteLLMClient::new());
         manager.enable_live_pricing = true;
+        manager.mode = PricingMode::Live;
         manager
     }
 
+    /// Create new pricing manager that uses LiteLLM pricing when it is cheaply available
+    pub fn with_auto_pricing() -> Self {
+        let mut manager = Self::with_live_pricing();
+        manager.mode = PricingMode::Auto;
+        manager
+    }
+
+    /// Create pricing manager for a `pricing.source` config value ("static", "live", "auto")
+    pub fn from_source(source: &str) -> Self {
+        match PricingMode::from_config_str(source) {
+            PricingMode::Static => Self::new(),
+            PricingMode::Live => Self::with_live_pricing(),
+            PricingMode::Auto => Self::with_auto_pricing(),
+        }
+    }
+
+    /// Create pricing manager from the `[pricing]` config section, including overrides
+    pub fn from_config(config: &PricingConfig) -> Self {
+        let mut manager = Self::from_source(&config.source);
+        for (model, rates) in &config.overrides {
+            manager.set_override(
+                model,
+                EnhancedModelPricing::new(
+                    rates.input_cost_per_mtok,
+                    rates.output_cost_per_mtok,
+                    rates
+                        .cache_creation_cost_per_mtok
+                        .unwrap_or(rates.input_cost_per_mtok * 0.25),
+                    rates
+                        .cache_read_cost_per_mtok
+                        .unwrap_or(rates.input_cost_per_mtok * 0.10),
+                    PricingSource::Override,
+                ),
+            );
+        }
+        manager
+    }
+
+    /// Override pricing for a model; takes precedence over live and static rates
+    pub fn set_override(&mut self, model_name: &str, pricing: EnhancedModelPricing) {
+        let pricing = EnhancedModelPricing {
+            source: PricingSource::Override,
+            ..pricing
+        };
+        self.overrides.insert(model_name.to_string(), pricing);
+    }
+
+    /// Get the override configured for a model, if any
+    pub fn get_override(&self, model_name: &str) -> Option<EnhancedModelPricing> {
+        self.overrides.get(model_name).cloned()
+    }
+
+    /// Get static pricing for a model as enhanced pricing (same rate for cache creation and read)
+    pub fn get_static_enhanced_pricing(&self, model_name: &str) -> Option<EnhancedModelPricing> {
+        self.get_pricing(model_name).map(|pricing| {
+            EnhancedModelPricing::new(
+                pricing.input_cost_per_mtok,
+                pricing.output_cost_per_mtok,
+                pricing.cache_cost_per_mtok,
+                pricing.cache_cost_per_mtok,
+                PricingSource::StaticFallback,
+            )
+        })
+    }
+
+    /// List models with static or override pricing, sorted by name
+    pub fn list_models(&self) -> Vec<String> {
+        let mut models: Vec<String> = self
+            .pricing_data
+            .keys()
+            .chain(self.overrides.keys())
+            .cloned()
+            .collect();
+        models.sort();
+        models.dedup();
+        models
+    }
+
+    /// Names of models available from the loaded LiteLLM data (empty when live pricing is off)
+    pub fn live_model_names(&self) -> Vec<String> {
+        match (&self.litellm_client, self.enable_live_pricing) {
+            (Some(client), true) => client.cached_model_names(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the configured pricing mode
+    pub fn mode(&self) -> PricingMode {
+        self.mode
+    }
+
     /// Pre-fetch and cache pricing data to avoid delays during calculations
-    /// Only fetches if explicitly using live pricing mode
+    /// Only fetches when live or auto pricing is enabled
     pub async fn initialize_live_pricing(&mut self) -> Result<(), anyhow::Error> {
+        if self.enable_live_pricing && self.mode == PricingMode::Auto {
+            if let Some(ref mut client) = self.litellm_client {
+                let timeout = std::time::Duration::from_secs(AUTO_FETCH_TIMEOUT_SECS);
+                if client.prefetch_with_fallback(timeout).await.is_err() {
+                    // No LiteLLM data reachable - auto mode quietly uses static pricing
+                    self.enable_live_pricing = false;
+                }
+            }
+            return Ok(());
+        }
+
         // Only fetch if live pricing is explicitly enabled
         // Default behavior should be fast offline mode
         if self.enable_live_pricing {
@@ -148,11 +281,20 @@ impl PricingManager {
     }
 
     /// Get enhanced pricing with live LiteLLM data if available
+    ///
+    /// Configured overrides win over everything else. Models missing from LiteLLM
+    /// fall back to the static table rather than a generic rate, and are reported
+    /// as `PricingSource::StaticFallback`.
     pub async fn get_enhanced_pricing(&mut self, model_name: &str) -> EnhancedModelPricing {
-        if self.enable_live_pricing {
-            if let Some(ref mut client) = self.litellm_client {
-                return client.get_pricing_with_fallback(model_name).await;
-            }
+        if let Some(pricing) = self.get_override(model_name) {
+            return pricing;
+        }
+
+        if self.enable_live_pricing
+            && let Some(ref mut client) = self.litellm_client
+            && let Ok(Some(pricing)) = client.get_model_pricing(model_name).await
+        {
+            return pricing;
         }
 
         // Fallback to static pricing
@@ -205,6 +347,8 @@ impl PricingManager {
             } else {
                 "Static fallback".to_string()
             }
+        } else if self.mode == PricingMode::Auto {
+            "Static (auto fallback)".to_string()
         } else {
             "Static".to_string()
         }
@@ -306,17 +450,61 @@ mod tests {
         );
     }
 
-    // TODO: Implement list_models method before enabling this test
-    // #[test]
-    // fn test_pricing_manager_list_models() {
-    //     let manager = PricingManager::new();
-    //     let models = manager.list_models().expect("Should list models");
-
-    //     assert!(models.len() >= 3);
-    //     assert!(models.contains(&"claude-sonnet-4-20250514".to_string()));
-    //     assert!(models.contains(&"claude-opus-4-20250514".to_string()));
-    //     assert!(models.contains(&"claude-haiku-3-5-20241022".to_string()));
-    // }
+    #[test]
+    fn test_pricing_manager_list_models() {
+        let manager = PricingManager::new();
+        let models = manager.list_models();
+
+        assert!(models.len() >= 3);
+        assert!(models.contains(&"claude-sonnet-4-20250514".to_string()));
+        assert!(models.contains(&"claude-opus-4-20250514".to_string()));
+        assert!(models.contains(&"claude-haiku-3-5-20241022".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_pricing_overrides_take_precedence() {
+        use crate::config::settings::PricingOverride;
+
+        let mut config = crate::config::Config::default().pricing;
+        config.source = "static".to_string();
+        config.overrides.insert(
+            "claude-sonnet-4-20250514".to_string(),
+            PricingOverride {
+                input_cost_per_mtok: 2.0,
+                output_cost_per_mtok: 10.0,
+                cache_creation_cost_per_mtok: None,
+                cache_read_cost_per_mtok: Some(0.5),
+            },
+        );
+        config.overrides.insert(
+            "my-custom-model".to_string(),
+            PricingOverride {
+                input_cost_per_mtok: 1.0,
+                output_cost_per_mtok: 2.0,
+                cache_creation_cost_per_mtok: None,
+                cache_read_cost_per_mtok: None,
+            },
+        );
+
+        let mut manager = PricingManager::from_config(&config);
+        assert!(
+            manager
+                .list_models()
+                .contains(&"my-custom-model".to_string())
+        );
+
+        let pricing = manager
+            .get_enhanced_pricing("claude-sonnet-4-20250514")
+            .await;
+        assert_eq!(pricing.source, PricingSource::Override);
+        assert!((pricing.input_cost_per_mtok - 2.0).abs() < 0.001);
+        assert!((pricing.cache_creation_cost_per_mtok - 0.5).abs() < 0.001); // 25% of input
+        assert!((pricing.cache_read_cost_per_mtok - 0.5).abs() < 0.001);
+
+        // Models without an override still use the static table
+        let opus = manager.get_enhanced_pricing("claude-opus-4-20250514").await;
+        assert_eq!(opus.source, PricingSource::StaticFallback);
+    }
 
     // TODO: Implement set_pricing and delete_pricing methods before enabling these tests
     // #[test]
@@ -405,6 +593,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pricing_manager_from_source() {
+        assert_eq!(
+            PricingManager::from_source("static").mode(),
+            PricingMode::Static
+        );
+        assert_eq!(
+            PricingManager::from_source("live").mode(),
+            PricingMode::Live
+        );
+        assert_eq!(
+            PricingManager::from_source("auto").mode(),
+            PricingMode::Auto
+        );
+        assert_eq!(
+            PricingManager::from_source("bogus").mode(),
+            PricingMode::Static
+        );
+
+        assert!(PricingManager::from_source("auto").is_live_pricing_enabled());
+        assert!(!PricingManager::from_source("static").is_live_pricing_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_auto_pricing_uses_litellm_data_with_static_fallback() {
+        use crate::models::litellm::{LiteLLMModelData, LiteLLMPricingData};
+
+        let mut models = HashMap::new();
+        models.insert(
+            "claude-opus-4-20250514".to_string(),
+            LiteLLMModelData {
+                input_cost_per_token: Some(0.000015),
+                output_cost_per_token: Some(0.000075),
+                cache_creation_input_token_cost: Some(0.00001875),
+                cache_read_input_token_cost: Some(0.0000015),
+                max_tokens: None,
+                max_input_tokens: None,
+                max_output_tokens: None,
+            },
+        );
+
+        let mut manager = PricingManager::with_auto_pricing();
+        manager.litellm_client = Some(LiteLLMClient::with_data(LiteLLMPricingData { models }));
+        manager.initialize_live_pricing().await.unwrap();
+        assert!(manager.is_live_pricing_enabled());
+
+        // Model present in LiteLLM data is priced live with granular cache rates
+        let opus = manager.get_enhanced_pricing("claude-opus-4-20250514").await;
+        assert_eq!(opus.source, PricingSource::LiteLLM);
+        assert!((opus.cache_creation_cost_per_mtok - 18.75).abs() < 0.001);
+
+        // Model missing from LiteLLM data falls back to the static table
+        let haiku = manager
+            .get_enhanced_pricing("claude-haiku-3-5-20241022")
+            .await;
+        assert_eq!(haiku.source, PricingSource::StaticFallback);
+        assert!((haiku.input_cost_per_mtok - 1.0).abs() < 0.001);
+    }
+
     // TODO: Implement set_pricing and delete_pricing methods before enabling this test
     // #[test]
     // fn test_pricing_manager_delete_pricing() {
@@ -0,0 +1,408 @@
+// Unicode bar charts and sparklines shown under tables with --chart
+use crate::analysis::DailyUsageList;
+use crate::analysis::usage::ProjectUsage;
+use crate::models::currency::format_currency;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+const EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// Segment fills for stacked bars: distinct glyphs without color, one color each with it
+const SEGMENT_GLYPHS: [char; 6] = ['█', '▓', '▒', '░', '▚', '▞'];
+const SEGMENT_COLORS: [&str; 6] = ["34", "35", "36", "33", "32", "31"];
+const MAX_LABEL_WIDTH: usize = 24;
+const MIN_BAR_WIDTH: usize = 10;
+/// Longest sparkline history, in days
+pub const SPARKLINE_DAYS: usize = 30;
+
+/// Width of the terminal in columns, or 80 when it can't be determined
+///
+/// `COLUMNS` wins over asking the terminal, so output can be sized explicitly.
+pub fn terminal_width() -> usize {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse::<usize>().ok())
+        .filter(|columns| *columns > 0)
+    {
+        return columns;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+        .filter(|columns| *columns > 0)
+        .unwrap_or(80)
+}
+
+fn paint(text: &str, color: &str, colored: bool) -> String {
+    if colored && !text.is_empty() {
+        format!("\x1b[{color}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+fn fit_label(label: &str) -> String {
+    if label.chars().count() > MAX_LABEL_WIDTH {
+        let cut: String = label.chars().take(MAX_LABEL_WIDTH - 1).collect();
+        format!("{cut}…")
+    } else {
+        label.to_string()
+    }
+}
+
+/// Horizontal bar of `value / max` over `width` cells, in eighth-cell steps
+pub fn bar(value: f64, max: f64, width: usize) -> String {
+    if max <= 0.0 || value <= 0.0 || width == 0 {
+        return String::new();
+    }
+    let eighths = ((value / max).min(1.0) * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    bar.push_str(EIGHTHS[eighths % 8]);
+    if bar.is_empty() {
+        // Keep non-zero values visible
+        bar.push_str(EIGHTHS[1]);
+    }
+    bar
+}
+
+/// One block character per value, scaled from zero to the series' maximum
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    if max <= 0.0 {
+        return SPARKS[0].to_string().repeat(values.len());
+    }
+    values
+        .iter()
+        .map(|value| {
+            let level = (value.max(0.0) / max * (SPARKS.len() - 1) as f64).round() as usize;
+            SPARKS[level.min(SPARKS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Bar split into one segment per part, in the order of `legend`
+fn stacked_bar(
+    parts: &BTreeMap<String, f64>,
+    legend: &[String],
+    width: usize,
+    colored: bool,
+) -> String {
+    let total: f64 = parts.values().sum();
+    if total <= 0.0 {
+        return String::new();
+    }
+    let mut output = String::new();
+    let mut used = 0;
+    let mut cumulative = 0.0;
+    for (index, name) in legend.iter().enumerate() {
+        let Some(value) = parts.get(name) else {
+            continue;
+        };
+        // Round cumulative widths so segments always add up to the full bar
+        cumulative += value;
+        let end = ((cumulative / total) * width as f64).round() as usize;
+        let cells = end.saturating_sub(used);
+        used = end;
+        let glyph = SEGMENT_GLYPHS[index % SEGMENT_GLYPHS.len()];
+        let segment = glyph.to_string().repeat(cells);
+        output.push_str(&paint(
+            &segment,
+            SEGMENT_COLORS[index % SEGMENT_COLORS.len()],
+            colored,
+        ));
+    }
+    output
+}
+
+/// Legend entries ordered by total share, largest first
+fn legend_order(totals: &BTreeMap<String, f64>) -> Vec<String> {
+    let mut names: Vec<(&String, f64)> = totals.iter().map(|(n, v)| (n, *v)).collect();
+    names.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    names.into_iter().map(|(name, _)| name.clone()).collect()
+}
+
+fn legend_line(totals: &BTreeMap<String, f64>, legend: &[String], colored: bool) -> String {
+    let total: f64 = totals.values().sum();
+    legend
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let glyph = SEGMENT_GLYPHS[index % SEGMENT_GLYPHS.len()].to_string();
+            let share = if total > 0.0 {
+                totals.get(name).copied().unwrap_or(0.0) / total * 100.0
+            } else {
+                0.0
+            };
+            format!(
+                "{} {} ({:.0}%)",
+                paint(
+                    &glyph,
+                    SEGMENT_COLORS[index % SEGMENT_COLORS.len()],
+                    colored
+                ),
+                name,
+                share
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Rows of `label  bar  value`, with bars sized to the terminal
+fn bar_rows(rows: &[(String, f64, String)], width: usize, colored: bool) -> Vec<String> {
+    let label_width = rows
+        .iter()
+        .map(|(label, _, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let value_width = rows
+        .iter()
+        .map(|(_, _, value)| value.chars().count())
+        .max()
+        .unwrap_or(0);
+    let bar_width = width
+        .saturating_sub(label_width + value_width + 4)
+        .max(MIN_BAR_WIDTH);
+    let max = rows.iter().map(|(_, value, _)| *value).fold(0.0, f64::max);
+
+    rows.iter()
+        .map(|(label, value, formatted)| {
+            let bar = bar(*value, max, bar_width);
+            let padding = bar_width - bar.chars().count();
+            format!(
+                "{label:<label_width$}  {}{}  {formatted:>value_width$}",
+                paint(&bar, "32", colored),
+                " ".repeat(padding)
+            )
+        })
+        .collect()
+}
+
+fn heading(title: &str, colored: bool) -> String {
+    paint(title, "1", colored)
+}
+
+/// Charts for the daily view: cost per day, model split per day, project sparklines
+pub fn daily_charts(
+    daily: &DailyUsageList,
+    currency: &str,
+    decimal_places: u8,
+    colored: bool,
+    width: usize,
+) -> String {
+    if daily.0.is_empty() {
+        return String::new();
+    }
+    let mut sections = Vec::new();
+
+    let cost_rows: Vec<(String, f64, String)> = daily
+        .0
+        .iter()
+        .map(|day| {
+            (
+                fit_label(&day.date),
+                day.total_cost_usd,
+                format_currency(day.total_cost_usd, currency, decimal_places),
+            )
+        })
+        .collect();
+    sections.push(
+        std::iter::once(heading("Daily cost", colored))
+            .chain(bar_rows(&cost_rows, width, colored))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+
+    // Model split, from USD shares so it is independent of currency conversion
+    let mut model_totals: BTreeMap<String, f64> = BTreeMap::new();
+    for day in &daily.0 {
+        for (model, cost) in &day.model_cost_usd {
+            *model_totals.entry(model.clone()).or_insert(0.0) += cost;
+        }
+    }
+    if model_totals.values().any(|cost| *cost > 0.0) {
+        let legend = legend_order(&model_totals);
+        let label_width = daily
+            .0
+            .iter()
+            .map(|day| fit_label(&day.date).chars().count())
+            .max()
+            .unwrap_or(0);
+        let bar_width = width.saturating_sub(label_width + 2).max(MIN_BAR_WIDTH);
+        let mut lines = vec![heading("Model split", colored)];
+        for day in &daily.0 {
+            lines.push(format!(
+                "{:<label_width$}  {}",
+                fit_label(&day.date),
+                stacked_bar(&day.model_cost_usd, &legend, bar_width, colored)
+            ));
+        }
+        lines.push(legend_line(&model_totals, &legend, colored));
+        sections.push(lines.join("\n"));
+    }
+
+    // One sparkline per project over the most recent days shown
+    let mut project_days: BTreeMap<String, BTreeMap<NaiveDate, f64>> = BTreeMap::new();
+    for day in &daily.0 {
+        let Some(date) = day.usage_date else {
+            continue;
+        };
+        for (project, cost) in &day.project_cost_usd {
+            *project_days
+                .entry(project.clone())
+                .or_default()
+                .entry(date)
+                .or_insert(0.0) += cost;
+        }
+    }
+    if let Some((days, series)) = recent_daily_series(&project_days) {
+        sections.push(sparkline_section(
+            &format!("Projects (last {days} days)"),
+            &series,
+            colored,
+        ));
+    }
+
+    sections.join("\n\n")
+}
+
+/// Charts for the timeframe views: cost per project, daily sparklines, model split
+pub fn project_charts(
+    usage: &[ProjectUsage],
+    currency: &str,
+    decimal_places: u8,
+    colored: bool,
+    width: usize,
+) -> String {
+    if usage.is_empty() {
+        return String::new();
+    }
+    let mut sections = Vec::new();
+
+    let mut projects: Vec<&ProjectUsage> = usage.iter().collect();
+    projects.sort_by(|a, b| b.total_cost_usd.total_cmp(&a.total_cost_usd));
+    let cost_rows: Vec<(String, f64, String)> = projects
+        .iter()
+        .map(|project| {
+            (
+                fit_label(&project.project_name),
+                project.total_cost_usd,
+                format_currency(project.total_cost_usd, currency, decimal_places),
+            )
+        })
+        .collect();
+    sections.push(
+        std::iter::once(heading("Cost by project", colored))
+            .chain(bar_rows(&cost_rows, width, colored))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+
+    // Daily sparklines over the period
+    let project_days: BTreeMap<String, BTreeMap<NaiveDate, f64>> = projects
+        .iter()
+        .map(|project| (project.project_name.clone(), project.daily_cost_usd.clone()))
+        .collect();
+    if let Some((days, series)) = recent_daily_series(&project_days) {
+        sections.push(sparkline_section(
+            &format!("Daily cost (last {days} days)"),
+            &series,
+            colored,
+        ));
+    }
+
+    let mut model_totals: BTreeMap<String, f64> = BTreeMap::new();
+    for project in usage {
+        for (model, model_usage) in &project.model_usage {
+            *model_totals.entry(model.clone()).or_insert(0.0) += model_usage.cost_usd;
+        }
+    }
+    if model_totals.values().any(|cost| *cost > 0.0) {
+        let legend = legend_order(&model_totals);
+        sections.push(format!(
+            "{}\n{}\n{}",
+            heading("Model split", colored),
+            stacked_bar(&model_totals, &legend, width.max(MIN_BAR_WIDTH), colored),
+            legend_line(&model_totals, &legend, colored)
+        ));
+    }
+
+    sections.join("\n\n")
+}
+
+/// Dense per-day series ending on the latest day, at most `SPARKLINE_DAYS` long
+///
+/// Days without usage count as zero. Returns `None` when there is less than two days of data.
+fn recent_daily_series(
+    per_name: &BTreeMap<String, BTreeMap<NaiveDate, f64>>,
+) -> Option<(usize, BTreeMap<String, Vec<f64>>)> {
+    let first_day = per_name
+        .values()
+        .filter_map(|days| days.keys().next())
+        .min()?;
+    let last_day = per_name
+        .values()
+        .filter_map(|days| days.keys().next_back())
+        .max()?;
+    let span = ((*last_day - *first_day).num_days() + 1) as usize;
+    let days = span.min(SPARKLINE_DAYS);
+    if days < 2 {
+        return None;
+    }
+    let start = *last_day - chrono::Duration::days(days as i64 - 1);
+    let series = per_name
+        .iter()
+        .map(|(name, costs)| {
+            let values = (0..days)
+                .map(|offset| {
+                    let date = start + chrono::Duration::days(offset as i64);
+                    costs.get(&date).copied().unwrap_or(0.0)
+                })
+                .collect();
+            (name.clone(), values)
+        })
+        .collect();
+    Some((days, series))
+}
+
+fn sparkline_section(title: &str, series: &BTreeMap<String, Vec<f64>>, colored: bool) -> String {
+    let label_width = series
+        .keys()
+        .map(|name| fit_label(name).chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![heading(title, colored)];
+    for (name, values) in series {
+        lines.push(format!(
+            "{:<label_width$}  {}",
+            fit_label(name),
+            paint(&sparkline(values), "36", colored)
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_and_sparkline_scaling() {
+        assert_eq!(bar(10.0, 10.0, 4), "████");
+        assert_eq!(bar(5.0, 10.0, 4), "██");
+        assert_eq!(bar(1.0, 10.0, 4), "▍");
+        assert_eq!(bar(0.0, 10.0, 4), "");
+        assert_eq!(bar(0.001, 10.0, 4), "▏");
+
+        assert_eq!(sparkline(&[0.0, 1.0, 7.0]), "▁▂█");
+        assert_eq!(sparkline(&[0.0, 0.0]), "▁▁");
+    }
+
+    #[test]
+    fn test_stacked_bar_fills_width() {
+        let parts = BTreeMap::from([("a".to_string(), 1.0), ("b".to_string(), 2.0)]);
+        let legend = legend_order(&parts);
+        assert_eq!(legend, vec!["b".to_string(), "a".to_string()]);
+        assert_eq!(stacked_bar(&parts, &legend, 9, false), "██████▓▓▓");
+    }
+}
@@ -1,2 +1,69 @@
 // CSV/JSON export
-// TODO: Implement in TASK-017
+use crate::output::locale::{NumberLocale, current_locale};
+
+/// Field delimiter for CSV in a locale
+///
+/// Locales that write decimals with a comma use semicolons, as spreadsheets there expect.
+pub fn csv_delimiter(locale: &NumberLocale) -> char {
+    if locale.decimal_separator == ',' {
+        ';'
+    } else {
+        ','
+    }
+}
+
+/// Quote a field if it contains the delimiter, a quote or a line break
+pub fn csv_field(value: &str, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format a number for CSV: no grouping, locale decimal separator
+pub fn csv_number(value: f64, decimal_places: usize, locale: &NumberLocale) -> String {
+    let formatted = format!("{value:.decimal_places$}");
+    if locale.decimal_separator == '.' {
+        formatted
+    } else {
+        formatted.replace('.', &locale.decimal_separator.to_string())
+    }
+}
+
+/// Render a header and rows as CSV using the configured locale's conventions
+pub fn to_csv(header: &[String], rows: &[Vec<String>]) -> String {
+    let delimiter = csv_delimiter(&current_locale());
+    std::iter::once(header)
+        .chain(rows.iter().map(Vec::as_slice))
+        .map(|record| {
+            record
+                .iter()
+                .map(|value| csv_field(value, delimiter))
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_quoting_and_locale_delimiter() {
+        let en = NumberLocale::from_tag("en-US").unwrap();
+        let de = NumberLocale::from_tag("de-DE").unwrap();
+        assert_eq!(csv_delimiter(&en), ',');
+        assert_eq!(csv_delimiter(&de), ';');
+
+        assert_eq!(csv_field("plain", ','), "plain");
+        assert_eq!(csv_field("a,b", ','), "\"a,b\"");
+        assert_eq!(csv_field("a,b", ';'), "a,b");
+        assert_eq!(csv_field("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+
+        assert_eq!(csv_number(1234.5, 2, &en), "1234.50");
+        assert_eq!(csv_number(1234.5, 2, &de), "1234,50");
+    }
+}
@@ -0,0 +1,150 @@
+// Locale-aware number formatting (decimal and thousands separators)
+use std::sync::OnceLock;
+
+/// Separators and currency symbol placement used when rendering numbers for a locale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberLocale {
+    pub decimal_separator: char,
+    pub group_separator: char,
+    pub symbol_prefix: bool, // "€10.45" vs "10,45 €"
+    pub symbol_space: bool,  // "€ 10,45" vs "€10,45"
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator: ',',
+            symbol_prefix: true,
+            symbol_space: false,
+        }
+    }
+}
+
+impl NumberLocale {
+    /// Parse a BCP 47 style tag such as "en-US", "pt-BR" or "de_DE"
+    ///
+    /// Returns `None` for languages without a known convention.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.replace('_', "-").to_lowercase();
+        let (language, region) = match tag.split_once('-') {
+            Some((language, region)) => (language, Some(region)),
+            None => (tag.as_str(), None),
+        };
+
+        let (decimal_separator, group_separator) = match (language, region) {
+            // Swiss German and Italian keep the point and group with apostrophes
+            ("de" | "it", Some("ch")) => ('.', '\''),
+            ("pt", Some("pt")) => (',', ' '),
+            ("en" | "ja" | "zh" | "ko" | "th" | "he" | "hi" | "ms" | "fil", _) => ('.', ','),
+            ("de" | "pt" | "es" | "it" | "nl" | "id" | "tr" | "da" | "ro" | "el" | "hr", _) => {
+                (',', '.')
+            }
+            (
+                "fr" | "sv" | "nb" | "no" | "fi" | "pl" | "cs" | "sk" | "hu" | "ru" | "uk" | "bg"
+                | "is",
+                _,
+            ) => (',', ' '),
+            _ => return None,
+        };
+
+        let (symbol_prefix, symbol_space) = match (language, region) {
+            ("de" | "it", Some("ch")) | ("pt", Some("br")) | ("nl", _) => (true, true),
+            ("en" | "ja" | "zh" | "ko" | "th" | "hi" | "ms" | "fil" | "id" | "tr", _) => {
+                (true, false)
+            }
+            _ => (false, true),
+        };
+
+        Some(Self {
+            decimal_separator,
+            group_separator,
+            symbol_prefix,
+            symbol_space,
+        })
+    }
+
+    /// Insert group separators into a string of ASCII digits
+    pub fn group_digits(&self, digits: &str) -> String {
+        let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, ch) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                result.push(self.group_separator);
+            }
+            result.push(ch);
+        }
+        result
+    }
+
+    /// Format a decimal with grouping and a fixed number of places
+    pub fn format_decimal(&self, value: f64, decimal_places: usize) -> String {
+        let formatted = format!("{:.*}", decimal_places, value.abs());
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        // Don't print "-0.00" for amounts that round to zero
+        let is_zero = formatted.chars().all(|c| c == '0' || c == '.');
+        let mut result = String::new();
+        if value < 0.0 && !is_zero {
+            result.push('-');
+        }
+        result.push_str(&self.group_digits(integer));
+        if let Some(fraction) = fraction {
+            result.push(self.decimal_separator);
+            result.push_str(fraction);
+        }
+        result
+    }
+}
+
+static LOCALE: OnceLock<NumberLocale> = OnceLock::new();
+
+/// Set the process-wide locale from the `output.locale` setting
+///
+/// Unknown tags fall back to en-US separators. Only the first call has effect.
+pub fn set_locale(tag: &str) {
+    let _ = LOCALE.set(NumberLocale::from_tag(tag).unwrap_or_default());
+}
+
+/// Locale used for table output (en-US until configured)
+pub fn current_locale() -> NumberLocale {
+    LOCALE.get().copied().unwrap_or_default()
+}
+
+/// Format a float with the configured locale's separators
+pub fn format_decimal(value: f64, decimal_places: usize) -> String {
+    current_locale().format_decimal(value, decimal_places)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_separators() {
+        let us = NumberLocale::from_tag("en-US").unwrap();
+        assert_eq!(us.format_decimal(1234567.891, 2), "1,234,567.89");
+
+        let br = NumberLocale::from_tag("pt-BR").unwrap();
+        assert_eq!(br.format_decimal(1234.5, 2), "1.234,50");
+        assert_eq!(br.group_digits("1000000"), "1.000.000");
+
+        let fr = NumberLocale::from_tag("fr_FR").unwrap();
+        assert_eq!(fr.format_decimal(-9876.5, 1), "-9 876,5");
+
+        let ch = NumberLocale::from_tag("de-CH").unwrap();
+        assert_eq!(ch.format_decimal(1234.6, 0), "1'235");
+
+        assert!(NumberLocale::from_tag("xx-YY").is_none());
+    }
+
+    #[test]
+    fn test_format_decimal_avoids_negative_zero() {
+        let us = NumberLocale::default();
+        assert_eq!(us.format_decimal(-0.001, 2), "0.00");
+        assert_eq!(us.format_decimal(-0.0, 2), "0.00");
+        assert_eq!(us.format_decimal(999.999, 2), "1,000.00");
+    }
+}
@@ -0,0 +1,262 @@
+// Prometheus text and OpenMetrics exposition of project usage
+use crate::analysis::usage::ProjectUsage;
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Exposition format for `--format` and `/metrics`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Prometheus,  // Text format 0.0.4, as the node_exporter textfile collector expects
+    OpenMetrics, // OpenMetrics 1.0.0
+}
+
+impl MetricsFormat {
+    pub fn from_arg(format: &str) -> Result<Self> {
+        match format {
+            "prometheus" => Ok(MetricsFormat::Prometheus),
+            "openmetrics" => Ok(MetricsFormat::OpenMetrics),
+            _ => anyhow::bail!(
+                "Invalid format: {format}. Must be 'table', 'json', 'prometheus' or 'openmetrics'"
+            ),
+        }
+    }
+
+    /// HTTP Content-Type for this format
+    pub fn content_type(self) -> &'static str {
+        match self {
+            MetricsFormat::Prometheus => "text/plain; version=0.0.4; charset=utf-8",
+            MetricsFormat::OpenMetrics => {
+                "application/openmetrics-text; version=1.0.0; charset=utf-8"
+            }
+        }
+    }
+}
+
+/// Escape a label value: backslash, double quote and line feed
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Write a family header and return the sample name
+///
+/// All-time totals are counters: OpenMetrics names the family without the
+/// `_total` suffix its samples carry. Totals for a time window can drop
+/// as the window moves, so they are gauges under a separate `ccost_window_` name.
+fn family_header(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    format: MetricsFormat,
+    windowed: bool,
+) -> String {
+    if windowed {
+        let gauge = name.replacen("ccost_", "ccost_window_", 1);
+        let _ = writeln!(
+            out,
+            "# HELP {gauge} {help} Within the selected time window."
+        );
+        let _ = writeln!(out, "# TYPE {gauge} gauge");
+        return gauge;
+    }
+    let family = match format {
+        MetricsFormat::Prometheus => format!("{name}_total"),
+        MetricsFormat::OpenMetrics => name.to_string(),
+    };
+    let _ = writeln!(out, "# HELP {family} {help}");
+    let _ = writeln!(out, "# TYPE {family} counter");
+    format!("{name}_total")
+}
+
+/// Render token, cost and message totals per project and model
+///
+/// Costs are always in USD. Samples are sorted by project and model so
+/// consecutive scrapes diff cleanly. `windowed` is set when `--since`,
+/// `--until` or a timeframe limits the data; the totals are then gauges,
+/// since only all-time totals are guaranteed never to decrease.
+pub fn to_metrics(usage: &[ProjectUsage], format: MetricsFormat, windowed: bool) -> String {
+    let mut rows: Vec<(&str, &crate::analysis::usage::ModelUsage)> = usage
+        .iter()
+        .flat_map(|project| {
+            project
+                .model_usage
+                .values()
+                .map(move |model| (project.project_name.as_str(), model))
+        })
+        .collect();
+    rows.sort_by(|a, b| (a.0, &a.1.model_name).cmp(&(b.0, &b.1.model_name)));
+
+    let mut out = String::new();
+    let tokens = family_header(
+        &mut out,
+        "ccost_tokens",
+        "Tokens processed by Claude, by kind.",
+        format,
+        windowed,
+    );
+    for (project, model) in &rows {
+        for (kind, count) in [
+            ("input", model.input_tokens),
+            ("output", model.output_tokens),
+            ("cache_creation", model.cache_creation_tokens),
+            ("cache_read", model.cache_read_tokens),
+        ] {
+            let _ = writeln!(
+                out,
+                "{tokens}{{project=\"{}\",model=\"{}\",kind=\"{kind}\"}} {count}",
+                label_value(project),
+                label_value(&model.model_name)
+            );
+        }
+    }
+
+    let cost = family_header(
+        &mut out,
+        "ccost_cost_usd",
+        "API-equivalent cost in US dollars.",
+        format,
+        windowed,
+    );
+    for (project, model) in &rows {
+        let _ = writeln!(
+            out,
+            "{cost}{{project=\"{}\",model=\"{}\"}} {}",
+            label_value(project),
+            label_value(&model.model_name),
+            model.cost_usd
+        );
+    }
+
+    let messages = family_header(
+        &mut out,
+        "ccost_messages",
+        "Deduplicated assistant messages.",
+        format,
+        windowed,
+    );
+    for (project, model) in &rows {
+        let _ = writeln!(
+            out,
+            "{messages}{{project=\"{}\",model=\"{}\"}} {}",
+            label_value(project),
+            label_value(&model.model_name),
+            model.message_count
+        );
+    }
+
+    if format == MetricsFormat::OpenMetrics {
+        out.push_str("# EOF\n");
+    }
+    out
+}
+
+/// Write metrics for the node_exporter textfile collector
+///
+/// The file is written next to `path` and renamed into place, so the collector
+/// never reads a partial file.
+pub fn write_textfile(path: &Path, contents: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Not a file path: {}", path.display()))?;
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    fs::write(&temp_path, contents)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    fs::rename(&temp_path, path).with_context(|| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to move metrics into place at {}", path.display())
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::usage::ModelUsage;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_metrics_formats() {
+        let model = ModelUsage {
+            model_name: "claude-sonnet-4".to_string(),
+            input_tokens: 100,
+            output_tokens: 20,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 5,
+            cost_usd: 0.25,
+            message_count: 3,
+            ..Default::default()
+        };
+        let usage = vec![ProjectUsage {
+            project_name: "my \"app\"".to_string(),
+            model_usage: HashMap::from([(model.model_name.clone(), model)]),
+            ..Default::default()
+        }];
+
+        let prometheus = to_metrics(&usage, MetricsFormat::Prometheus, false);
+        assert!(prometheus.contains("# TYPE ccost_tokens_total counter\n"));
+        assert!(prometheus.contains(
+            "ccost_tokens_total{project=\"my \\\"app\\\"\",model=\"claude-sonnet-4\",kind=\"cache_read\"} 5\n"
+        ));
+        assert!(prometheus.contains(
+            "ccost_cost_usd_total{project=\"my \\\"app\\\"\",model=\"claude-sonnet-4\"} 0.25\n"
+        ));
+        assert!(!prometheus.contains("# EOF"));
+
+        let openmetrics = to_metrics(&usage, MetricsFormat::OpenMetrics, false);
+        assert!(openmetrics.contains("# TYPE ccost_messages counter\n"));
+        assert!(openmetrics.ends_with("} 3\n# EOF\n"));
+    }
+
+    #[test]
+    fn test_windowed_metrics_are_gauges_with_escaped_labels() {
+        let model = ModelUsage {
+            model_name: "claude-opus-4".to_string(),
+            input_tokens: 7,
+            message_count: 1,
+            ..Default::default()
+        };
+        let usage = vec![ProjectUsage {
+            project_name: "C:\\work\nrepo".to_string(),
+            model_usage: HashMap::from([(model.model_name.clone(), model)]),
+            ..Default::default()
+        }];
+
+        let openmetrics = to_metrics(&usage, MetricsFormat::OpenMetrics, true);
+        assert!(openmetrics.contains("# TYPE ccost_window_tokens gauge\n"));
+        assert!(openmetrics.contains(
+            "ccost_window_tokens{project=\"C:\\\\work\\nrepo\",model=\"claude-opus-4\",kind=\"input\"} 7\n"
+        ));
+        assert!(!openmetrics.contains("_total"));
+        assert!(!openmetrics.contains("counter"));
+        // Exactly one EOF marker, as the last line
+        assert_eq!(openmetrics.matches("# EOF").count(), 1);
+        assert!(openmetrics.ends_with("\n# EOF\n"));
+
+        let prometheus = to_metrics(&usage, MetricsFormat::Prometheus, true);
+        assert!(prometheus.contains("# TYPE ccost_window_messages gauge\n"));
+        assert!(!prometheus.contains("# EOF"));
+    }
+
+    #[test]
+    fn test_write_textfile_replaces_atomically() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("ccost.prom");
+
+        write_textfile(&path, "first\n").unwrap();
+        write_textfile(&path, "second\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second\n");
+        // The temporary file was renamed away
+        let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        assert!(write_textfile(&temp_dir.path().join("missing/ccost.prom"), "x").is_err());
+    }
+}
@@ -1,5 +1,10 @@
 // Output module
+pub mod chart;
 pub mod export;
+pub mod locale;
+pub mod metrics;
+pub mod otlp;
 pub mod table;
+pub mod webhook;
 
 pub use table::{DailyUsageRow, OutputFormat};
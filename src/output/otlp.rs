@@ -0,0 +1,378 @@
+// OpenTelemetry export over OTLP/HTTP with JSON encoding
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+/// Log records per request; collectors reject very large bodies
+pub const MAX_LOG_RECORDS: usize = 1000;
+
+const SEVERITY_INFO: u8 = 9;
+const AGGREGATION_CUMULATIVE: u8 = 2;
+
+/// One deduplicated assistant message, priced in USD
+#[derive(Debug, Clone)]
+pub struct UsageEvent {
+    pub timestamp: DateTime<Utc>,
+    pub project: String,
+    pub model: String,
+    pub session_id: Option<String>,
+    pub message_id: Option<String>,
+    pub request_id: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost_usd: f64,
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({"key": key, "value": {"stringValue": value}})
+}
+
+// OTLP/JSON encodes 64-bit integers as strings
+fn int_attribute(key: &str, value: u64) -> Value {
+    json!({"key": key, "value": {"intValue": value.to_string()}})
+}
+
+fn double_attribute(key: &str, value: f64) -> Value {
+    json!({"key": key, "value": {"doubleValue": value}})
+}
+
+fn unix_nanos(time: DateTime<Utc>) -> String {
+    time.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+fn resource(service_name: &str) -> Value {
+    json!({
+        "attributes": [
+            string_attribute("service.name", service_name),
+            string_attribute("service.version", env!("CARGO_PKG_VERSION")),
+        ]
+    })
+}
+
+fn scope() -> Value {
+    json!({"name": "ccost", "version": env!("CARGO_PKG_VERSION")})
+}
+
+/// project, model and (when known) session.id
+fn series_attributes(project: &str, model: &str, session_id: Option<&str>) -> Vec<Value> {
+    let mut attributes = vec![
+        string_attribute("project", project),
+        string_attribute("model", model),
+    ];
+    if let Some(session_id) = session_id {
+        attributes.push(string_attribute("session.id", session_id));
+    }
+    attributes
+}
+
+/// Log export requests, one record per message, at most `MAX_LOG_RECORDS` each
+pub fn logs_payloads(events: &[UsageEvent], service_name: &str, now: DateTime<Utc>) -> Vec<Value> {
+    events
+        .chunks(MAX_LOG_RECORDS)
+        .map(|chunk| {
+            let records: Vec<Value> = chunk
+                .iter()
+                .map(|event| {
+                    let mut attributes = vec![string_attribute("event.name", "ccost.message")];
+                    attributes.extend(series_attributes(
+                        &event.project,
+                        &event.model,
+                        event.session_id.as_deref(),
+                    ));
+                    if let Some(message_id) = &event.message_id {
+                        attributes.push(string_attribute("message.id", message_id));
+                    }
+                    if let Some(request_id) = &event.request_id {
+                        attributes.push(string_attribute("request.id", request_id));
+                    }
+                    attributes.extend([
+                        int_attribute("tokens.input", event.input_tokens),
+                        int_attribute("tokens.output", event.output_tokens),
+                        int_attribute("tokens.cache_creation", event.cache_creation_tokens),
+                        int_attribute("tokens.cache_read", event.cache_read_tokens),
+                        double_attribute("cost.usd", event.cost_usd),
+                    ]);
+                    json!({
+                        "timeUnixNano": unix_nanos(event.timestamp),
+                        "observedTimeUnixNano": unix_nanos(now),
+                        "severityNumber": SEVERITY_INFO,
+                        "severityText": "INFO",
+                        "body": {"stringValue": format!(
+                            "{} {} ${:.4}", event.project, event.model, event.cost_usd
+                        )},
+                        "attributes": attributes,
+                    })
+                })
+                .collect();
+            json!({
+                "resourceLogs": [{
+                    "resource": resource(service_name),
+                    "scopeLogs": [{"scope": scope(), "logRecords": records}],
+                }]
+            })
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Series {
+    start: Option<DateTime<Utc>>,
+    tokens: [u64; 4], // input, output, cache_creation, cache_read
+    cost_usd: f64,
+    messages: u64,
+}
+
+/// Cumulative sums per project, model and session
+///
+/// Pass every message, not just a time window: each series starts at its
+/// first message ever, so overlapping or repeated exports share one start
+/// time and their values never go down.
+pub fn metrics_payload(events: &[UsageEvent], service_name: &str, now: DateTime<Utc>) -> Value {
+    let mut series: BTreeMap<(&str, &str, Option<&str>), Series> = BTreeMap::new();
+    for event in events {
+        let entry = series
+            .entry((&event.project, &event.model, event.session_id.as_deref()))
+            .or_default();
+        entry.start = Some(
+            entry
+                .start
+                .map_or(event.timestamp, |s| s.min(event.timestamp)),
+        );
+        entry.tokens[0] += event.input_tokens;
+        entry.tokens[1] += event.output_tokens;
+        entry.tokens[2] += event.cache_creation_tokens;
+        entry.tokens[3] += event.cache_read_tokens;
+        entry.cost_usd += event.cost_usd;
+        entry.messages += 1;
+    }
+
+    let mut token_points = Vec::new();
+    let mut cost_points = Vec::new();
+    let mut message_points = Vec::new();
+    for ((project, model, session_id), totals) in &series {
+        let attributes = series_attributes(project, model, *session_id);
+        let start = unix_nanos(totals.start.unwrap_or(now));
+        let time = unix_nanos(now);
+        for (kind, tokens) in ["input", "output", "cache_creation", "cache_read"]
+            .into_iter()
+            .zip(totals.tokens)
+        {
+            let mut attributes = attributes.clone();
+            attributes.push(string_attribute("kind", kind));
+            token_points.push(json!({
+                "attributes": attributes,
+                "startTimeUnixNano": start,
+                "timeUnixNano": time,
+                "asInt": tokens.to_string(),
+            }));
+        }
+        cost_points.push(json!({
+            "attributes": attributes,
+            "startTimeUnixNano": start,
+            "timeUnixNano": time,
+            "asDouble": totals.cost_usd,
+        }));
+        message_points.push(json!({
+            "attributes": attributes,
+            "startTimeUnixNano": start,
+            "timeUnixNano": time,
+            "asInt": totals.messages.to_string(),
+        }));
+    }
+
+    let sum = |name: &str, unit: &str, description: &str, points: Vec<Value>| {
+        json!({
+            "name": name,
+            "unit": unit,
+            "description": description,
+            "sum": {
+                "aggregationTemporality": AGGREGATION_CUMULATIVE,
+                "isMonotonic": true,
+                "dataPoints": points,
+            },
+        })
+    };
+    json!({
+        "resourceMetrics": [{
+            "resource": resource(service_name),
+            "scopeMetrics": [{
+                "scope": scope(),
+                "metrics": [
+                    sum("ccost.tokens", "{token}", "Tokens processed by Claude, by kind", token_points),
+                    sum("ccost.cost", "USD", "API-equivalent cost in US dollars", cost_points),
+                    sum("ccost.messages", "{message}", "Deduplicated assistant messages", message_points),
+                ],
+            }],
+        }]
+    })
+}
+
+/// Posts OTLP/JSON requests to a collector
+pub struct OtlpExporter {
+    client: reqwest::Client,
+    endpoint: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl OtlpExporter {
+    /// `endpoint` is the collector base URL; `/v1/logs` and `/v1/metrics` are appended
+    pub fn new(endpoint: &str, headers: &BTreeMap<String, String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("ccost/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            headers: headers.clone(),
+        })
+    }
+
+    pub async fn export_logs(&self, payload: &Value) -> Result<()> {
+        self.post("v1/logs", payload).await
+    }
+
+    pub async fn export_metrics(&self, payload: &Value) -> Result<()> {
+        self.post("v1/metrics", payload).await
+    }
+
+    async fn post(&self, path: &str, payload: &Value) -> Result<()> {
+        let url = format!("{}/{}", self.endpoint, path);
+        let mut request = self.client.post(&url).json(payload);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach OTLP endpoint {url}"))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("OTLP endpoint {} returned {}: {}", url, status, body.trim());
+        }
+        Ok(())
+    }
+}
+
+/// Number of data points `metrics_payload` produces for these events
+pub fn metric_point_count(events: &[UsageEvent]) -> usize {
+    let series: HashSet<(&str, &str, Option<&str>)> = events
+        .iter()
+        .map(|e| {
+            (
+                e.project.as_str(),
+                e.model.as_str(),
+                e.session_id.as_deref(),
+            )
+        })
+        .collect();
+    series.len() * 6 // four token kinds, cost and messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(session: &str, minute: u32, cost_usd: f64) -> UsageEvent {
+        UsageEvent {
+            timestamp: Utc.with_ymd_and_hms(2025, 6, 1, 10, minute, 0).unwrap(),
+            project: "app".to_string(),
+            model: "claude-sonnet-4".to_string(),
+            session_id: Some(session.to_string()),
+            message_id: Some(format!("msg-{minute}")),
+            request_id: None,
+            input_tokens: 100,
+            output_tokens: 10,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 1000,
+            cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_otlp_payloads() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let events = vec![
+            event("s1", 5, 0.5),
+            event("s1", 1, 0.25),
+            event("s2", 9, 1.0),
+        ];
+
+        let logs = logs_payloads(&events, "ccost", now);
+        assert_eq!(logs.len(), 1);
+        let records = &logs[0]["resourceLogs"][0]["scopeLogs"][0]["logRecords"];
+        assert_eq!(records.as_array().unwrap().len(), 3);
+        assert_eq!(records[0]["timeUnixNano"], "1748772300000000000");
+        assert!(
+            records[0]["attributes"]
+                .as_array()
+                .unwrap()
+                .contains(&int_attribute("tokens.cache_read", 1000))
+        );
+
+        let metrics = metrics_payload(&events, "ccost", now);
+        let metrics = &metrics["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+        let cost = &metrics[1]["sum"]["dataPoints"];
+        assert_eq!(metrics[1]["name"], "ccost.cost");
+        assert_eq!(cost.as_array().unwrap().len(), 2);
+        assert_eq!(cost[0]["asDouble"], 0.75);
+        assert_eq!(cost[0]["startTimeUnixNano"], "1748772060000000000");
+        assert_eq!(metrics[2]["sum"]["dataPoints"][0]["asInt"], "2");
+        assert_eq!(metric_point_count(&events), 12);
+    }
+
+    #[test]
+    fn test_overlapping_exports_share_cumulative_start() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut first = vec![event("s1", 1, 0.25), event("s1", 5, 0.5)];
+        first[1].request_id = Some("req-5".to_string());
+        let mut second = first.clone();
+        second.push(event("s1", 30, 1.0));
+
+        let cost = |events: &[UsageEvent]| {
+            let metrics = metrics_payload(events, "ccost", now);
+            metrics["resourceMetrics"][0]["scopeMetrics"][0]["metrics"][1]["sum"].clone()
+        };
+        let (before, after) = (cost(&first), cost(&second));
+        assert_eq!(after["aggregationTemporality"], AGGREGATION_CUMULATIVE);
+        assert_eq!(after["isMonotonic"], true);
+        assert_eq!(
+            before["dataPoints"][0]["startTimeUnixNano"],
+            after["dataPoints"][0]["startTimeUnixNano"]
+        );
+        assert_eq!(
+            after["dataPoints"][0]["startTimeUnixNano"],
+            "1748772060000000000"
+        );
+        assert_eq!(after["dataPoints"][0]["timeUnixNano"], unix_nanos(now));
+        assert_eq!(before["dataPoints"][0]["asDouble"], 0.75);
+        assert_eq!(after["dataPoints"][0]["asDouble"], 1.75);
+
+        let logs = logs_payloads(&first[1..], "ccost", now);
+        let attributes = logs[0]["resourceLogs"][0]["scopeLogs"][0]["logRecords"][0]["attributes"]
+            .as_array()
+            .unwrap()
+            .clone();
+        for expected in [
+            string_attribute("event.name", "ccost.message"),
+            string_attribute("project", "app"),
+            string_attribute("model", "claude-sonnet-4"),
+            string_attribute("session.id", "s1"),
+            string_attribute("message.id", "msg-5"),
+            string_attribute("request.id", "req-5"),
+            int_attribute("tokens.input", 100),
+            int_attribute("tokens.output", 10),
+            double_attribute("cost.usd", 0.5),
+        ] {
+            assert!(attributes.contains(&expected), "missing {expected}");
+        }
+    }
+}
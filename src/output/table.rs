@@ -1,5 +1,6 @@
 use crate::analysis::projects::ProjectSummary;
 use crate::analysis::usage::{ModelUsage, ProjectUsage};
+use crate::models::currency::CurrencyProvenance;
 use serde::Serialize;
 use tabled::{
     Table, Tabled,
@@ -18,6 +19,57 @@ pub trait OutputFormat {
         decimal_places: u8,
         colored: bool,
     ) -> String;
+
+    /// JSON payload, wrapped with currency provenance when `envelope` is set
+    ///
+    /// `original_usd` is the same payload before conversion; the envelope
+    /// carries it as `data_usd` so each amount's USD original is available.
+    /// Without `envelope` the payload keeps its plain shape.
+    fn to_json_with_currency(
+        &self,
+        currency: &CurrencyProvenance,
+        original_usd: &Self,
+        envelope: bool,
+    ) -> Result<String, serde_json::Error>
+    where
+        Self: Serialize + Sized,
+    {
+        if !envelope {
+            return self.to_json();
+        }
+        CurrencyEnvelope::new(Some(currency), self, Some(original_usd)).to_json()
+    }
+}
+
+/// JSON report wrapped with how its amounts were converted
+///
+/// `currency` is null when the payload mixes currencies (each item then
+/// carries its own provenance). `data_usd` is omitted for USD output.
+#[derive(Serialize)]
+pub struct CurrencyEnvelope<'a, T: Serialize> {
+    pub currency: Option<&'a CurrencyProvenance>,
+    pub data: &'a T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_usd: Option<&'a T>,
+}
+
+impl<'a, T: Serialize> CurrencyEnvelope<'a, T> {
+    pub fn new(
+        currency: Option<&'a CurrencyProvenance>,
+        data: &'a T,
+        original_usd: Option<&'a T>,
+    ) -> Self {
+        let converted = currency.is_some_and(|currency| currency.target != "USD");
+        Self {
+            currency,
+            data,
+            data_usd: original_usd.filter(|_| converted),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
 }
 
 /// Row for project usage summary table
@@ -94,6 +146,46 @@ pub struct DailyUsageRow {
     pub total_cost: String,
 }
 
+/// Row for what-if re-pricing table
+#[derive(Tabled, Serialize, Debug)]
+pub struct WhatIfRow {
+    #[tabled(rename = "Project")]
+    pub project: String,
+    #[tabled(rename = "Model")]
+    pub model: String,
+    #[tabled(rename = "Messages")]
+    pub messages: String,
+    #[tabled(rename = "Original Cost")]
+    pub original_cost: String,
+    #[tabled(rename = "What-if Cost")]
+    pub whatif_cost: String,
+    #[tabled(rename = "Difference")]
+    pub difference: String,
+    #[tabled(rename = "Change")]
+    pub change: String,
+}
+
+/// Row for budget status table
+#[derive(Tabled, Serialize, Debug)]
+pub struct BudgetRow {
+    #[tabled(rename = "Budget")]
+    pub budget: String,
+    #[tabled(rename = "Scope")]
+    pub scope: String,
+    #[tabled(rename = "Period")]
+    pub period: String,
+    #[tabled(rename = "Spent")]
+    pub spent: String,
+    #[tabled(rename = "Limit")]
+    pub limit: String,
+    #[tabled(rename = "Used")]
+    pub used: String,
+    #[tabled(rename = "Projected")]
+    pub projected: String,
+    #[tabled(rename = "Status")]
+    pub status: String,
+}
+
 impl ProjectUsageRow {
     pub fn from_project_usage_with_currency(
         usage: &ProjectUsage,
@@ -328,22 +420,7 @@ impl OutputFormat for Vec<ProjectSummary> {
 
 /// Format a number with commas for thousands separator
 pub fn format_number(n: u64) -> String {
-    if n == 0 {
-        return "0".to_string();
-    }
-
-    let mut result = String::new();
-    let s = n.to_string();
-    let chars: Vec<char> = s.chars().collect();
-
-    for (i, ch) in chars.iter().enumerate() {
-        if i > 0 && (chars.len() - i) % 3 == 0 {
-            result.push(',');
-        }
-        result.push(*ch);
-    }
-
-    result
+    crate::output::locale::current_locale().group_digits(&n.to_string())
 }
 
 /// Table type enum for proper column coloring
@@ -354,6 +431,18 @@ pub enum TableType {
     ProjectSummary,
     DailyUsage,
     Conversations,
+    Pricing,
+    WhatIf,
+    PlanHistory,
+    PlanSummary,
+    Budget,
+    Compare,
+    Anomalies,
+    Cache,
+    Optimization,
+    Heatmap,
+    Commits,
+    Branches,
 }
 
 /// Strip ANSI escape codes from a string to get its visual length
@@ -364,11 +453,20 @@ fn strip_ansi_codes(s: &str) -> String {
 }
 
 /// Apply modern table styling with optional column colors
-pub fn apply_table_style_with_color(
-    mut table: Table,
+pub fn apply_table_style_with_color(table: Table, colored: bool, table_type: TableType) -> String {
+    style_table(table, colored, table_type, true)
+}
+
+/// Apply the same styling to a table whose last row is data rather than a totals row
+pub fn apply_list_table_style_with_color(
+    table: Table,
     colored: bool,
     table_type: TableType,
 ) -> String {
+    style_table(table, colored, table_type, false)
+}
+
+fn style_table(mut table: Table, colored: bool, table_type: TableType, has_totals: bool) -> String {
     // Start with no borders
     let style = Style::blank();
 
@@ -430,20 +528,128 @@ pub fn apply_table_style_with_color(
                 table.modify(Columns::single(6), Color::FG_MAGENTA); // Outliers
                 table.modify(Columns::single(7), Color::FG_CYAN); // Duration
             }
+            TableType::Pricing => {
+                // Model, Source, Input, Output, Cache Write, Cache Read
+                table.modify(Columns::single(1), Color::FG_CYAN); // Source
+                table.modify(Columns::single(2), Color::FG_BLUE); // Input
+                table.modify(Columns::single(3), Color::FG_BLUE); // Output
+                table.modify(Columns::single(4), Color::FG_GREEN); // Cache Write
+                table.modify(Columns::single(5), Color::FG_GREEN); // Cache Read
+            }
+            TableType::WhatIf => {
+                // Project, Model, Messages, Original Cost, What-if Cost, Difference, Change
+                table.modify(Columns::single(1), Color::FG_CYAN); // Model
+                table.modify(Columns::single(2), Color::FG_YELLOW); // Messages
+                table.modify(Columns::single(3), Color::FG_RED); // Original Cost
+                table.modify(Columns::single(4), Color::FG_MAGENTA); // What-if Cost
+                table.modify(Columns::single(5), Color::FG_GREEN); // Difference
+            }
+            TableType::PlanHistory => {
+                // Month, Messages, API Cost, then one savings column per plan
+                table.modify(Columns::single(1), Color::FG_YELLOW); // Messages
+                table.modify(Columns::single(2), Color::FG_RED); // API Cost
+                table.modify(Columns::new(3..), Color::FG_GREEN); // Plan savings
+            }
+            TableType::PlanSummary => {
+                // Plan, Price/Month, Avg API Cost, Break-even, Months Ahead, Avg Savings
+                table.modify(Columns::single(1), Color::FG_BLUE); // Price/Month
+                table.modify(Columns::single(2), Color::FG_RED); // Avg API Cost
+                table.modify(Columns::single(3), Color::FG_MAGENTA); // Break-even
+                table.modify(Columns::single(4), Color::FG_YELLOW); // Months Ahead
+                table.modify(Columns::single(5), Color::FG_GREEN); // Avg Savings
+            }
+            TableType::Budget => {
+                // Budget, Scope, Period, Spent, Limit, Used, Projected, Status
+                table.modify(Columns::single(1), Color::FG_CYAN); // Scope
+                table.modify(Columns::single(3), Color::FG_RED); // Spent
+                table.modify(Columns::single(4), Color::FG_BLUE); // Limit
+                table.modify(Columns::single(5), Color::FG_YELLOW); // Used
+                table.modify(Columns::single(6), Color::FG_MAGENTA); // Projected
+            }
+            TableType::Compare => {
+                // Project/Model, Tokens, Δ Tokens, Messages, Δ Messages, Cost, Δ Cost
+                table.modify(Columns::single(1), Color::FG_BLUE); // Tokens
+                table.modify(Columns::single(2), Color::FG_BLUE); // Δ Tokens
+                table.modify(Columns::single(3), Color::FG_YELLOW); // Messages
+                table.modify(Columns::single(4), Color::FG_YELLOW); // Δ Messages
+                table.modify(Columns::single(5), Color::FG_RED); // Cost
+                table.modify(Columns::single(6), Color::FG_GREEN); // Δ Cost
+            }
+            TableType::Anomalies => {
+                // Kind, Subject, Project, Date, Cost, Typical, Score
+                table.modify(Columns::single(0), Color::FG_CYAN); // Kind
+                table.modify(Columns::single(3), Color::FG_BLUE); // Date
+                table.modify(Columns::single(4), Color::FG_RED); // Cost
+                table.modify(Columns::single(5), Color::FG_GREEN); // Typical
+                table.modify(Columns::single(6), Color::FG_MAGENTA); // Score
+            }
+            TableType::Cache => {
+                // Group, Cache Writes, Cache Reads, Read/Write, Hit Rate, Read Savings,
+                // Write Premium, Unread Writes, Net Savings
+                table.modify(Columns::single(1), Color::FG_CYAN); // Cache Writes
+                table.modify(Columns::single(2), Color::FG_BLUE); // Cache Reads
+                table.modify(Columns::single(3), Color::FG_MAGENTA); // Read/Write
+                table.modify(Columns::single(4), Color::FG_YELLOW); // Hit Rate
+                table.modify(Columns::single(5), Color::FG_GREEN); // Read Savings
+                table.modify(Columns::single(6), Color::FG_RED); // Write Premium
+                table.modify(Columns::single(7), Color::FG_RED); // Unread Writes
+                table.modify(Columns::single(8), Color::FG_GREEN); // Net Savings
+            }
+            TableType::Optimization => {
+                // Kind, Project, Subject, Messages, Cost, Est. Saving
+                table.modify(Columns::single(0), Color::FG_CYAN); // Kind
+                table.modify(Columns::single(3), Color::FG_YELLOW); // Messages
+                table.modify(Columns::single(4), Color::FG_RED); // Cost
+                table.modify(Columns::single(5), Color::FG_GREEN); // Est. Saving
+            }
+            TableType::Heatmap => {
+                // Day, 24 hour columns, Total
+                table.modify(Columns::first(), Color::FG_CYAN); // Day
+                table.modify(Columns::new(1..25), Color::FG_YELLOW); // Hours
+                table.modify(Columns::last(), Color::FG_RED); // Total
+            }
+            TableType::Commits => {
+                // Commit/Author/Branch, ..., Messages, Cost
+                table.modify(Columns::first(), Color::FG_CYAN); // Commit, author or branch
+                table.modify(Columns::last(), Color::FG_RED); // Cost
+            }
+            TableType::Branches => {
+                // Project, Branch, First Used, Last Used, Sessions, Messages, Input Tokens,
+                // Output Tokens, Cost
+                table.modify(Columns::single(1), Color::FG_CYAN); // Branch
+                table.modify(Columns::single(4), Color::FG_YELLOW); // Sessions
+                table.modify(Columns::single(5), Color::FG_YELLOW); // Messages
+                table.modify(Columns::single(6), Color::FG_BLUE); // Input Tokens
+                table.modify(Columns::single(7), Color::FG_BLUE); // Output Tokens
+                table.modify(Columns::last(), Color::FG_RED); // Cost
+            }
         }
     } else {
         // Make headers bold and white (default non-colored mode)
         table.modify(Rows::first(), Color::FG_WHITE | Color::BOLD);
 
         // Always apply red to cost column (last column) even in non-colored mode
-        table.modify(Columns::last().not(Rows::first()), Color::FG_RED);
+        if !matches!(
+            table_type,
+            TableType::Pricing
+                | TableType::WhatIf
+                | TableType::PlanHistory
+                | TableType::PlanSummary
+                | TableType::Budget
+                | TableType::Compare
+                | TableType::Anomalies
+                | TableType::Cache
+                | TableType::Optimization
+        ) {
+            table.modify(Columns::last().not(Rows::first()), Color::FG_RED);
+        }
     }
 
     let mut result = table.to_string();
 
     // Add horizontal lines manually with proper Unicode characters
     let lines: Vec<&str> = result.lines().collect();
-    if lines.len() > 2 {
+    if lines.len() > 2 || (!has_totals && lines.len() > 1) {
         // Calculate the visual width by stripping ANSI codes from the first line
         let line_width = strip_ansi_codes(lines[0]).len();
         let separator = "─".repeat(line_width); // Use Unicode box-drawing character
@@ -456,20 +662,99 @@ pub fn apply_table_style_with_color(
         new_result.push_str(&separator);
         new_result.push('\n');
 
-        // Add all data rows except the last
-        for line in lines.iter().take(lines.len() - 1).skip(1) {
-            new_result.push_str(line);
+        if has_totals {
+            // Add all data rows except the last
+            for line in lines.iter().take(lines.len() - 1).skip(1) {
+                new_result.push_str(line);
+                new_result.push('\n');
+            }
+
+            // Add separator before totals
+            new_result.push_str(&separator);
             new_result.push('\n');
+            // Add totals row
+            new_result.push_str(lines[lines.len() - 1]);
+        } else {
+            new_result.push_str(&lines[1..].join("\n"));
         }
 
-        // Add separator before totals
-        new_result.push_str(&separator);
-        new_result.push('\n');
-        // Add totals row
-        new_result.push_str(lines[lines.len() - 1]);
-
         result = new_result;
     }
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::currency::{RateMode, RateSource};
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+
+    fn model(cost_usd: f64) -> ModelUsage {
+        ModelUsage {
+            model_name: "claude-sonnet-4".to_string(),
+            input_tokens: 1000,
+            output_tokens: 100,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            cost_usd,
+            message_count: 1,
+            daily_cost_usd: BTreeMap::new(),
+        }
+    }
+
+    fn provenance(target: &str, rate: f64) -> CurrencyProvenance {
+        CurrencyProvenance {
+            target: target.to_string(),
+            rate,
+            rate_date: NaiveDate::from_ymd_opt(2025, 6, 6),
+            earliest_rate_date: None,
+            source: RateSource::Cache,
+            rate_mode: RateMode::Latest,
+            original_total_usd: 10.0,
+            converted_total: 10.0 * rate,
+        }
+    }
+
+    #[test]
+    fn test_currency_envelope_json_shape() {
+        let usd = vec![model(10.0)];
+        let eur = vec![model(9.0)];
+
+        // Legacy shape unless --currency-provenance is given
+        let currency = provenance("EUR", 0.9);
+        assert_eq!(
+            eur.to_json_with_currency(&currency, &usd, false).unwrap(),
+            eur.to_json().unwrap()
+        );
+
+        let json = eur.to_json_with_currency(&currency, &usd, true).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys, vec!["currency", "data", "data_usd"]);
+        assert_eq!(value["currency"]["target"], "EUR");
+        assert_eq!(value["currency"]["source"], "cache");
+        assert_eq!(value["data"][0]["cost_usd"], 9.0);
+        assert_eq!(value["data_usd"][0]["cost_usd"], 10.0);
+
+        // USD output has no separate originals; mixed currencies have no single block
+        let currency = provenance("USD", 1.0);
+        let value: serde_json::Value = serde_json::from_str(
+            &CurrencyEnvelope::new(Some(&currency), &usd, Some(&usd))
+                .to_json()
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(value.get("data_usd").is_none());
+        let value: serde_json::Value =
+            serde_json::from_str(&CurrencyEnvelope::new(None, &usd, None).to_json().unwrap())
+                .unwrap();
+        assert!(value["currency"].is_null());
+    }
+}
@@ -0,0 +1,170 @@
+// Webhook payloads for alerts (generic JSON or Slack) and delivery with retries
+use crate::analysis::alerts::{Alert, Severity};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// First retry delay; doubled after each failed attempt
+const RETRY_BASE_DELAY_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    Generic, // The alert as a JSON object
+    Slack,   // Incoming-webhook message with a `text` field
+}
+
+impl WebhookFormat {
+    pub fn from_config_str(format: &str) -> Result<Self> {
+        match format {
+            "generic" => Ok(WebhookFormat::Generic),
+            "slack" => Ok(WebhookFormat::Slack),
+            _ => anyhow::bail!("Invalid webhook format: {format}. Must be 'generic' or 'slack'"),
+        }
+    }
+
+    /// Request body for one alert
+    pub fn payload(&self, alert: &Alert, detected_at: DateTime<Utc>) -> Value {
+        match self {
+            WebhookFormat::Generic => json!({
+                "source": "ccost",
+                "key": alert.key,
+                "kind": alert.kind,
+                "severity": alert.severity,
+                "title": alert.title,
+                "message": alert.message,
+                "detected_at": detected_at.to_rfc3339(),
+                "details": alert.details,
+            }),
+            WebhookFormat::Slack => {
+                let icon = match alert.severity {
+                    Severity::Warning => ":warning:",
+                    Severity::Critical => ":rotating_light:",
+                };
+                json!({"text": format!("{icon} *{}*\n{}", alert.title, alert.message)})
+            }
+        }
+    }
+}
+
+/// POST `payload` to `url`, retrying connection failures, 429 and 5xx responses
+///
+/// Waits 1s, 2s, 4s... between attempts. Other 4xx responses fail at once,
+/// since repeating the same request cannot fix them.
+pub async fn post_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &Value,
+    max_retries: u32,
+) -> Result<()> {
+    let mut attempt = 0;
+    loop {
+        let error = match client.post(url).json(payload).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                let error = anyhow::anyhow!("Webhook returned {}: {}", status, body.trim());
+                if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS) {
+                    return Err(error);
+                }
+                error
+            }
+            Err(e) => anyhow::Error::new(e).context("Failed to reach webhook"),
+        };
+
+        if attempt >= max_retries {
+            return Err(error).with_context(|| format!("Giving up after {} attempts", attempt + 1));
+        }
+        tokio::time::sleep(Duration::from_millis(RETRY_BASE_DELAY_MS << attempt)).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::alerts::AlertKind;
+    use axum::{Router, extract::State, http::StatusCode, routing::post};
+    use chrono::TimeZone;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serve `statuses` in order (repeating the last), returning the URL and attempt counter
+    async fn webhook_server(statuses: Vec<StatusCode>) -> (String, Arc<AtomicUsize>) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let app =
+            Router::new()
+                .route(
+                    "/hook",
+                    post(
+                        |State((statuses, attempts)): State<(
+                            Vec<StatusCode>,
+                            Arc<AtomicUsize>,
+                        )>| async move {
+                            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                            statuses[attempt.min(statuses.len() - 1)]
+                        },
+                    ),
+                )
+                .with_state((statuses, attempts.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, attempts)
+    }
+
+    #[tokio::test]
+    async fn test_post_with_retry() {
+        let client = reqwest::Client::new();
+        let payload = json!({"text": "spike"});
+
+        // A server error is retried until it succeeds
+        let (url, attempts) =
+            webhook_server(vec![StatusCode::INTERNAL_SERVER_ERROR, StatusCode::OK]).await;
+        post_with_retry(&client, &url, &payload, 3).await.unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        // Other client errors fail on the first attempt
+        let (url, attempts) = webhook_server(vec![StatusCode::BAD_REQUEST]).await;
+        let error = post_with_retry(&client, &url, &payload, 3)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Webhook returned 400"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // Rate limiting is retried, up to max_retries
+        let (url, attempts) = webhook_server(vec![StatusCode::TOO_MANY_REQUESTS]).await;
+        let error = post_with_retry(&client, &url, &payload, 1)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Giving up after 2 attempts");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_webhook_payloads() {
+        let alert = Alert {
+            key: "budget:team:2025-06-01T00:00:00+00:00:exceeded".to_string(),
+            kind: AlertKind::Budget,
+            severity: Severity::Critical,
+            title: "Budget 'team' exceeded".to_string(),
+            message: "$120.00 of $100.00 spent this month (120.0%)".to_string(),
+            details: json!({"name": "team"}),
+        };
+        let now = Utc.with_ymd_and_hms(2025, 6, 20, 8, 0, 0).unwrap();
+
+        let generic = WebhookFormat::Generic.payload(&alert, now);
+        assert_eq!(generic["kind"], "budget");
+        assert_eq!(generic["severity"], "critical");
+        assert_eq!(generic["detected_at"], "2025-06-20T08:00:00+00:00");
+        assert_eq!(generic["details"]["name"], "team");
+
+        let slack = WebhookFormat::Slack.payload(&alert, now);
+        assert_eq!(
+            slack["text"],
+            ":rotating_light: *Budget 'team' exceeded*\n$120.00 of $100.00 spent this month (120.0%)"
+        );
+        assert!(WebhookFormat::from_config_str("teams").is_err());
+    }
+}
@@ -195,6 +195,7 @@ mod tests {
             cost_usd: Some(0.001),
             cwd: None,
             original_cwd: None,
+            git_branch: None,
         }
     }
 
@@ -224,6 +225,7 @@ mod tests {
             cost_usd: Some(0.001),
             cwd: None,
             original_cwd: None,
+            git_branch: None,
         }
     }
 
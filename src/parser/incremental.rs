@@ -0,0 +1,247 @@
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::deduplication::DeduplicationEngine;
+use super::jsonl::{JsonlParser, UsageData};
+use crate::utils::{EnhancedUsageData, maybe_hide_project_name};
+
+/// Read position and unique messages of one JSONL file
+struct TrackedFile {
+    path: PathBuf,
+    offset: u64,
+    lines: usize,
+    project_name: Option<String>, // From the first message with a working directory
+    messages: Vec<UsageData>,
+}
+
+/// Counts from one refresh; message counts only cover newly read lines
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RefreshStats {
+    pub files_found: usize,
+    pub files_processed: usize,
+    pub total_messages: usize,
+    pub unique_messages: usize,
+}
+
+/// JSONL loader that only parses lines appended since the previous refresh
+///
+/// Deduplication state is kept across refreshes, so a message seen in an
+/// earlier refresh is still recognized when it shows up again in another
+/// branch. If a file shrinks or disappears, everything is re-read from scratch.
+pub struct IncrementalParser {
+    parser: JsonlParser,
+    dedup_engine: DeduplicationEngine,
+    files: Vec<TrackedFile>,
+    index: HashMap<PathBuf, usize>,
+}
+
+impl IncrementalParser {
+    pub fn new(parser: JsonlParser) -> Self {
+        Self {
+            parser,
+            dedup_engine: DeduplicationEngine::new(),
+            files: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Parse new lines from every JSONL file
+    ///
+    /// Files from projects other than `project` are left where they were, so a
+    /// later refresh with a different filter still reads them from the start.
+    pub fn refresh(
+        &mut self,
+        project: Option<&str>,
+        verbose: bool,
+        json_output: bool,
+    ) -> Result<RefreshStats> {
+        let paths = self
+            .parser
+            .find_jsonl_files()
+            .map_err(|e| anyhow!("Failed to find JSONL files: {}", e))?;
+
+        // Truncated or removed files invalidate what was already counted
+        let rewritten = self.files.iter().any(|file| {
+            std::fs::metadata(&file.path).map_or(true, |metadata| metadata.len() < file.offset)
+        });
+        if rewritten {
+            self.dedup_engine = DeduplicationEngine::new();
+            self.files.clear();
+            self.index.clear();
+        }
+
+        let mut stats = RefreshStats {
+            files_found: paths.len(),
+            ..Default::default()
+        };
+        for path in paths {
+            let index = *self.index.entry(path.clone()).or_insert_with(|| {
+                self.files.push(TrackedFile {
+                    path: path.clone(),
+                    offset: 0,
+                    lines: 0,
+                    project_name: None,
+                    messages: Vec::new(),
+                });
+                self.files.len() - 1
+            });
+            let file = &mut self.files[index];
+
+            let appended =
+                match self
+                    .parser
+                    .parse_file_from(&path, file.offset, file.lines, verbose)
+                {
+                    Ok(appended) => appended,
+                    Err(e) => {
+                        if verbose {
+                            warn(
+                                &format!("Failed to parse file {}: {}", path.display(), e),
+                                json_output,
+                            );
+                        }
+                        continue;
+                    }
+                };
+            // Same naming rule as a full parse: the first message with a working directory wins
+            if file.project_name.is_none()
+                && appended
+                    .messages
+                    .iter()
+                    .any(|m| m.cwd.is_some() || m.original_cwd.is_some())
+            {
+                file.project_name = Some(
+                    self.parser
+                        .get_unified_project_name(&path, &appended.messages),
+                );
+            }
+            let raw_project_name = raw_project_name(&self.parser, file);
+            if let Some(filter_project) = project
+                && raw_project_name != filter_project
+            {
+                continue;
+            }
+            file.offset = appended.offset;
+            file.lines += appended.lines;
+
+            stats.total_messages += appended.messages.len();
+            match self
+                .dedup_engine
+                .filter_duplicates(appended.messages, &raw_project_name)
+            {
+                Ok(unique_data) => {
+                    stats.unique_messages += unique_data.len();
+                    file.messages.extend(unique_data);
+                }
+                Err(e) => {
+                    if verbose {
+                        warn(
+                            &format!("Failed to deduplicate file {}: {}", path.display(), e),
+                            json_output,
+                        );
+                    }
+                    continue;
+                }
+            }
+            stats.files_processed += 1;
+        }
+
+        Ok(stats)
+    }
+
+    /// All unique messages read so far, tagged with their project
+    pub fn usage_data(&self, hidden: bool) -> Vec<EnhancedUsageData> {
+        let mut usage_data = Vec::new();
+        for file in &self.files {
+            let project_name =
+                maybe_hide_project_name(&raw_project_name(&self.parser, file), hidden);
+            usage_data.extend(file.messages.iter().map(|data| EnhancedUsageData {
+                usage_data: data.clone(),
+                project_name: project_name.clone(),
+            }));
+        }
+        usage_data
+    }
+}
+
+fn raw_project_name(parser: &JsonlParser, file: &TrackedFile) -> String {
+    match &file.project_name {
+        Some(name) => name.clone(),
+        None => parser.get_unified_project_name(&file.path, &[]),
+    }
+}
+
+fn warn(message: &str, json_output: bool) {
+    if json_output {
+        eprintln!(r#"{{"status": "warning", "message": "{}"}}"#, message);
+    } else {
+        eprintln!("Warning: {}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn line(id: &str, request: &str) -> String {
+        format!(
+            r#"{{"timestamp":"2025-06-09T10:30:00Z","requestId":"{request}","cwd":"/home/user/alpha","message":{{"id":"{id}","content":"hi","model":"claude-sonnet-4","usage":{{"input_tokens":10,"output_tokens":20}}}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_refresh_reads_appended_lines_and_keeps_dedup_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().to_path_buf();
+        fs::create_dir_all(base_dir.join("-home-user-alpha")).unwrap();
+        let main = base_dir.join("-home-user-alpha").join("main.jsonl");
+        let branch = base_dir.join("-home-user-alpha").join("branch.jsonl");
+        fs::write(&main, format!("{}\n", line("msg_1", "req_1"))).unwrap();
+
+        let mut incremental = IncrementalParser::new(JsonlParser::new(base_dir));
+        let stats = incremental.refresh(None, false, false).unwrap();
+        assert_eq!(stats.unique_messages, 1);
+        assert_eq!(incremental.usage_data(false)[0].project_name, "alpha");
+
+        // A new message plus a branch that repeats the first one
+        let mut file = fs::OpenOptions::new().append(true).open(&main).unwrap();
+        writeln!(file, "{}", line("msg_2", "req_2")).unwrap();
+        fs::write(&branch, format!("{}\n", line("msg_1", "req_1"))).unwrap();
+        let stats = incremental.refresh(None, false, false).unwrap();
+        assert_eq!(stats.total_messages, 2);
+        assert_eq!(stats.unique_messages, 1);
+        assert_eq!(incremental.usage_data(false).len(), 2);
+
+        // Nothing new means nothing parsed
+        let stats = incremental.refresh(None, false, false).unwrap();
+        assert_eq!(stats.total_messages, 0);
+
+        // A rewritten file starts everything over
+        fs::write(&main, format!("{}\n", line("msg_3", "req_3"))).unwrap();
+        incremental.refresh(None, false, false).unwrap();
+        assert_eq!(incremental.usage_data(false).len(), 2);
+    }
+
+    #[test]
+    fn test_refresh_leaves_filtered_out_files_unread() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().to_path_buf();
+        fs::create_dir_all(base_dir.join("-home-user-alpha")).unwrap();
+        let main = base_dir.join("-home-user-alpha").join("main.jsonl");
+        fs::write(&main, format!("{}\n", line("msg_1", "req_1"))).unwrap();
+
+        let mut incremental = IncrementalParser::new(JsonlParser::new(base_dir));
+        let stats = incremental.refresh(Some("beta"), false, false).unwrap();
+        assert_eq!(stats.files_processed, 0);
+        assert!(incremental.usage_data(false).is_empty());
+
+        // Without the filter, the skipped lines are read after all
+        let stats = incremental.refresh(None, false, false).unwrap();
+        assert_eq!(stats.unique_messages, 1);
+        assert_eq!(incremental.usage_data(false)[0].project_name, "alpha");
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
@@ -92,6 +92,8 @@ pub struct UsageData {
     pub cwd: Option<String>,
     #[serde(rename = "originalCwd")]
     pub original_cwd: Option<String>,
+    #[serde(rename = "gitBranch")]
+    pub git_branch: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +101,14 @@ pub struct ParsedConversation {
     pub messages: Vec<UsageData>,
 }
 
+/// Lines appended to a file since a previous read
+#[derive(Debug, Clone)]
+pub struct AppendedLines {
+    pub messages: Vec<UsageData>,
+    pub offset: u64,  // Byte offset just past the last consumed line
+    pub lines: usize, // Lines consumed, including skipped ones
+}
+
 #[derive(Clone)]
 pub struct JsonlParser {
     base_dir: PathBuf,
@@ -190,6 +200,71 @@ impl JsonlParser {
         Ok(ParsedConversation { messages })
     }
 
+    /// Parse the lines written after `offset`
+    ///
+    /// A trailing line without a newline is only consumed if it already parses;
+    /// otherwise it is left for the next read, since the writer may still be
+    /// appending to it. `first_line` is only used to number lines in warnings.
+    pub fn parse_file_from(
+        &self,
+        file_path: &Path,
+        offset: u64,
+        first_line: usize,
+        verbose: bool,
+    ) -> Result<AppendedLines> {
+        let mut file = File::open(file_path)
+            .map_err(|e| anyhow!("Failed to open file {}: {}", file_path.display(), e))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| anyhow!("Failed to seek in {}: {}", file_path.display(), e))?;
+
+        let mut reader = BufReader::new(file);
+        let mut appended = AppendedLines {
+            messages: Vec::new(),
+            offset,
+            lines: 0,
+        };
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            let read = reader
+                .read_until(b'\n', &mut buffer)
+                .map_err(|e| anyhow!("Failed to read {}: {}", file_path.display(), e))?;
+            if read == 0 {
+                break;
+            }
+
+            let line_num = first_line + appended.lines + 1;
+            let line = String::from_utf8_lossy(&buffer);
+            let parsed = if line.trim().is_empty() {
+                Ok(None)
+            } else {
+                self.parse_line(&line, line_num, file_path)
+            };
+            if buffer.last() != Some(&b'\n') && parsed.is_err() {
+                break;
+            }
+            appended.offset += read as u64;
+            appended.lines += 1;
+
+            match parsed {
+                Ok(Some(usage_data)) => appended.messages.push(usage_data),
+                Ok(None) => {}
+                Err(e) => {
+                    if verbose {
+                        eprintln!(
+                            "Warning: Skipping malformed JSON at {}:{}: {}",
+                            file_path.display(),
+                            line_num,
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(appended)
+    }
+
     /// Parse a single line of JSONL
     fn parse_line(
         &self,
@@ -509,6 +584,42 @@ mod tests {
         assert_eq!(first_msg.request_id, Some("req-1".to_string()));
     }
 
+    #[test]
+    fn test_parse_file_from_leaves_partial_line_for_next_read() {
+        let (_temp_dir, base_dir) = setup_test_environment();
+        let parser = JsonlParser::new(base_dir.clone());
+        let file_path = base_dir.join("project1").join("conversation.jsonl");
+        let lines = create_test_jsonl_content();
+
+        // Two complete lines and the start of a third that is still being written
+        let (head, tail) = lines[2].split_at(20);
+        fs::write(&file_path, format!("{}\n{}\n{}", lines[0], lines[1], head)).unwrap();
+        let first = parser.parse_file_from(&file_path, 0, 0, false).unwrap();
+        assert_eq!(first.messages.len(), 2);
+        assert_eq!(first.lines, 2);
+        assert_eq!(first.offset as usize, lines[0].len() + lines[1].len() + 2);
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&file_path)
+            .unwrap();
+        use std::io::Write;
+        write!(file, "{}\n{}\n{}\n", tail, lines[3], lines[7]).unwrap();
+        let second = parser
+            .parse_file_from(&file_path, first.offset, first.lines, false)
+            .unwrap();
+        assert_eq!(second.messages.len(), 3);
+        assert_eq!(second.messages[0].request_id, Some("req-3".to_string()));
+        assert_eq!(second.lines, 3);
+
+        // A final line that already parses is read even without its newline
+        write!(file, "{}", lines[0]).unwrap();
+        let third = parser
+            .parse_file_from(&file_path, second.offset, 5, false)
+            .unwrap();
+        assert_eq!(third.messages.len(), 1);
+    }
+
     #[test]
     fn test_find_jsonl_files() {
         let (_temp_dir, base_dir) = setup_test_environment();
@@ -740,6 +851,28 @@ mod tests {
         assert_eq!(message.content, Some("First part Second part".to_string()));
     }
 
+    #[test]
+    fn test_claude_code_line_with_git_branch() {
+        let parser = JsonlParser::new(PathBuf::from("/test"));
+        let test_path = Path::new("/test/file.jsonl");
+
+        // An assistant line as Claude Code writes it
+        let line = r#"{"parentUuid":"7d0c1d9e-3f1b-4c55-9a70-2b1f0c6a8e11","isSidechain":false,"userType":"external","cwd":"/home/user/projects/app","sessionId":"4f6b2a10-8c3d-4e2f-b1a7-9d5e6f708192","version":"1.0.35","gitBranch":"feature/ABC-123","message":{"id":"msg_01XkZ9","type":"message","role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"text","text":"Done."}],"stop_reason":"end_turn","stop_sequence":null,"usage":{"input_tokens":4,"cache_creation_input_tokens":1024,"cache_read_input_tokens":20480,"output_tokens":256,"service_tier":"standard"}},"requestId":"req_011CQ","type":"assistant","uuid":"a1b2c3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d","timestamp":"2025-06-20T09:15:42.123Z"}"#;
+
+        let usage_data = parser.parse_line(line, 1, test_path).unwrap().unwrap();
+        assert_eq!(usage_data.git_branch.as_deref(), Some("feature/ABC-123"));
+        assert_eq!(usage_data.cwd.as_deref(), Some("/home/user/projects/app"));
+        assert_eq!(usage_data.request_id.as_deref(), Some("req_011CQ"));
+        let usage = usage_data.usage.unwrap();
+        assert_eq!(usage.cache_read_input_tokens, Some(20480));
+        assert_eq!(usage.output_tokens, Some(256));
+
+        // Older transcripts have no gitBranch
+        let line = r#"{"timestamp":"2025-06-09T10:30:00Z","uuid":"test-uuid","message":{"content":"Hi","role":"user"}}"#;
+        let usage_data = parser.parse_line(line, 1, test_path).unwrap().unwrap();
+        assert_eq!(usage_data.git_branch, None);
+    }
+
     #[test]
     fn test_backwards_compatibility_string_content() {
         let parser = JsonlParser::new(PathBuf::from("/test"));
@@ -773,6 +906,7 @@ mod tests {
             cost_usd: None,
             cwd: Some("/home/user/projs/transcribr".to_string()),
             original_cwd: None,
+            git_branch: None,
         }];
 
         let file_path = PathBuf::from(
@@ -792,6 +926,7 @@ mod tests {
             cost_usd: None,
             cwd: None,
             original_cwd: Some("/home/user/.claude".to_string()),
+            git_branch: None,
         }];
 
         let file_path2 =
@@ -810,6 +945,7 @@ mod tests {
             cost_usd: None,
             cwd: None,
             original_cwd: None,
+            git_branch: None,
         }];
 
         let file_path3 =
@@ -828,6 +964,7 @@ mod tests {
             cost_usd: None,
             cwd: Some("/home/user/.config/nvim".to_string()),
             original_cwd: None,
+            git_branch: None,
         }];
 
         let file_path4 =
@@ -852,6 +989,7 @@ mod tests {
             cost_usd: None,
             cwd: Some("/home/user/moneyz/transcribr".to_string()),
             original_cwd: None,
+            git_branch: None,
         }];
 
         // Test file with verbose directory name
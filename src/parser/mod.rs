@@ -1,4 +1,5 @@
 // JSONL parsing module
 pub mod conversation;
 pub mod deduplication;
+pub mod incremental;
 pub mod jsonl;
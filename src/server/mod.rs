@@ -0,0 +1,165 @@
+// Local HTTP JSON API (ccost serve)
+pub mod routes;
+
+use crate::commands::timeframe_utils::TimeframeContext;
+use axum::Router;
+use axum::routing::get;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+/// State shared by every request
+///
+/// Requests are served one at a time: each refreshes the incremental parser,
+/// which only reads lines appended since the previous request.
+pub struct ServerState {
+    pub context: Mutex<TimeframeContext>,
+    pub default_currency: String,
+    pub hidden: bool,
+    pub currency_provenance: bool, // Wrap JSON bodies in the currency envelope
+}
+
+pub fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/usage", get(routes::usage))
+        .route("/projects", get(routes::projects))
+        .route("/daily", get(routes::daily))
+        .route("/models", get(routes::models))
+        .route("/sessions", get(routes::sessions))
+        .route("/metrics", get(routes::metrics))
+        .fallback(routes::not_found)
+        .with_state(state)
+}
+
+/// Serve the API until interrupted
+pub async fn run(listener: TcpListener, state: Arc<ServerState>) -> anyhow::Result<()> {
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Server error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{CostCalculationMode, TimezoneCalculator, UsageTracker};
+    use crate::models::PricingManager;
+    use crate::models::currency::CurrencyConverter;
+    use crate::parser::incremental::IncrementalParser;
+    use crate::parser::jsonl::JsonlParser;
+    use crate::utils::DateFormatter;
+    use chrono::{Duration, Utc};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn context(projects_dir: std::path::PathBuf) -> TimeframeContext {
+        TimeframeContext {
+            pricing_manager: PricingManager::new(),
+            usage_tracker: UsageTracker::new(CostCalculationMode::Auto),
+            incremental_parser: IncrementalParser::new(JsonlParser::new(projects_dir.clone())),
+            date_formatter: DateFormatter::new("yyyy-mm-dd").unwrap(),
+            timezone_calc: TimezoneCalculator::new("UTC", 0).unwrap(),
+            projects_dir,
+            currency_converter: CurrencyConverter::new(),
+            currency_provenance: None,
+            usage_usd: None,
+            forecast: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_serves_json_and_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_dir = temp_dir.path().join("-home-user-alpha");
+        fs::create_dir_all(&project_dir).unwrap();
+        let timestamp = (Utc::now() - Duration::hours(1)).to_rfc3339();
+        let lines: Vec<String> = [("req_1", 0.25), ("req_2", 0.5)]
+            .iter()
+            .map(|(request, cost)| {
+                format!(
+                    r#"{{"timestamp":"{timestamp}","requestId":"{request}","sessionId":"s1","cwd":"/home/user/alpha","message":{{"content":"hi","model":"claude-sonnet-4","usage":{{"input_tokens":10,"output_tokens":20}}}},"costUSD":{cost}}}"#
+                )
+            })
+            .collect();
+        fs::write(project_dir.join("main.jsonl"), lines.join("\n") + "\n").unwrap();
+
+        let state = Arc::new(ServerState {
+            context: Mutex::new(context(temp_dir.path().to_path_buf())),
+            default_currency: "USD".to_string(),
+            hidden: false,
+            currency_provenance: false,
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(run(listener, state));
+
+        let client = reqwest::Client::new();
+        let get = |path: &str| client.get(format!("{base}{path}")).send();
+
+        let response = get("/usage").await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()["content-type"].to_str().unwrap(),
+            "application/json"
+        );
+        let usage: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(usage[0]["project_name"], "alpha");
+        assert_eq!(usage[0]["message_count"], 2);
+        assert_eq!(usage[0]["total_cost_usd"], 0.75);
+
+        let daily: serde_json::Value = get("/daily?days=2").await.unwrap().json().await.unwrap();
+        assert_eq!(daily.as_array().unwrap().len(), 1);
+        // since/until narrow the days window like they do on the other endpoints
+        let tomorrow = (Utc::now() + Duration::days(1)).format("%Y-%m-%d");
+        let daily: serde_json::Value = get(&format!("/daily?days=2&since={tomorrow}"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(daily["status"], "success");
+        assert_eq!(daily["data"], serde_json::json!([]));
+        let two_days_ago = (Utc::now() - Duration::days(2)).format("%Y-%m-%d");
+        let daily: serde_json::Value = get(&format!("/daily?days=3&until={two_days_ago}"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(daily["data"], serde_json::json!([]));
+        let yesterday = (Utc::now() - Duration::days(1)).format("%Y-%m-%d");
+        let daily: serde_json::Value = get(&format!("/daily?days=2&since={yesterday}"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(daily.as_array().unwrap().len(), 1);
+        let models: serde_json::Value = get("/models").await.unwrap().json().await.unwrap();
+        assert_eq!(models[0]["model_name"], "claude-sonnet-4");
+
+        // Filters that match nothing are a success with no data
+        let empty: serde_json::Value = get("/usage?project=missing")
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(empty["status"], "success");
+        assert_eq!(empty["data"], serde_json::json!([]));
+
+        let response = get("/nope").await.unwrap();
+        assert_eq!(response.status(), 404);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "error");
+
+        let response = get("/daily?days=soon").await.unwrap();
+        assert_eq!(response.status(), 400);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "error");
+
+        server.abort();
+    }
+}
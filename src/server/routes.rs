@@ -0,0 +1,452 @@
+// Endpoint handlers; bodies match the corresponding `--json` output
+use crate::analysis::UsageFilter;
+use crate::analysis::anomalies::DEFAULT_THRESHOLD;
+use crate::analysis::conversations::analyze_conversations;
+use crate::analysis::usage::{ProjectUsage, merge_model_usage};
+use crate::commands::daily::{convert_daily_currency, group_usage_by_day, total_daily_cost};
+use crate::commands::timeframe_utils::TimeframeContext;
+use crate::output::OutputFormat;
+use crate::output::metrics::{MetricsFormat, to_metrics};
+use crate::parser::jsonl::UsageData;
+use crate::server::ServerState;
+use crate::utils::{EnhancedUsageData, apply_usage_filters, resolve_filters};
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Query parameters, named after the CLI flags
+#[derive(Debug, Default, Deserialize)]
+pub struct ApiQuery {
+    pub project: Option<String>, // Comma-separated list on /projects
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub model: Option<String>,
+    pub currency: Option<String>,
+    pub days: Option<u32>, // /daily only
+}
+
+const NO_DATA: &str =
+    r#"{"status": "success", "message": "No usage data found matching filters", "data": []}"#;
+const NO_FILES: &str = r#"{"status": "warning", "message": "No JSONL files found", "data": []}"#;
+
+/// Default for /daily, as for `ccost daily`
+const DEFAULT_DAYS: u32 = 7;
+
+fn json(status: StatusCode, body: String) -> Response {
+    (status, [(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+fn error(status: StatusCode, message: impl std::fmt::Display) -> Response {
+    let body = serde_json::json!({"status": "error", "message": message.to_string()});
+    json(status, body.to_string())
+}
+
+fn serialized(result: Result<String, serde_json::Error>) -> Response {
+    match result {
+        Ok(body) => json(StatusCode::OK, body),
+        Err(e) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize results: {e}"),
+        ),
+    }
+}
+
+/// Parse new lines from disk and return every message, or `None` if there are no JSONL files
+fn load(
+    context: &mut TimeframeContext,
+    hidden: bool,
+) -> anyhow::Result<Option<Vec<EnhancedUsageData>>> {
+    // Always load every project so one request's filter never hides lines from the next
+    let stats = context.incremental_parser.refresh(None, false, true)?;
+    if stats.files_found == 0 {
+        return Ok(None);
+    }
+    Ok(Some(context.incremental_parser.usage_data(hidden)))
+}
+
+fn filter_and_load(
+    context: &mut TimeframeContext,
+    query: &ApiQuery,
+    hidden: bool,
+    projects: Option<&[&str]>,
+) -> anyhow::Result<Option<(UsageFilter, Vec<EnhancedUsageData>)>> {
+    // Requests share one converter; start each with no recorded rates
+    context.reset_currency_provenance();
+    let (_, since, until, model) = resolve_filters(
+        None,
+        None,
+        query.since.clone(),
+        query.until.clone(),
+        query.model.clone(),
+        &context.timezone_calc,
+    );
+    let usage_filter = UsageFilter {
+        project_name: query.project.clone(),
+        model_name: model,
+        since,
+        until,
+        branch_name: None,
+    };
+
+    let Some(usage_data) = load(context, hidden)? else {
+        return Ok(None);
+    };
+    // With hidden names, the project filter matches the anonymized names
+    let selected = usage_data
+        .into_iter()
+        .filter(|enhanced| {
+            projects.is_none_or(|names| names.contains(&enhanced.project_name.as_str()))
+        })
+        .collect();
+    Ok(Some((usage_filter, selected)))
+}
+
+fn tuples(usage_data: Vec<EnhancedUsageData>) -> Vec<(UsageData, String)> {
+    usage_data
+        .into_iter()
+        .map(|enhanced| (enhanced.usage_data, enhanced.project_name))
+        .collect()
+}
+
+/// Per-project usage in the target currency, as `ccost --json` computes it
+async fn project_usage(
+    context: &mut TimeframeContext,
+    usage_data: Vec<EnhancedUsageData>,
+    usage_filter: &UsageFilter,
+    target_currency: &str,
+) -> anyhow::Result<Vec<ProjectUsage>> {
+    let (usage, _) = context
+        .calculate_usage_enhanced(tuples(usage_data), usage_filter)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to calculate usage: {}", e))?;
+    let mut filtered_usage = apply_usage_filters(usage, usage_filter);
+    context
+        .convert_currency(&mut filtered_usage, target_currency, false, true)
+        .await?;
+    Ok(filtered_usage)
+}
+
+/// Shared body of /usage and /projects
+async fn usage_response(
+    state: &ServerState,
+    query: &ApiQuery,
+    projects: Option<&[&str]>,
+) -> Response {
+    let target_currency = query.currency.as_deref().unwrap_or(&state.default_currency);
+    let mut context = state.context.lock().await;
+    let (usage_filter, usage_data) =
+        match filter_and_load(&mut context, query, state.hidden, projects) {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) => return json(StatusCode::OK, NO_FILES.to_string()),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        };
+    if usage_data.is_empty() {
+        return json(StatusCode::OK, NO_DATA.to_string());
+    }
+
+    let usage = match project_usage(&mut context, usage_data, &usage_filter, target_currency).await
+    {
+        Ok(usage) => usage,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if usage.is_empty() {
+        return json(StatusCode::OK, NO_DATA.to_string());
+    }
+    serialized(match &context.currency_provenance {
+        Some(currency) => usage.to_json_with_currency(
+            currency,
+            context.usage_usd.as_ref().unwrap_or(&usage),
+            state.currency_provenance,
+        ),
+        None => usage.to_json(),
+    })
+}
+
+/// `GET /usage`: totals per project, like `ccost --json`
+pub async fn usage(
+    State(state): State<Arc<ServerState>>,
+    query: Result<Query<ApiQuery>, QueryRejection>,
+) -> Response {
+    let query = match query {
+        Ok(Query(query)) => query,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.body_text()),
+    };
+    let project = query.project.clone();
+    let projects: Option<Vec<&str>> = project.as_deref().map(|name| vec![name]);
+    usage_response(&state, &query, projects.as_deref()).await
+}
+
+/// `GET /projects`: like `ccost projects --json`; `project` takes a comma-separated list
+pub async fn projects(
+    State(state): State<Arc<ServerState>>,
+    query: Result<Query<ApiQuery>, QueryRejection>,
+) -> Response {
+    let mut query = match query {
+        Ok(Query(query)) => query,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.body_text()),
+    };
+    let project_list = query.project.take();
+    let projects: Option<Vec<&str>> = project_list.as_deref().map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect()
+    });
+    usage_response(&state, &query, projects.as_deref()).await
+}
+
+/// `GET /models`: per-model totals across the selected projects
+pub async fn models(
+    State(state): State<Arc<ServerState>>,
+    query: Result<Query<ApiQuery>, QueryRejection>,
+) -> Response {
+    let query = match query {
+        Ok(Query(query)) => query,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.body_text()),
+    };
+    let target_currency = query.currency.as_deref().unwrap_or(&state.default_currency);
+    let mut context = state.context.lock().await;
+    let project = query.project.clone();
+    let projects: Option<Vec<&str>> = project.as_deref().map(|name| vec![name]);
+    let (usage_filter, usage_data) =
+        match filter_and_load(&mut context, &query, state.hidden, projects.as_deref()) {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) => return json(StatusCode::OK, NO_FILES.to_string()),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        };
+    if usage_data.is_empty() {
+        return json(StatusCode::OK, NO_DATA.to_string());
+    }
+
+    let usage = match project_usage(&mut context, usage_data, &usage_filter, target_currency).await
+    {
+        Ok(usage) => usage,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    let models = merge_model_usage(&usage);
+    if models.is_empty() {
+        return json(StatusCode::OK, NO_DATA.to_string());
+    }
+    let models_usd = context
+        .usage_usd
+        .as_ref()
+        .map(|usage| merge_model_usage(usage));
+    serialized(match &context.currency_provenance {
+        Some(currency) => models.to_json_with_currency(
+            currency,
+            models_usd.as_ref().unwrap_or(&models),
+            state.currency_provenance,
+        ),
+        None => models.to_json(),
+    })
+}
+
+/// `GET /daily`: like `ccost daily --json`; `days` defaults to 7, and `since`/`until` narrow that window
+pub async fn daily(
+    State(state): State<Arc<ServerState>>,
+    query: Result<Query<ApiQuery>, QueryRejection>,
+) -> Response {
+    let query = match query {
+        Ok(Query(query)) => query,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.body_text()),
+    };
+    let target_currency = query.currency.as_deref().unwrap_or(&state.default_currency);
+    let mut context = state.context.lock().await;
+    let project = query.project.clone();
+    let projects: Option<Vec<&str>> = project.as_deref().map(|name| vec![name]);
+    let (usage_filter, mut usage_data) =
+        match filter_and_load(&mut context, &query, state.hidden, projects.as_deref()) {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) => return json(StatusCode::OK, NO_FILES.to_string()),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        };
+    usage_data.retain(|enhanced| {
+        let Some(time) = enhanced
+            .usage_data
+            .timestamp
+            .as_deref()
+            .and_then(|ts| context.usage_tracker.parse_timestamp(ts).ok())
+        else {
+            return true;
+        };
+        usage_filter.since.is_none_or(|since| time >= since)
+            && usage_filter.until.is_none_or(|until| time <= until)
+    });
+    if usage_data.is_empty() {
+        return json(StatusCode::OK, NO_DATA.to_string());
+    }
+
+    let mut daily_usage = match group_usage_by_day(
+        &usage_data,
+        query.days.unwrap_or(DEFAULT_DAYS),
+        query.model.clone(),
+        &context.usage_tracker,
+        &context.pricing_manager,
+        &context.date_formatter,
+        true,
+    ) {
+        Ok(daily_usage) => daily_usage,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    if daily_usage.0.is_empty() {
+        return json(
+            StatusCode::OK,
+            r#"{"status": "success", "message": "No daily usage data found matching filters", "data": []}"#
+                .to_string(),
+        );
+    }
+
+    let daily_usage_usd = daily_usage.clone();
+    let original_total_usd = total_daily_cost(&daily_usage);
+    if target_currency != "USD"
+        && let Err(e) = convert_daily_currency(
+            &context.currency_converter,
+            &mut daily_usage,
+            target_currency,
+            false,
+            true,
+        )
+        .await
+    {
+        return error(StatusCode::INTERNAL_SERVER_ERROR, e);
+    }
+    let currency = context.currency_converter.provenance(
+        target_currency,
+        original_total_usd,
+        total_daily_cost(&daily_usage),
+    );
+    serialized(daily_usage.to_json_with_currency(
+        &currency,
+        &daily_usage_usd,
+        state.currency_provenance,
+    ))
+}
+
+/// `GET /sessions`: one entry per session, most recent first
+pub async fn sessions(
+    State(state): State<Arc<ServerState>>,
+    query: Result<Query<ApiQuery>, QueryRejection>,
+) -> Response {
+    let query = match query {
+        Ok(Query(query)) => query,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.body_text()),
+    };
+    let target_currency = query.currency.as_deref().unwrap_or(&state.default_currency);
+    let mut context = state.context.lock().await;
+    let project = query.project.clone();
+    let projects: Option<Vec<&str>> = project.as_deref().map(|name| vec![name]);
+    let (usage_filter, usage_data) =
+        match filter_and_load(&mut context, &query, state.hidden, projects.as_deref()) {
+            Ok(Some(loaded)) => loaded,
+            Ok(None) => return json(StatusCode::OK, NO_FILES.to_string()),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+        };
+
+    let context = &mut *context;
+    let mut sessions = match analyze_conversations(
+        &context.usage_tracker,
+        &tuples(usage_data),
+        &mut context.pricing_manager,
+        &usage_filter,
+    )
+    .await
+    {
+        Ok(sessions) => sessions,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    sessions.flag_outliers(DEFAULT_THRESHOLD);
+    if sessions.0.is_empty() {
+        return json(StatusCode::OK, NO_DATA.to_string());
+    }
+
+    // Convert each session at its own days' rates; keep USD amounts if conversion fails
+    let sessions_usd = sessions.clone();
+    let original_total_usd: f64 = sessions.0.iter().map(|s| s.total_cost).sum();
+    if target_currency != "USD" {
+        for session in &mut sessions.0 {
+            if session.total_cost <= 0.0 {
+                continue;
+            }
+            if let Ok(converted) = context
+                .currency_converter
+                .convert_daily_from_usd(
+                    session.total_cost,
+                    &session.daily_cost_usd,
+                    target_currency,
+                )
+                .await
+            {
+                let factor = converted / session.total_cost;
+                session.total_cost = converted;
+                session.cost_per_message *= factor;
+                session.cost_per_token *= factor;
+                for model_usage in session.model_usage.values_mut() {
+                    model_usage.cost_usd *= factor;
+                }
+            }
+        }
+    }
+    let currency = context.currency_converter.provenance(
+        target_currency,
+        original_total_usd,
+        sessions.0.iter().map(|s| s.total_cost).sum(),
+    );
+    serialized(sessions.to_json_with_currency(&currency, &sessions_usd, state.currency_provenance))
+}
+
+/// `GET /metrics`: Prometheus counters in USD, or OpenMetrics when the scraper asks for it
+pub async fn metrics(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    query: Result<Query<ApiQuery>, QueryRejection>,
+) -> Response {
+    let mut query = match query {
+        Ok(Query(query)) => query,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.body_text()),
+    };
+    let format = if headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/openmetrics-text"))
+    {
+        MetricsFormat::OpenMetrics
+    } else {
+        MetricsFormat::Prometheus
+    };
+
+    let mut context = state.context.lock().await;
+    let project_list = query.project.take();
+    let projects: Option<Vec<&str>> = project_list.as_deref().map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect()
+    });
+    let windowed = query.since.is_some() || query.until.is_some();
+    let usage = match filter_and_load(&mut context, &query, state.hidden, projects.as_deref()) {
+        Ok(Some((usage_filter, usage_data))) => {
+            match project_usage(&mut context, usage_data, &usage_filter, "USD").await {
+                Ok(usage) => usage,
+                Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+            }
+        }
+        Ok(None) => Vec::new(),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, format.content_type())],
+        to_metrics(&usage, format, windowed),
+    )
+        .into_response()
+}
+
+pub async fn not_found() -> Response {
+    error(
+        StatusCode::NOT_FOUND,
+        "Unknown endpoint; try /usage, /projects, /daily, /models, /sessions or /metrics",
+    )
+}
@@ -0,0 +1,740 @@
+// Dashboard state and keyboard handling
+use crate::models::currency::format_currency;
+use crate::output::locale::format_decimal;
+use crate::output::table::format_number;
+use crate::tui::data::{DashboardQuery, Snapshot};
+use crate::utils::DateFormatter;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cmp::Ordering;
+
+/// Top-level dashboard tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Overview,
+    Projects,
+    Models,
+    Daily,
+    Sessions,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 5] = [
+        Tab::Overview,
+        Tab::Projects,
+        Tab::Models,
+        Tab::Daily,
+        Tab::Sessions,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Tab::Overview => "Overview",
+            Tab::Projects => "Projects",
+            Tab::Models => "Models",
+            Tab::Daily => "Daily",
+            Tab::Sessions => "Sessions",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        Tab::ALL.iter().position(|tab| tab == self).unwrap_or(0)
+    }
+}
+
+/// A table the dashboard can show; the Sessions tab switches to Messages on drill-down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Projects,
+    Models,
+    Daily,
+    Sessions,
+    Messages,
+}
+
+impl View {
+    fn index(&self) -> usize {
+        match self {
+            View::Projects => 0,
+            View::Models => 1,
+            View::Daily => 2,
+            View::Sessions => 3,
+            View::Messages => 4,
+        }
+    }
+
+    /// Column headers, with whether each column is numeric (right-aligned)
+    pub fn columns(&self) -> &'static [(&'static str, bool)] {
+        match self {
+            View::Projects => &[
+                ("Project", false),
+                ("Input Tokens", true),
+                ("Output Tokens", true),
+                ("Cache Creation", true),
+                ("Cache Read", true),
+                ("Messages", true),
+                ("Total Cost", true),
+            ],
+            View::Models => &[
+                ("Model", false),
+                ("Input Tokens", true),
+                ("Output Tokens", true),
+                ("Cache Creation", true),
+                ("Cache Read", true),
+                ("Messages", true),
+                ("Cost", true),
+                ("Share", true),
+            ],
+            View::Daily => &[
+                ("Date", false),
+                ("Input Tokens", true),
+                ("Output Tokens", true),
+                ("Cache Creation", true),
+                ("Cache Read", true),
+                ("Messages", true),
+                ("Projects", true),
+                ("Total Cost", true),
+            ],
+            View::Sessions => &[
+                ("Started", false),
+                ("Project", false),
+                ("Session", false),
+                ("Messages", true),
+                ("Duration", true),
+                ("Cache Hit", true),
+                ("Efficiency", true),
+                ("Cost", true),
+            ],
+            View::Messages => &[
+                ("Time", false),
+                ("Model", false),
+                ("Input Tokens", true),
+                ("Output Tokens", true),
+                ("Cache Creation", true),
+                ("Cache Read", true),
+                ("Cost", true),
+            ],
+        }
+    }
+}
+
+/// Column and direction a table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortState {
+    pub column: usize,
+    pub descending: bool,
+}
+
+enum SortKey {
+    Text(String),
+    Number(f64),
+}
+
+fn compare_keys(a: &SortKey, b: &SortKey) -> Ordering {
+    match (a, b) {
+        (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+        (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+        (SortKey::Number(_), SortKey::Text(_)) => Ordering::Less,
+        (SortKey::Text(_), SortKey::Number(_)) => Ordering::Greater,
+    }
+}
+
+fn sort_by_column<T>(items: &mut [T], sort: SortState, key: impl Fn(&T, usize) -> SortKey) {
+    items.sort_by(|a, b| {
+        let ordering = compare_keys(&key(a, sort.column), &key(b, sort.column));
+        if sort.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Rows of the table currently on screen, already formatted
+pub struct TableView {
+    pub view: View,
+    pub rows: Vec<Vec<String>>,
+    pub sort: SortState,
+    pub selected: usize,
+}
+
+pub struct App {
+    pub tab: Tab,
+    pub snapshot: Snapshot,
+    pub query: DashboardQuery,
+    pub project_names: Vec<String>,
+    pub model_names: Vec<String>,
+    pub focus_project: Option<String>, // Set by drilling into a project
+    pub focus_session: Option<String>, // Set by drilling into a session
+    pub currency: String,
+    pub decimal_places: u8,
+    pub date_formatter: DateFormatter,
+    pub status: Option<String>,
+    pub needs_refresh: bool,
+    pub should_quit: bool,
+    sorts: [SortState; 5],
+    selected: [usize; 5],
+}
+
+impl App {
+    pub fn new(
+        snapshot: Snapshot,
+        query: DashboardQuery,
+        project_names: Vec<String>,
+        model_names: Vec<String>,
+        currency: &str,
+        decimal_places: u8,
+        date_formatter: DateFormatter,
+    ) -> Self {
+        let mut app = App {
+            tab: Tab::Overview,
+            snapshot,
+            query,
+            project_names,
+            model_names,
+            focus_project: None,
+            focus_session: None,
+            currency: currency.to_string(),
+            decimal_places,
+            date_formatter,
+            status: None,
+            needs_refresh: false,
+            should_quit: false,
+            sorts: [
+                SortState {
+                    column: 6,
+                    descending: true,
+                },
+                SortState {
+                    column: 6,
+                    descending: true,
+                },
+                SortState {
+                    column: 0,
+                    descending: true,
+                },
+                SortState {
+                    column: 0,
+                    descending: true,
+                },
+                SortState {
+                    column: 0,
+                    descending: false,
+                },
+            ],
+            selected: [0; 5],
+        };
+        app.sort_all();
+        app
+    }
+
+    /// Replace the data after the query changed, keeping sort orders
+    pub fn set_snapshot(&mut self, snapshot: Snapshot) {
+        self.snapshot = snapshot;
+        self.sort_all();
+        self.selected = [0; 5];
+    }
+
+    /// Table shown on the current tab, if it has one
+    pub fn current_view(&self) -> Option<View> {
+        match self.tab {
+            Tab::Overview => None,
+            Tab::Projects => Some(View::Projects),
+            Tab::Models => Some(View::Models),
+            Tab::Daily => Some(View::Daily),
+            Tab::Sessions if self.focus_session.is_some() => Some(View::Messages),
+            Tab::Sessions => Some(View::Sessions),
+        }
+    }
+
+    pub fn total_cost(&self) -> f64 {
+        self.snapshot
+            .projects
+            .iter()
+            .map(|p| p.total_cost_usd)
+            .sum()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.tab = Tab::ALL[(self.tab.index() + 1) % Tab::ALL.len()];
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.tab = Tab::ALL[(self.tab.index() + Tab::ALL.len() - 1) % Tab::ALL.len()];
+            }
+            KeyCode::Char(c @ '1'..='5') => {
+                self.tab = Tab::ALL[c as usize - '1' as usize];
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX),
+            KeyCode::Char('s') => self.change_sort(|sort, columns| SortState {
+                column: (sort.column + 1) % columns,
+                descending: sort.descending,
+            }),
+            KeyCode::Char('r') => self.change_sort(|sort, _| SortState {
+                column: sort.column,
+                descending: !sort.descending,
+            }),
+            KeyCode::Char('p') => {
+                self.query.project = cycle(&self.project_names, self.query.project.as_ref());
+                self.focus_project = None;
+                self.focus_session = None;
+                self.needs_refresh = true;
+            }
+            KeyCode::Char('m') => {
+                self.query.model = cycle(&self.model_names, self.query.model.as_ref());
+                self.focus_session = None;
+                self.needs_refresh = true;
+            }
+            KeyCode::Char('d') => {
+                self.query.range = self.query.range.next();
+                self.focus_session = None;
+                self.needs_refresh = true;
+            }
+            KeyCode::Enter => self.drill_down(),
+            KeyCode::Esc | KeyCode::Backspace => self.go_back(),
+            _ => {}
+        }
+    }
+
+    fn drill_down(&mut self) {
+        match self.current_view() {
+            Some(View::Projects) => {
+                let Some(project) = self
+                    .snapshot
+                    .projects
+                    .get(self.selected[View::Projects.index()])
+                else {
+                    return;
+                };
+                self.focus_project = Some(project.project_name.clone());
+                self.focus_session = None;
+                self.selected[View::Sessions.index()] = 0;
+                self.tab = Tab::Sessions;
+            }
+            Some(View::Sessions) => {
+                let Some(session) = self
+                    .visible_sessions()
+                    .get(self.selected[View::Sessions.index()])
+                    .map(|session| session.conversation_id.clone())
+                else {
+                    return;
+                };
+                self.focus_session = Some(session);
+                self.selected[View::Messages.index()] = 0;
+            }
+            _ => {}
+        }
+    }
+
+    fn go_back(&mut self) {
+        if self.tab != Tab::Sessions {
+            return;
+        }
+        if self.focus_session.take().is_none() && self.focus_project.take().is_some() {
+            self.tab = Tab::Projects;
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let Some(view) = self.current_view() else {
+            return;
+        };
+        let len = self.row_count(view);
+        let selected = &mut self.selected[view.index()];
+        *selected = if len == 0 {
+            0
+        } else {
+            selected.saturating_add_signed(delta).min(len - 1)
+        };
+    }
+
+    fn change_sort(&mut self, change: impl Fn(SortState, usize) -> SortState) {
+        let Some(view) = self.current_view() else {
+            return;
+        };
+        let sort = &mut self.sorts[view.index()];
+        *sort = change(*sort, view.columns().len());
+        self.sort_view(view);
+    }
+
+    fn sort_all(&mut self) {
+        for view in [
+            View::Projects,
+            View::Models,
+            View::Daily,
+            View::Sessions,
+            View::Messages,
+        ] {
+            self.sort_view(view);
+        }
+    }
+
+    fn sort_view(&mut self, view: View) {
+        let sort = self.sorts[view.index()];
+        let total_cost = self.total_cost();
+        match view {
+            View::Projects => {
+                sort_by_column(
+                    &mut self.snapshot.projects,
+                    sort,
+                    |p, column| match column {
+                        0 => SortKey::Text(p.project_name.clone()),
+                        1 => SortKey::Number(p.total_input_tokens as f64),
+                        2 => SortKey::Number(p.total_output_tokens as f64),
+                        3 => SortKey::Number(p.total_cache_creation_tokens as f64),
+                        4 => SortKey::Number(p.total_cache_read_tokens as f64),
+                        5 => SortKey::Number(p.message_count as f64),
+                        _ => SortKey::Number(p.total_cost_usd),
+                    },
+                )
+            }
+            View::Models => sort_by_column(&mut self.snapshot.models, sort, |m, column| {
+                match column {
+                    0 => SortKey::Text(m.model_name.clone()),
+                    1 => SortKey::Number(m.input_tokens as f64),
+                    2 => SortKey::Number(m.output_tokens as f64),
+                    3 => SortKey::Number(m.cache_creation_tokens as f64),
+                    4 => SortKey::Number(m.cache_read_tokens as f64),
+                    5 => SortKey::Number(m.message_count as f64),
+                    // Share is proportional to cost
+                    _ => SortKey::Number(m.cost_usd / total_cost.max(f64::MIN_POSITIVE)),
+                }
+            }),
+            View::Daily => {
+                sort_by_column(&mut self.snapshot.daily.0, sort, |d, column| match column {
+                    0 => SortKey::Text(
+                        d.usage_date
+                            .map(|date| date.to_string())
+                            .unwrap_or_else(|| d.date.clone()),
+                    ),
+                    1 => SortKey::Number(d.total_input_tokens as f64),
+                    2 => SortKey::Number(d.total_output_tokens as f64),
+                    3 => SortKey::Number(d.total_cache_creation_tokens as f64),
+                    4 => SortKey::Number(d.total_cache_read_tokens as f64),
+                    5 => SortKey::Number(d.message_count as f64),
+                    6 => SortKey::Number(d.projects_count as f64),
+                    _ => SortKey::Number(d.total_cost_usd),
+                })
+            }
+            View::Sessions => {
+                sort_by_column(
+                    &mut self.snapshot.sessions,
+                    sort,
+                    |s, column| match column {
+                        0 => SortKey::Text(s.start_time.to_rfc3339()),
+                        1 => SortKey::Text(s.project_name.clone()),
+                        2 => SortKey::Text(s.conversation_id.clone()),
+                        3 => SortKey::Number(s.message_count as f64),
+                        4 => SortKey::Number(s.duration_minutes),
+                        5 => SortKey::Number(f64::from(s.cache_hit_rate)),
+                        6 => SortKey::Number(f64::from(s.efficiency_score)),
+                        _ => SortKey::Number(s.total_cost),
+                    },
+                )
+            }
+            View::Messages => {
+                sort_by_column(
+                    &mut self.snapshot.messages,
+                    sort,
+                    |m, column| match column {
+                        0 => SortKey::Text(m.time.to_rfc3339()),
+                        1 => SortKey::Text(m.model_name.clone()),
+                        2 => SortKey::Number(m.input_tokens as f64),
+                        3 => SortKey::Number(m.output_tokens as f64),
+                        4 => SortKey::Number(m.cache_creation_tokens as f64),
+                        5 => SortKey::Number(m.cache_read_tokens as f64),
+                        _ => SortKey::Number(m.cost),
+                    },
+                )
+            }
+        }
+    }
+
+    /// Sessions shown on the Sessions tab, narrowed to the drilled-into project
+    pub fn visible_sessions(&self) -> Vec<&crate::analysis::conversations::ConversationInsight> {
+        self.snapshot
+            .sessions
+            .iter()
+            .filter(|session| {
+                self.focus_project
+                    .as_ref()
+                    .is_none_or(|project| session.project_name == *project)
+            })
+            .collect()
+    }
+
+    fn row_count(&self, view: View) -> usize {
+        match view {
+            View::Projects => self.snapshot.projects.len(),
+            View::Models => self.snapshot.models.len(),
+            View::Daily => self.snapshot.daily.0.len(),
+            View::Sessions => self.visible_sessions().len(),
+            View::Messages => self
+                .snapshot
+                .messages
+                .iter()
+                .filter(|m| self.focus_session.as_ref() == Some(&m.session_id))
+                .count(),
+        }
+    }
+
+    fn money(&self, amount: f64) -> String {
+        format_currency(amount, &self.currency, self.decimal_places)
+    }
+
+    /// Formatted rows of the current table
+    pub fn table(&self) -> Option<TableView> {
+        let view = self.current_view()?;
+        let rows: Vec<Vec<String>> = match view {
+            View::Projects => self
+                .snapshot
+                .projects
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.project_name.clone(),
+                        format_number(p.total_input_tokens),
+                        format_number(p.total_output_tokens),
+                        format_number(p.total_cache_creation_tokens),
+                        format_number(p.total_cache_read_tokens),
+                        format_number(p.message_count),
+                        self.money(p.total_cost_usd),
+                    ]
+                })
+                .collect(),
+            View::Models => {
+                let total_cost = self.total_cost();
+                self.snapshot
+                    .models
+                    .iter()
+                    .map(|m| {
+                        let share = if total_cost > 0.0 {
+                            m.cost_usd / total_cost * 100.0
+                        } else {
+                            0.0
+                        };
+                        vec![
+                            m.model_name.clone(),
+                            format_number(m.input_tokens),
+                            format_number(m.output_tokens),
+                            format_number(m.cache_creation_tokens),
+                            format_number(m.cache_read_tokens),
+                            format_number(m.message_count),
+                            self.money(m.cost_usd),
+                            format!("{}%", format_decimal(share, 1)),
+                        ]
+                    })
+                    .collect()
+            }
+            View::Daily => self
+                .snapshot
+                .daily
+                .0
+                .iter()
+                .map(|d| {
+                    vec![
+                        d.date.clone(),
+                        format_number(d.total_input_tokens),
+                        format_number(d.total_output_tokens),
+                        format_number(d.total_cache_creation_tokens),
+                        format_number(d.total_cache_read_tokens),
+                        format_number(d.message_count),
+                        d.projects_count.to_string(),
+                        self.money(d.total_cost_usd),
+                    ]
+                })
+                .collect(),
+            View::Sessions => self
+                .visible_sessions()
+                .into_iter()
+                .map(|s| {
+                    let duration = if s.duration_minutes >= 60.0 {
+                        format!("{}h", format_decimal(s.duration_minutes / 60.0, 1))
+                    } else {
+                        format!("{}m", format_decimal(s.duration_minutes, 1))
+                    };
+                    let cost = match s.outlier_score {
+                        Some(_) => format!("! {}", self.money(s.total_cost)),
+                        None => self.money(s.total_cost),
+                    };
+                    vec![
+                        self.date_formatter
+                            .format_for_table_with_time(&s.start_time),
+                        s.project_name.clone(),
+                        s.conversation_id.chars().take(8).collect(),
+                        format_number(s.message_count),
+                        duration,
+                        format!(
+                            "{}%",
+                            format_decimal(f64::from(s.cache_hit_rate) * 100.0, 1)
+                        ),
+                        format!("{}%", format_decimal(f64::from(s.efficiency_score), 1)),
+                        cost,
+                    ]
+                })
+                .collect(),
+            View::Messages => self
+                .snapshot
+                .messages
+                .iter()
+                .filter(|m| self.focus_session.as_ref() == Some(&m.session_id))
+                .map(|m| {
+                    vec![
+                        self.date_formatter.format_for_table_with_time(&m.time),
+                        m.model_name.clone(),
+                        format_number(m.input_tokens),
+                        format_number(m.output_tokens),
+                        format_number(m.cache_creation_tokens),
+                        format_number(m.cache_read_tokens),
+                        self.money(m.cost),
+                    ]
+                })
+                .collect(),
+        };
+        Some(TableView {
+            view,
+            rows,
+            sort: self.sorts[view.index()],
+            selected: self.selected[view.index()],
+        })
+    }
+
+    /// One-line summary of the active filters and drill-down path
+    pub fn filter_line(&self) -> String {
+        let mut line = format!(
+            "Project: {} · Model: {} · Range: {} · Currency: {}",
+            self.query.project.as_deref().unwrap_or("all"),
+            self.query.model.as_deref().unwrap_or("all"),
+            self.query.range.label(),
+            self.currency
+        );
+        if self.tab == Tab::Sessions {
+            if let Some(project) = &self.focus_project {
+                line.push_str(&format!(" · Viewing: {project}"));
+            }
+            if let Some(session) = &self.focus_session {
+                let short: String = session.chars().take(8).collect();
+                line.push_str(&format!(" › {short}"));
+            }
+        }
+        line
+    }
+}
+
+/// Next value in `all` after `current`, wrapping back to no filter
+fn cycle(all: &[String], current: Option<&String>) -> Option<String> {
+    match current {
+        None => all.first().cloned(),
+        Some(current) => all
+            .iter()
+            .position(|value| value == current)
+            .and_then(|index| all.get(index + 1))
+            .cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::DailyUsageList;
+    use crate::analysis::usage::ProjectUsage;
+    use crate::tui::data::DateRange;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn project(name: &str, cost: f64, messages: u64) -> ProjectUsage {
+        ProjectUsage {
+            project_name: name.to_string(),
+            total_input_tokens: 0,
+            total_output_tokens: 0,
+            total_cache_creation_tokens: 0,
+            total_cache_read_tokens: 0,
+            total_cost_usd: cost,
+            model_usage: HashMap::new(),
+            message_count: messages,
+            pricing_source: None,
+            daily_cost_usd: BTreeMap::new(),
+            branch_model_usage: HashMap::new(),
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_sorting_filters_and_drill_down() {
+        let snapshot = Snapshot {
+            projects: vec![project("alpha", 1.0, 30), project("beta", 5.0, 10)],
+            models: Vec::new(),
+            daily: DailyUsageList(Vec::new()),
+            sessions: Vec::new(),
+            messages: Vec::new(),
+        };
+        let query = DashboardQuery {
+            project: None,
+            model: None,
+            branch: None,
+            range: DateRange::All,
+        };
+        let mut app = App::new(
+            snapshot,
+            query,
+            vec!["alpha".to_string(), "beta".to_string()],
+            Vec::new(),
+            "USD",
+            2,
+            DateFormatter::new("yyyy-mm-dd").unwrap(),
+        );
+
+        // Projects default to most expensive first
+        app.handle_key(key(KeyCode::Char('2')));
+        let names = |app: &App| -> Vec<String> {
+            app.table()
+                .unwrap()
+                .rows
+                .iter()
+                .map(|r| r[0].clone())
+                .collect()
+        };
+        assert_eq!(names(&app), vec!["beta", "alpha"]);
+
+        // Sort by messages (cycling wraps past the cost column)
+        app.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(app.table().unwrap().sort.column, 0);
+        for _ in 0..5 {
+            app.handle_key(key(KeyCode::Char('s')));
+        }
+        assert_eq!(names(&app), vec!["alpha", "beta"]);
+        app.handle_key(key(KeyCode::Char('r')));
+        assert_eq!(names(&app), vec!["beta", "alpha"]);
+
+        // Drill into the selected project, then back out
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.tab, Tab::Sessions);
+        assert_eq!(app.focus_project.as_deref(), Some("alpha"));
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.tab, Tab::Projects);
+        assert_eq!(app.focus_project, None);
+
+        // Filters cycle through each value and back to none
+        app.handle_key(key(KeyCode::Char('p')));
+        assert_eq!(app.query.project.as_deref(), Some("alpha"));
+        assert!(app.needs_refresh);
+        app.handle_key(key(KeyCode::Char('p')));
+        app.handle_key(key(KeyCode::Char('p')));
+        assert_eq!(app.query.project, None);
+        app.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(app.query.range, DateRange::Today);
+    }
+}
@@ -0,0 +1,423 @@
+// Dashboard data: the same aggregations the CLI commands print
+use crate::analysis::conversations::{ConversationInsight, analyze_conversations};
+use crate::analysis::usage::{ModelUsage, ProjectUsage, merge_model_usage};
+use crate::analysis::{DailyUsageList, UsageFilter};
+use crate::commands::daily::group_usage_by_day;
+use crate::commands::timeframe_utils::TimeframeContext;
+use crate::models::currency::CurrencyConverter;
+use crate::utils::{EnhancedUsageData, apply_usage_filters};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Date range presets cycled with `d`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateRange {
+    All,
+    Today,
+    ThisWeek,
+    ThisMonth,
+    Last30Days,
+}
+
+impl DateRange {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateRange::All => "all time",
+            DateRange::Today => "today",
+            DateRange::ThisWeek => "this week",
+            DateRange::ThisMonth => "this month",
+            DateRange::Last30Days => "last 30 days",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            DateRange::All => DateRange::Today,
+            DateRange::Today => DateRange::ThisWeek,
+            DateRange::ThisWeek => DateRange::ThisMonth,
+            DateRange::ThisMonth => DateRange::Last30Days,
+            DateRange::Last30Days => DateRange::All,
+        }
+    }
+}
+
+/// Filters currently applied to the dashboard
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashboardQuery {
+    pub project: Option<String>,
+    pub model: Option<String>,
+    pub branch: Option<String>,
+    pub range: DateRange,
+}
+
+/// One message, for drilling down into a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageRow {
+    pub session_id: String,
+    pub time: DateTime<Utc>,
+    pub model_name: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cost: f64,
+}
+
+/// Everything the dashboard shows for one query, in the target currency
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub projects: Vec<ProjectUsage>,
+    pub models: Vec<ModelUsage>,
+    pub daily: DailyUsageList,
+    pub sessions: Vec<ConversationInsight>,
+    pub messages: Vec<MessageRow>,
+}
+
+/// USD conversion rates per UTC day, fetched once at startup
+///
+/// Applying these reproduces `convert_daily_from_usd` and `convert_from_usd_on`
+/// without re-reading the rate cache for every row.
+struct RateTable {
+    latest: f64,
+    daily: HashMap<NaiveDate, f64>,
+}
+
+impl RateTable {
+    async fn build(
+        converter: &CurrencyConverter,
+        target_currency: &str,
+        dates: &BTreeSet<NaiveDate>,
+    ) -> Result<Self> {
+        let latest = converter.convert_from_usd(1.0, target_currency).await?;
+        let mut daily = HashMap::new();
+        for date in dates {
+            daily.insert(
+                *date,
+                converter
+                    .convert_from_usd_on(1.0, target_currency, *date)
+                    .await?,
+            );
+        }
+        Ok(RateTable { latest, daily })
+    }
+
+    fn identity() -> Self {
+        RateTable {
+            latest: 1.0,
+            daily: HashMap::new(),
+        }
+    }
+
+    fn convert_on(&self, amount: f64, date: Option<NaiveDate>) -> f64 {
+        let rate = date
+            .and_then(|date| self.daily.get(&date))
+            .unwrap_or(&self.latest);
+        amount * rate
+    }
+
+    fn convert_daily(&self, total: f64, daily_usd: &BTreeMap<NaiveDate, f64>) -> f64 {
+        let dated: f64 = daily_usd.values().sum();
+        daily_usd
+            .iter()
+            .map(|(date, amount)| self.convert_on(*amount, Some(*date)))
+            .sum::<f64>()
+            + (total - dated) * self.latest
+    }
+}
+
+/// Parsed usage plus the context needed to re-aggregate it for any query
+pub struct DashboardData {
+    context: TimeframeContext,
+    usage_data: Vec<EnhancedUsageData>,
+    base_since: Option<DateTime<Utc>>,
+    base_until: Option<DateTime<Utc>>,
+    rates: RateTable,
+    pub project_names: Vec<String>,
+    pub model_names: Vec<String>,
+    pub conversion_warning: Option<String>,
+}
+
+impl DashboardData {
+    pub async fn new(
+        context: TimeframeContext,
+        usage_data: Vec<EnhancedUsageData>,
+        base_since: Option<DateTime<Utc>>,
+        base_until: Option<DateTime<Utc>>,
+        target_currency: &str,
+    ) -> Self {
+        let mut project_names = BTreeSet::new();
+        let mut model_names = BTreeSet::new();
+        let mut dates = BTreeSet::new();
+        for enhanced in &usage_data {
+            project_names.insert(enhanced.project_name.clone());
+            if let Some(model) = enhanced
+                .usage_data
+                .message
+                .as_ref()
+                .and_then(|m| m.model.clone())
+            {
+                model_names.insert(model);
+            }
+            if let Some(time) = enhanced
+                .usage_data
+                .timestamp
+                .as_deref()
+                .and_then(|ts| context.usage_tracker.parse_timestamp(ts).ok())
+            {
+                dates.insert(time.date_naive());
+            }
+        }
+
+        // Keep USD amounts if conversion fails, as the CLI does
+        let (rates, conversion_warning) = if target_currency == "USD" {
+            (RateTable::identity(), None)
+        } else {
+            match RateTable::build(&context.currency_converter, target_currency, &dates).await {
+                Ok(rates) => (rates, None),
+                Err(e) => (
+                    RateTable::identity(),
+                    Some(format!("Failed to convert currency: {e}")),
+                ),
+            }
+        };
+
+        DashboardData {
+            context,
+            usage_data,
+            base_since,
+            base_until,
+            rates,
+            project_names: project_names.into_iter().collect(),
+            model_names: model_names.into_iter().collect(),
+            conversion_warning,
+        }
+    }
+
+    /// Date bounds for a preset, narrowed by any --since/--until given on the command line
+    fn bounds(&self, range: DateRange) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let timezone_calc = &self.context.timezone_calc;
+        let preset_since = match range {
+            DateRange::All => None,
+            DateRange::Today => Some(timezone_calc.today_start()),
+            DateRange::ThisWeek => Some(timezone_calc.this_week_start()),
+            DateRange::ThisMonth => Some(timezone_calc.this_month_start()),
+            DateRange::Last30Days => {
+                let today = timezone_calc.local_date(Utc::now());
+                Some(timezone_calc.day_start(today - chrono::Duration::days(29)))
+            }
+        };
+        let since = match (self.base_since, preset_since) {
+            (Some(base), Some(preset)) => Some(base.max(preset)),
+            (base, preset) => base.or(preset),
+        };
+        (since, self.base_until)
+    }
+
+    /// Aggregate usage for a query
+    pub async fn snapshot(&mut self, query: &DashboardQuery) -> Result<Snapshot> {
+        let (since, until) = self.bounds(query.range);
+        let usage_filter = UsageFilter {
+            project_name: query.project.clone(),
+            model_name: query.model.clone(),
+            since,
+            until,
+            branch_name: query.branch.clone(),
+        };
+
+        // Project and branch filters are applied up front, so every panel sees the same messages
+        let selected: Vec<EnhancedUsageData> = self
+            .usage_data
+            .iter()
+            .filter(|enhanced| {
+                query
+                    .project
+                    .as_ref()
+                    .is_none_or(|project| enhanced.project_name == *project)
+                    && usage_filter.matches_branch(&enhanced.usage_data)
+            })
+            .cloned()
+            .collect();
+        let usage_tuples: Vec<(crate::parser::jsonl::UsageData, String)> = selected
+            .iter()
+            .map(|enhanced| (enhanced.usage_data.clone(), enhanced.project_name.clone()))
+            .collect();
+
+        // Projects and models, as in `ccost today` and friends
+        let (project_usage, _) = self
+            .context
+            .calculate_usage_enhanced(usage_tuples.clone(), &usage_filter)
+            .await?;
+        let mut projects = apply_usage_filters(project_usage, &usage_filter);
+        for project in &mut projects {
+            project.total_cost_usd = self
+                .rates
+                .convert_daily(project.total_cost_usd, &project.daily_cost_usd);
+            for model_usage in project.model_usage.values_mut() {
+                model_usage.cost_usd = self
+                    .rates
+                    .convert_daily(model_usage.cost_usd, &model_usage.daily_cost_usd);
+            }
+        }
+        let models = merge_model_usage(&projects);
+
+        // Daily totals, as in `ccost daily`
+        let in_range: Vec<EnhancedUsageData> = selected
+            .into_iter()
+            .filter(|enhanced| {
+                let Some(time) = enhanced
+                    .usage_data
+                    .timestamp
+                    .as_deref()
+                    .and_then(|ts| self.context.usage_tracker.parse_timestamp(ts).ok())
+                else {
+                    return false;
+                };
+                since.is_none_or(|since| time >= since) && until.is_none_or(|until| time <= until)
+            })
+            .collect();
+        let mut daily = group_usage_by_day(
+            &in_range,
+            self.days_spanned(&in_range),
+            query.model.clone(),
+            &self.context.usage_tracker,
+            &self.context.pricing_manager,
+            &self.context.date_formatter,
+            false,
+        )?;
+        for day in &mut daily.0 {
+            day.total_cost_usd = self.rates.convert_on(day.total_cost_usd, day.usage_date);
+        }
+
+        // Sessions, with per-day conversion of each session's cost
+        let mut sessions = analyze_conversations(
+            &self.context.usage_tracker,
+            &usage_tuples,
+            &mut self.context.pricing_manager,
+            &usage_filter,
+        )
+        .await?
+        .0;
+        for session in &mut sessions {
+            if session.total_cost > 0.0 {
+                let converted = self
+                    .rates
+                    .convert_daily(session.total_cost, &session.daily_cost_usd);
+                let factor = converted / session.total_cost;
+                session.total_cost = converted;
+                session.cost_per_message *= factor;
+                session.cost_per_token *= factor;
+                for model_usage in session.model_usage.values_mut() {
+                    model_usage.cost_usd *= factor;
+                }
+            }
+        }
+
+        let messages = self.messages(&usage_tuples, &usage_filter).await?;
+
+        Ok(Snapshot {
+            projects,
+            models,
+            daily,
+            sessions,
+            messages,
+        })
+    }
+
+    /// Number of days back to today that covers every message
+    fn days_spanned(&self, data: &[EnhancedUsageData]) -> u32 {
+        let earliest = data
+            .iter()
+            .filter_map(|enhanced| enhanced.usage_data.timestamp.as_deref())
+            .filter_map(|ts| self.context.usage_tracker.parse_timestamp(ts).ok())
+            .map(|time| time.date_naive())
+            .min();
+        match earliest {
+            Some(date) => (Utc::now().date_naive() - date).num_days().max(0) as u32 + 1,
+            None => 1,
+        }
+    }
+
+    /// Individual messages that belong to a session, priced like the session totals
+    async fn messages(
+        &mut self,
+        data: &[(crate::parser::jsonl::UsageData, String)],
+        filter: &UsageFilter,
+    ) -> Result<Vec<MessageRow>> {
+        let mut rows = Vec::new();
+        for (message, _) in data {
+            let (Some(usage), Some(session_id)) = (&message.usage, &message.session_id) else {
+                continue;
+            };
+            let Some(time) = message
+                .timestamp
+                .as_deref()
+                .and_then(|ts| self.context.usage_tracker.parse_timestamp(ts).ok())
+            else {
+                continue;
+            };
+            if filter.since.is_some_and(|since| time < since)
+                || filter.until.is_some_and(|until| time > until)
+            {
+                continue;
+            }
+            let model_name = message
+                .message
+                .as_ref()
+                .and_then(|m| m.model.clone())
+                .unwrap_or_else(|| "unknown".to_string());
+            if let Some(ref filter_model) = filter.model_name
+                && model_name != *filter_model
+            {
+                continue;
+            }
+
+            let cost = match message.cost_usd {
+                Some(embedded_cost) => embedded_cost,
+                None => {
+                    self.context
+                        .usage_tracker
+                        .calculate_enhanced_cost(
+                            usage,
+                            &model_name,
+                            &mut self.context.pricing_manager,
+                        )
+                        .await?
+                        .0
+                }
+            };
+            rows.push(MessageRow {
+                session_id: session_id.clone(),
+                time,
+                model_name,
+                input_tokens: usage.input_tokens.unwrap_or(0),
+                output_tokens: usage.output_tokens.unwrap_or(0),
+                cache_creation_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                cost: self.rates.convert_on(cost, Some(time.date_naive())),
+            });
+        }
+        rows.sort_by_key(|row| row.time);
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_table_matches_dated_conversion() {
+        let date = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let rates = RateTable {
+            latest: 0.9,
+            daily: HashMap::from([(date, 0.8)]),
+        };
+        let daily_usd = BTreeMap::from([(date, 10.0)]);
+
+        // Dated part at that day's rate, undated remainder at the latest rate
+        assert!((rates.convert_daily(12.0, &daily_usd) - 9.8).abs() < 1e-9);
+        assert!((rates.convert_on(10.0, None) - 9.0).abs() < 1e-9);
+        assert!((rates.convert_daily(10.0, &BTreeMap::new()) - 9.0).abs() < 1e-9);
+    }
+}
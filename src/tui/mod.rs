@@ -0,0 +1,44 @@
+// Interactive terminal dashboard (ccost tui)
+pub mod app;
+pub mod data;
+pub mod ui;
+
+use crate::tui::app::App;
+use crate::tui::data::DashboardData;
+use crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+/// Run the dashboard until the user quits, restoring the terminal afterwards
+pub async fn run(app: &mut App, data: &mut DashboardData) -> anyhow::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, app, data).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    data: &mut DashboardData,
+) -> anyhow::Result<()> {
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        // Resize and other events just trigger a redraw
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+
+        if app.needs_refresh {
+            app.needs_refresh = false;
+            app.status = Some("Loading…".to_string());
+            terminal.draw(|frame| ui::draw(frame, app))?;
+            let snapshot = data.snapshot(&app.query).await?;
+            app.set_snapshot(snapshot);
+            app.status = data.conversion_warning.clone();
+        }
+    }
+    Ok(())
+}
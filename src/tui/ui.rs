@@ -0,0 +1,217 @@
+// Dashboard rendering
+use crate::models::currency::format_currency;
+use crate::output::table::format_number;
+use crate::tui::app::{App, Tab, TableView};
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table, TableState, Tabs};
+
+const HELP: &str = "Tab/←→ switch · ↑↓ move · s sort · r reverse · p project · m model · d dates · Enter drill down · Esc back · q quit";
+
+/// Number of projects and models listed on the overview
+const OVERVIEW_TOP: usize = 5;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [tabs_area, filter_area, main_area, help_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let titles = Tab::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, tab)| format!("{} {}", index + 1, tab.title()));
+    frame.render_widget(
+        Tabs::new(titles)
+            .select(app.tab.index())
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED)),
+        tabs_area,
+    );
+    frame.render_widget(Paragraph::new(app.filter_line()).dim(), filter_area);
+
+    match app.table() {
+        Some(table) => draw_table(frame, main_area, &table),
+        None => draw_overview(frame, main_area, app),
+    }
+
+    let footer = match &app.status {
+        Some(status) => Line::from(status.as_str()).fg(Color::Yellow),
+        None => Line::from(HELP).dim(),
+    };
+    frame.render_widget(Paragraph::new(footer), help_area);
+}
+
+fn draw_table(frame: &mut Frame, area: Rect, table: &TableView) {
+    let columns = table.view.columns();
+    let header = Row::new(columns.iter().enumerate().map(|(index, (title, numeric))| {
+        let marker = match (index == table.sort.column, table.sort.descending) {
+            (true, true) => " ▼",
+            (true, false) => " ▲",
+            (false, _) => "",
+        };
+        aligned(format!("{title}{marker}"), *numeric)
+    }))
+    .style(Style::new().add_modifier(Modifier::BOLD));
+
+    let rows = table.rows.iter().map(|row| {
+        Row::new(
+            row.iter()
+                .zip(columns)
+                .map(|(value, (_, numeric))| aligned(value.clone(), *numeric)),
+        )
+    });
+
+    // Size each column to its widest cell, header included
+    let widths = columns.iter().enumerate().map(|(index, (title, _))| {
+        let widest = table
+            .rows
+            .iter()
+            .map(|row| row[index].chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(title.chars().count() + 2);
+        Constraint::Length(widest as u16)
+    });
+
+    let block = Block::new()
+        .borders(Borders::TOP)
+        .title(match table.rows.len() {
+            1 => " 1 row ".to_string(),
+            count => format!(" {count} rows "),
+        });
+    let mut state = TableState::default().with_selected(Some(table.selected));
+    frame.render_stateful_widget(
+        Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .column_spacing(2)
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+        area,
+        &mut state,
+    );
+}
+
+fn aligned(value: String, numeric: bool) -> Cell<'static> {
+    let alignment = if numeric {
+        Alignment::Right
+    } else {
+        Alignment::Left
+    };
+    Cell::from(Line::from(value).alignment(alignment))
+}
+
+fn draw_overview(frame: &mut Frame, area: Rect, app: &App) {
+    let [summary_area, spark_area, top_area] = Layout::vertical([
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+
+    let snapshot = &app.snapshot;
+    let money = |amount: f64| format_currency(amount, &app.currency, app.decimal_places);
+    let sum = |f: fn(&crate::analysis::usage::ProjectUsage) -> u64| -> u64 {
+        snapshot.projects.iter().map(f).sum()
+    };
+    let label = |text: &'static str| Span::from(text).bold();
+    let summary = vec![
+        Line::from(vec![
+            label("Total cost    "),
+            Span::from(money(app.total_cost())),
+        ]),
+        Line::from(vec![
+            label("Messages      "),
+            Span::from(format_number(sum(|p| p.message_count))),
+            Span::from(format!(
+                " across {} projects and {} sessions",
+                snapshot.projects.len(),
+                snapshot.sessions.len()
+            )),
+        ]),
+        Line::from(vec![
+            label("Tokens        "),
+            Span::from(format!(
+                "{} input · {} output",
+                format_number(sum(|p| p.total_input_tokens)),
+                format_number(sum(|p| p.total_output_tokens))
+            )),
+        ]),
+        Line::from(vec![
+            label("Cache         "),
+            Span::from(format!(
+                "{} created · {} read",
+                format_number(sum(|p| p.total_cache_creation_tokens)),
+                format_number(sum(|p| p.total_cache_read_tokens))
+            )),
+        ]),
+    ];
+    frame.render_widget(
+        Paragraph::new(summary).block(Block::new().borders(Borders::TOP).title(" Summary ")),
+        summary_area,
+    );
+
+    // Sparkline values are integers, so plot hundredths of the currency unit
+    let mut days: Vec<_> = snapshot.daily.0.iter().collect();
+    days.sort_by_key(|day| day.usage_date);
+    let points: Vec<u64> = days
+        .iter()
+        .map(|day| (day.total_cost_usd * 100.0).round().max(0.0) as u64)
+        .collect();
+    let span = match (days.first(), days.last()) {
+        (Some(first), Some(last)) => format!(" Daily cost, {} – {} ", first.date, last.date),
+        _ => " Daily cost ".to_string(),
+    };
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::new().borders(Borders::TOP).title(span))
+            .data(&points)
+            .style(Style::new().fg(Color::Cyan)),
+        spark_area,
+    );
+
+    let [projects_area, models_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .spacing(3)
+            .areas(top_area);
+    let mut projects: Vec<_> = snapshot.projects.iter().collect();
+    projects.sort_by(|a, b| b.total_cost_usd.total_cmp(&a.total_cost_usd));
+    draw_top(
+        frame,
+        projects_area,
+        " Top projects ",
+        projects
+            .iter()
+            .take(OVERVIEW_TOP)
+            .map(|p| (p.project_name.clone(), money(p.total_cost_usd))),
+    );
+    let mut models: Vec<_> = snapshot.models.iter().collect();
+    models.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    draw_top(
+        frame,
+        models_area,
+        " Top models ",
+        models
+            .iter()
+            .take(OVERVIEW_TOP)
+            .map(|m| (m.model_name.clone(), money(m.cost_usd))),
+    );
+}
+
+fn draw_top(
+    frame: &mut Frame,
+    area: Rect,
+    title: &'static str,
+    entries: impl Iterator<Item = (String, String)>,
+) {
+    let rows = entries.map(|(name, cost)| Row::new([aligned(name, false), aligned(cost, true)]));
+    frame.render_widget(
+        Table::new(rows, [Constraint::Fill(1), Constraint::Length(14)])
+            .block(Block::new().borders(Borders::TOP).title(title)),
+        area,
+    );
+}
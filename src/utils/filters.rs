@@ -104,6 +104,9 @@ pub fn print_filter_info(filter: &UsageFilter, json_output: bool, date_formatter
     if let Some(ref model) = filter.model_name {
         println!("  Model: {model}");
     }
+    if let Some(ref branch) = filter.branch_name {
+        println!("  Branch: {branch}");
+    }
     if let Some(ref since) = filter.since {
         println!(
             "  Since: {}",
@@ -126,57 +129,150 @@ pub fn apply_usage_filters(
 ) -> Vec<crate::analysis::usage::ProjectUsage> {
     usage
         .into_iter()
-        .filter(|project| {
+        .map(|mut project| {
             // Project filter already applied during parsing
-            if let Some(ref model_filter) = filter.model_name {
-                // If model filter specified, only include projects that have usage for that model
-                project.model_usage.contains_key(model_filter)
-            } else {
-                true
+            if filter.model_name.is_none() && filter.branch_name.is_none() {
+                return project;
             }
-        })
-        .map(|mut project| {
+
+            // With --branch, start from that branch's share of each model
+            let mut filtered_model_usage = match filter.branch_name {
+                Some(ref branch) => project
+                    .branch_model_usage
+                    .remove(&Some(branch.clone()))
+                    .unwrap_or_default(),
+                None => std::mem::take(&mut project.model_usage),
+            };
             // If model filter is specified, filter model usage within each project
             if let Some(ref model_filter) = filter.model_name {
-                let filtered_model_usage: std::collections::HashMap<
-                    String,
-                    crate::analysis::usage::ModelUsage,
-                > = project
-                    .model_usage
-                    .into_iter()
-                    .filter(|(model_name, _)| model_name == model_filter)
-                    .collect();
-
-                // Recalculate project totals based on filtered models
-                let total_input_tokens =
-                    filtered_model_usage.values().map(|m| m.input_tokens).sum();
-                let total_output_tokens =
-                    filtered_model_usage.values().map(|m| m.output_tokens).sum();
-                let total_cache_creation_tokens = filtered_model_usage
-                    .values()
-                    .map(|m| m.cache_creation_tokens)
-                    .sum();
-                let total_cache_read_tokens = filtered_model_usage
-                    .values()
-                    .map(|m| m.cache_read_tokens)
-                    .sum();
-                let total_cost_usd = filtered_model_usage.values().map(|m| m.cost_usd).sum();
-                let message_count = filtered_model_usage.values().map(|m| m.message_count).sum();
-
-                project.model_usage = filtered_model_usage;
-                project.total_input_tokens = total_input_tokens;
-                project.total_output_tokens = total_output_tokens;
-                project.total_cache_creation_tokens = total_cache_creation_tokens;
-                project.total_cache_read_tokens = total_cache_read_tokens;
-                project.total_cost_usd = total_cost_usd;
-                project.message_count = message_count;
+                filtered_model_usage.retain(|model_name, _| model_name == model_filter);
+            }
+
+            // Recalculate project totals based on filtered models
+            let total_input_tokens = filtered_model_usage.values().map(|m| m.input_tokens).sum();
+            let total_output_tokens = filtered_model_usage.values().map(|m| m.output_tokens).sum();
+            let total_cache_creation_tokens = filtered_model_usage
+                .values()
+                .map(|m| m.cache_creation_tokens)
+                .sum();
+            let total_cache_read_tokens = filtered_model_usage
+                .values()
+                .map(|m| m.cache_read_tokens)
+                .sum();
+            let total_cost_usd = filtered_model_usage.values().map(|m| m.cost_usd).sum();
+            let message_count = filtered_model_usage.values().map(|m| m.message_count).sum();
+            let mut daily_cost_usd = std::collections::BTreeMap::new();
+            for model_usage in filtered_model_usage.values() {
+                for (date, cost) in &model_usage.daily_cost_usd {
+                    *daily_cost_usd.entry(*date).or_insert(0.0) += cost;
+                }
             }
 
+            project.model_usage = filtered_model_usage;
+            project.total_input_tokens = total_input_tokens;
+            project.total_output_tokens = total_output_tokens;
+            project.total_cache_creation_tokens = total_cache_creation_tokens;
+            project.total_cache_read_tokens = total_cache_read_tokens;
+            project.total_cost_usd = total_cost_usd;
+            project.message_count = message_count;
+            project.daily_cost_usd = daily_cost_usd;
             project
         })
         .filter(|project| {
-            // Remove projects with no usage after model filtering
+            // Remove projects with no usage after model and branch filtering
             project.message_count > 0
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{CostCalculationMode, UsageTracker};
+    use crate::models::PricingManager;
+    use crate::parser::jsonl::{Message, Usage, UsageData};
+
+    fn message(model: &str, branch: Option<&str>, cost: f64) -> UsageData {
+        UsageData {
+            timestamp: Some("2025-06-01T10:00:00Z".to_string()),
+            uuid: None,
+            request_id: None,
+            session_id: Some("s1".to_string()),
+            message: Some(Message {
+                model: Some(model.to_string()),
+                ..Message::default()
+            }),
+            usage: Some(Usage {
+                input_tokens: Some(100),
+                output_tokens: Some(10),
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: None,
+            }),
+            cost_usd: Some(cost),
+            cwd: None,
+            original_cwd: None,
+            git_branch: branch.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_apply_usage_filters_by_branch_and_model() {
+        let data = vec![
+            (
+                message("claude-sonnet-4", Some("main"), 1.0),
+                "app".to_string(),
+            ),
+            (
+                message("claude-opus-4", Some("main"), 2.0),
+                "app".to_string(),
+            ),
+            (
+                message("claude-sonnet-4", Some("feature/ABC-1"), 4.0),
+                "app".to_string(),
+            ),
+            (message("claude-sonnet-4", None, 8.0), "scratch".to_string()),
+        ];
+        let tracker = UsageTracker::new(CostCalculationMode::Auto);
+        let pricing_manager = PricingManager::new();
+        let usage = |filter: &UsageFilter| {
+            let projects = tracker
+                .calculate_usage_with_projects_filtered(data.clone(), &pricing_manager, filter)
+                .unwrap();
+            apply_usage_filters(projects, filter)
+        };
+
+        let filter = UsageFilter {
+            branch_name: Some("main".to_string()),
+            ..UsageFilter::default()
+        };
+        let projects = usage(&filter);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].project_name, "app");
+        assert_eq!(projects[0].total_cost_usd, 3.0);
+        assert_eq!(projects[0].message_count, 2);
+        assert_eq!(projects[0].total_input_tokens, 200);
+        assert_eq!(projects[0].model_usage.len(), 2);
+
+        // Both filters together keep one model on one branch
+        let filter = UsageFilter {
+            model_name: Some("claude-sonnet-4".to_string()),
+            branch_name: Some("main".to_string()),
+            ..UsageFilter::default()
+        };
+        let projects = usage(&filter);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].total_cost_usd, 1.0);
+        assert_eq!(projects[0].model_usage["claude-sonnet-4"].message_count, 1);
+
+        let filter = UsageFilter {
+            branch_name: Some("release".to_string()),
+            ..UsageFilter::default()
+        };
+        assert!(usage(&filter).is_empty());
+
+        // Without filters every branch is counted
+        let projects = usage(&UsageFilter::default());
+        let total: f64 = projects.iter().map(|p| p.total_cost_usd).sum();
+        assert_eq!(total, 15.0);
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// End-to-end tests that run the ccost binary against a throwaway home directory
+/// Messages carry an embedded costUSD so no pricing data has to be fetched
+
+fn ccost(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ccost"))
+        .args(args)
+        .env("HOME", home)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to run ccost")
+}
+
+/// A home with one project holding a single message of the given cost, timestamped now
+fn home_with_spend(cost_usd: f64) -> TempDir {
+    let home = TempDir::new().unwrap();
+    let project_dir = home.path().join(".claude/projects/-tmp-app");
+    fs::create_dir_all(&project_dir).unwrap();
+    let message = serde_json::json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "uuid": "u0",
+        "requestId": "r0",
+        "sessionId": "s1",
+        "cwd": "/tmp/app",
+        "costUSD": cost_usd,
+        "message": {
+            "content": "x",
+            "role": "assistant",
+            "model": "claude-sonnet-4-20250514",
+            "usage": {"input_tokens": 100, "output_tokens": 10}
+        }
+    });
+    fs::write(project_dir.join("session.jsonl"), format!("{message}\n")).unwrap();
+    home
+}
+
+fn set_config(home: &Path, key: &str, value: &str) {
+    let output = ccost(home, &["config", "set", key, value]);
+    assert!(output.status.success(), "config set {key} failed");
+}
+
+#[test]
+fn test_budget_status_exit_codes() {
+    let home = home_with_spend(5.0);
+
+    set_config(home.path(), "budgets.monthly.amount", "100");
+    let output = ccost(home.path(), &["budget", "status", "--json"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    // Spend of $5 against a $1 limit is exceeded, which scripts see as exit code 3
+    set_config(home.path(), "budgets.monthly.amount", "1");
+    let output = ccost(home.path(), &["budget", "status", "--json"]);
+    assert_eq!(output.status.code(), Some(3));
+    let statuses: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(statuses[0]["status"], "exceeded");
+    assert_eq!(statuses[0]["spent"], 5.0);
+}
+
+#[test]
+fn test_this_month_json_shape() {
+    let home = home_with_spend(5.0);
+
+    // Same array as the other usage views, so existing scripts keep working
+    let output = ccost(home.path(), &["this-month", "--json"]);
+    assert!(output.status.success());
+    let usage: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(usage[0]["project_name"], "app");
+    assert_eq!(usage[0]["total_cost_usd"], 5.0);
+
+    // The forecast is only reported inside the opt-in envelope
+    let output = ccost(
+        home.path(),
+        &["this-month", "--json", "--currency-provenance"],
+    );
+    assert!(output.status.success());
+    let envelope: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(envelope["currency"]["target"], "USD");
+    assert_eq!(envelope["data"], usage);
+    assert!(envelope.get("data_usd").is_none());
+    assert_eq!(envelope["forecast"]["month_to_date"], 5.0);
+    assert!(envelope["forecast"]["projected_total"].as_f64().unwrap() >= 5.0);
+}